@@ -55,6 +55,8 @@ pub struct AgentDirectory {
     pub instance_name: String,
     /// Optional instance identifier (e.g., "1" from "polecat-1", "alice" from "crew-alice")
     pub instance_id: Option<String>,
+    /// Name of the rig the agent belongs to, if any
+    pub rig: Option<String>,
 }
 
 impl AgentDirectory {
@@ -74,16 +76,79 @@ impl AgentDirectory {
 
         let role = AgentRole::from_name(parts[0]);
         let instance_id = parts.get(1).map(|s| s.to_string());
+        let rig = Self::rig_from_path(&path, ".agents");
 
         Some(AgentDirectory {
             path,
             role,
             instance_name: dir_name,
             instance_id,
+            rig,
         })
     }
+
+    /// Parse a rig member directory such as `<rig>/crew/<name>` or `<rig>/polecats/<name>`
+    ///
+    /// Unlike `.agents/` entries, the directory name is the instance itself and the
+    /// role comes from the containing directory.
+    pub fn from_rig_member(path: PathBuf) -> Option<Self> {
+        let dir_name = path.file_name()?.to_str()?.to_string();
+        let group = path.parent()?.file_name()?.to_str()?;
+
+        let role = match group {
+            "crew" => AgentRole::Crew,
+            "polecats" => AgentRole::Polecat,
+            _ => return None,
+        };
+        let rig = Self::rig_from_path(&path, group);
+
+        Some(AgentDirectory {
+            path,
+            role,
+            instance_name: dir_name.clone(),
+            instance_id: Some(dir_name),
+            rig,
+        })
+    }
+
+    /// The agent's address within the town, as used by `gt`
+    ///
+    /// Examples:
+    /// - `mayor` → `mayor`
+    /// - `frontend/crew/alice` → `frontend/crew/alice`
+    /// - `frontend/.agents/witness-frontend` → `frontend/witness-frontend`
+    pub fn address(&self) -> String {
+        let group = self
+            .path
+            .parent()
+            .and_then(|parent| parent.file_name())
+            .and_then(|name| name.to_str());
+
+        match (&self.rig, group) {
+            (Some(rig), Some(group @ ("crew" | "polecats"))) => {
+                format!("{}/{}/{}", rig, group, self.instance_name)
+            }
+            (Some(rig), _) => format!("{}/{}", rig, self.instance_name),
+            (None, _) => self.instance_name.clone(),
+        }
+    }
+
+    /// Returns the rig name for `<rig>/<group>/<agent>` paths
+    fn rig_from_path(path: &Path, group: &str) -> Option<String> {
+        let parent = path.parent()?;
+        if parent.file_name()?.to_str()? != group {
+            return None;
+        }
+        Some(parent.parent()?.file_name()?.to_str()?.to_string())
+    }
 }
 
+/// Agent directories that live directly in the Gas Town root
+pub const TOWN_AGENTS: &[&str] = &["mayor", "deacon"];
+
+/// Rig subdirectories whose children are agents named by directory
+const RIG_MEMBER_GROUPS: &[&str] = &["crew", "polecats"];
+
 /// Discovers agent directories from known locations
 pub struct AgentDiscovery {
     /// Root directory for Gas Town (e.g., ~/gt/)
@@ -100,7 +165,7 @@ impl AgentDiscovery {
     ///
     /// Scans:
     /// - Standalone agents: `~/.gazetown/agents/`
-    /// - In-rig agents: `<rig>/.agents/`
+    /// - Everything found by `discover_town_agents`
     pub fn discover_agents(&self) -> Vec<AgentDirectory> {
         let mut agents = Vec::new();
 
@@ -109,6 +174,30 @@ impl AgentDiscovery {
             agents.extend(standalone);
         }
 
+        agents.extend(self.discover_town_agents());
+
+        agents
+    }
+
+    /// Discover the agents that live inside the Gas Town root
+    ///
+    /// Scans:
+    /// - Town-level agents: `<root>/mayor`, `<root>/deacon`
+    /// - In-rig agents: `<rig>/.agents/`, `<rig>/crew/`, `<rig>/polecats/`
+    pub fn discover_town_agents(&self) -> Vec<AgentDirectory> {
+        let mut agents = Vec::new();
+
+        if let Some(root) = self.gastown_root.as_ref() {
+            for name in TOWN_AGENTS {
+                let path = root.join(name);
+                if path.is_dir()
+                    && let Some(agent) = AgentDirectory::from_path(path)
+                {
+                    agents.push(agent);
+                }
+            }
+        }
+
         // Discover in-rig agents
         if let Some(rig_agents) = self.discover_rig_agents() {
             agents.extend(rig_agents);
@@ -139,7 +228,7 @@ impl AgentDiscovery {
 
         let mut agents = Vec::new();
 
-        // Scan for rig directories (any subdirectory with .agents folder)
+        // Scan for rig directories (any subdirectory with .agents, crew or polecats folders)
         if let Ok(entries) = std::fs::read_dir(root) {
             for entry in entries.flatten() {
                 if let Ok(metadata) = entry.metadata() {
@@ -148,6 +237,12 @@ impl AgentDiscovery {
                         if agents_dir.exists() {
                             agents.extend(self.scan_agents_directory(&agents_dir));
                         }
+                        for group in RIG_MEMBER_GROUPS {
+                            let group_dir = entry.path().join(group);
+                            if group_dir.exists() {
+                                agents.extend(self.scan_rig_members(&group_dir));
+                            }
+                        }
                     }
                 }
             }
@@ -156,6 +251,26 @@ impl AgentDiscovery {
         Some(agents)
     }
 
+    /// Scan `<rig>/crew/` or `<rig>/polecats/` for member directories
+    fn scan_rig_members(&self, group_dir: &Path) -> Vec<AgentDirectory> {
+        let mut agents = Vec::new();
+
+        if let Ok(entries) = std::fs::read_dir(group_dir) {
+            for entry in entries.flatten() {
+                let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+                if let Ok(metadata) = entry.metadata() {
+                    if metadata.is_dir() && !is_hidden {
+                        if let Some(agent) = AgentDirectory::from_rig_member(entry.path()) {
+                            agents.push(agent);
+                        }
+                    }
+                }
+            }
+        }
+
+        agents
+    }
+
     /// Scan a specific agents directory for agent subdirectories
     fn scan_agents_directory(&self, agents_dir: &Path) -> Vec<AgentDirectory> {
         let mut agents = Vec::new();
@@ -248,6 +363,73 @@ mod tests {
         assert_eq!(agent.instance_id, Some("role".to_string()));
     }
 
+    #[test]
+    fn test_agent_directory_rig_from_agents_dir() {
+        let agent =
+            AgentDirectory::from_path(PathBuf::from("/gt/frontend/.agents/witness-frontend"))
+                .unwrap();
+
+        assert_eq!(agent.rig, Some("frontend".to_string()));
+        assert_eq!(agent.address(), "frontend/witness-frontend");
+
+        let mayor = AgentDirectory::from_path(PathBuf::from("/gt/mayor")).unwrap();
+        assert_eq!(mayor.rig, None);
+        assert_eq!(mayor.address(), "mayor");
+    }
+
+    #[test]
+    fn test_agent_directory_from_rig_member() {
+        let crew =
+            AgentDirectory::from_rig_member(PathBuf::from("/gt/frontend/crew/alice")).unwrap();
+        assert_eq!(crew.role, AgentRole::Crew);
+        assert_eq!(crew.instance_name, "alice");
+        assert_eq!(crew.instance_id, Some("alice".to_string()));
+        assert_eq!(crew.rig, Some("frontend".to_string()));
+        assert_eq!(crew.address(), "frontend/crew/alice");
+
+        let polecat =
+            AgentDirectory::from_rig_member(PathBuf::from("/gt/frontend/polecats/toast")).unwrap();
+        assert_eq!(polecat.role, AgentRole::Polecat);
+        assert_eq!(polecat.address(), "frontend/polecats/toast");
+
+        assert!(
+            AgentDirectory::from_rig_member(PathBuf::from("/gt/frontend/hooks/toast")).is_none()
+        );
+    }
+
+    #[test]
+    fn test_discover_town_agents() {
+        let town = tempfile::TempDir::new().unwrap();
+        for dir in [
+            "mayor",
+            "frontend/crew/alice",
+            "frontend/polecats/toast",
+            "frontend/.agents/witness-frontend",
+            "frontend/.agents/unknown-thing",
+            "notes/crew-like",
+        ] {
+            std::fs::create_dir_all(town.path().join(dir)).unwrap();
+        }
+
+        let discovery = AgentDiscovery::new(Some(town.path().to_path_buf()));
+        let mut addresses: Vec<String> = discovery
+            .discover_town_agents()
+            .iter()
+            .map(|agent| agent.address())
+            .collect();
+        addresses.sort();
+
+        assert_eq!(
+            addresses,
+            vec![
+                "frontend/crew/alice",
+                "frontend/polecats/toast",
+                "frontend/witness-frontend",
+                "mayor",
+            ]
+        );
+    }
+
     #[test]
     fn test_agent_directory_display() {
         assert_eq!(AgentRole::Mayor.to_string(), "Mayor");
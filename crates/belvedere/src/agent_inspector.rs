@@ -0,0 +1,805 @@
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, Hsla, Render, SharedString,
+    Subscription, Task, Window, div, prelude::*, px,
+};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use ui::{ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, Disableable, LabelSize};
+
+use crate::agent_discovery::AgentRole;
+use crate::dashboard_buffer::{AgentStatus, format_elapsed};
+use crate::gt_cli::GtCli;
+use crate::town_model::{TownModel, TownModelEvent};
+
+/// Message sent by the inspector's quick nudge action
+pub const DEFAULT_NUDGE_MESSAGE: &str = "Check your hook and continue with your assignment.";
+
+/// Lines shown when tailing an agent's log
+const LOG_TAIL_LINES: usize = 50;
+
+/// Bytes read from the end of an agent's log, however large the log has grown
+pub(crate) const LOG_TAIL_BYTES: u64 = 256 * 1024;
+
+/// Directory inside an agent's directory holding its session logs
+const AGENT_LOG_DIR: &str = "logs";
+
+/// Everything the inspector shows about one agent
+#[derive(Clone, Debug, PartialEq)]
+pub struct AgentDetails {
+    pub name: String,
+    pub role: AgentRole,
+    pub status: AgentStatus,
+    pub instance_id: Option<String>,
+    pub path: Option<PathBuf>,
+    /// Id and path of the agent's hook
+    pub hook: Option<(String, PathBuf)>,
+    /// Open issues assigned to the agent, as `(convoy, issue)` ids
+    pub assignments: Vec<(String, String)>,
+    /// Total tokens per recorded sample, oldest first
+    pub token_history: Vec<u64>,
+    pub context_fill: Option<f32>,
+    pub last_activity: Option<Instant>,
+    /// Recent error messages, newest first
+    pub recent_errors: Vec<String>,
+    /// Whether a human is attached to the agent's session
+    pub attended: bool,
+    /// When humans took the agent over and for how long, newest first
+    pub interventions: Vec<(Instant, Duration)>,
+}
+
+/// Outcome of the last quick action
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionStatus {
+    Running(String),
+    Succeeded(String),
+    Failed(String),
+}
+
+/// The end of an agent's newest log file
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogTail {
+    pub path: PathBuf,
+    pub lines: Vec<String>,
+}
+
+/// Right dock inspector for the agent selected in the town tree
+pub struct AgentInspector {
+    model: Entity<TownModel>,
+    gt: GtCli,
+    agent: Option<String>,
+    log_tail: Option<Result<LogTail, String>>,
+    action_status: Option<ActionStatus>,
+    action_task: Option<Task<()>>,
+    log_task: Option<Task<()>>,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+pub enum AgentInspectorEvent {
+    /// Attach to the agent's tmux session
+    Attach(String),
+}
+
+impl EventEmitter<AgentInspectorEvent> for AgentInspector {}
+
+impl AgentInspector {
+    pub fn new(model: Entity<TownModel>, gt: GtCli, cx: &mut Context<Self>) -> Self {
+        let subscription = cx.subscribe(&model, |_, _, _: &TownModelEvent, cx| cx.notify());
+
+        Self {
+            model,
+            gt,
+            agent: None,
+            log_tail: None,
+            action_status: None,
+            action_task: None,
+            log_task: None,
+            focus_handle: cx.focus_handle(),
+            _subscription: subscription,
+        }
+    }
+
+    pub fn agent(&self) -> Option<&str> {
+        self.agent.as_deref()
+    }
+
+    /// Inspects `agent`, or shows the empty state for `None`
+    pub fn set_agent(&mut self, agent: Option<String>, cx: &mut Context<Self>) {
+        if self.agent == agent {
+            return;
+        }
+        self.agent = agent;
+        self.log_tail = None;
+        self.action_status = None;
+        self.action_task = None;
+        self.log_task = None;
+        cx.notify();
+    }
+
+    pub fn set_gt_cli(&mut self, gt: GtCli) {
+        self.gt = gt;
+    }
+
+    pub fn action_status(&self) -> Option<&ActionStatus> {
+        self.action_status.as_ref()
+    }
+
+    pub fn log_tail(&self) -> Option<&Result<LogTail, String>> {
+        self.log_tail.as_ref()
+    }
+
+    pub fn details(&self, cx: &App) -> Option<AgentDetails> {
+        let name = self.agent.as_deref()?;
+        let model = self.model.read(cx);
+        let agent = model.agent(name)?;
+        let activity = model.activity(name);
+
+        let assignments = model
+            .convoys_for_agent(name)
+            .flat_map(|convoy| {
+                convoy
+                    .issues
+                    .iter()
+                    .filter(|issue| issue.assignee.as_deref() == Some(name) && !issue.is_closed())
+                    .map(|issue| (convoy.id.clone(), issue.id.clone()))
+            })
+            .collect();
+
+        Some(AgentDetails {
+            name: agent.name.clone(),
+            role: agent.role,
+            status: agent.status.clone(),
+            instance_id: agent.instance_id.clone(),
+            path: agent.path.clone(),
+            hook: model
+                .hook_for_agent(name)
+                .map(|hook| (hook.id(), hook.path.clone())),
+            assignments,
+            token_history: activity
+                .map(|activity| {
+                    activity
+                        .token_history
+                        .iter()
+                        .map(|(_, usage)| usage.input_tokens + usage.output_tokens)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            context_fill: agent.context_fill,
+            last_activity: activity.and_then(|activity| activity.last_activity),
+            recent_errors: activity
+                .map(|activity| {
+                    activity
+                        .recent_errors
+                        .iter()
+                        .rev()
+                        .map(|(_, message)| message.clone())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            attended: model.is_attended(name),
+            interventions: activity
+                .map(|activity| activity.interventions.iter().rev().copied().collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Sends `DEFAULT_NUDGE_MESSAGE` to the agent with `gt nudge`
+    pub fn nudge(&mut self, cx: &mut Context<Self>) {
+        let Some(agent) = self.agent.clone() else {
+            return;
+        };
+
+        let gt = self.gt.clone();
+        let target = agent.clone();
+        let run = cx.background_spawn(async move {
+            gt.run(["nudge", target.as_str(), DEFAULT_NUDGE_MESSAGE])
+        });
+
+        self.action_status = Some(ActionStatus::Running(format!("Nudging {}…", agent)));
+        self.action_task = Some(cx.spawn(async move |this, cx| {
+            let result = run.await;
+            this.update(cx, |this, cx| {
+                this.action_status = Some(match result {
+                    Ok(_) => ActionStatus::Succeeded(format!("Nudged {}", agent)),
+                    Err(err) => ActionStatus::Failed(err.to_string()),
+                });
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    /// The hook worktree if the agent has one, otherwise its own directory
+    pub fn worktree_path(&self, cx: &App) -> Option<PathBuf> {
+        let details = self.details(cx)?;
+        details.hook.map(|(_, path)| path).or(details.path)
+    }
+
+    pub fn open_worktree(&mut self, cx: &mut Context<Self>) {
+        match self.worktree_path(cx) {
+            Some(path) => cx.open_with_system(&path),
+            None => {
+                self.action_status =
+                    Some(ActionStatus::Failed("Agent has no worktree on disk".into()));
+                cx.notify();
+            }
+        }
+    }
+
+    /// Reads the last lines of the agent's newest log file
+    pub fn tail_logs(&mut self, cx: &mut Context<Self>) {
+        let Some(agent_path) = self.details(cx).and_then(|details| details.path) else {
+            self.log_tail = Some(Err("Agent has no directory on disk".into()));
+            cx.notify();
+            return;
+        };
+
+        let read = cx.background_spawn(async move { read_log_tail(&agent_path, LOG_TAIL_LINES) });
+        self.log_task = Some(cx.spawn(async move |this, cx| {
+            let tail = read.await;
+            this.update(cx, |this, cx| {
+                this.log_tail = Some(tail);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn render_details(&self, details: AgentDetails, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let status_colors = cx.theme().status();
+
+        let (status_icon, status_color) = match &details.status {
+            AgentStatus::Active => ("●", status_colors.success),
+            AgentStatus::Idle => ("○", colors.text_muted),
+            AgentStatus::Error(_) => ("✗", status_colors.error),
+        };
+
+        let row = |label: &'static str, value: String| {
+            div()
+                .flex()
+                .gap(px(8.0))
+                .child(
+                    div()
+                        .w(px(72.0))
+                        .flex_shrink_0()
+                        .text_color(colors.text_muted)
+                        .child(label),
+                )
+                .child(div().text_color(colors.text).child(value))
+        };
+
+        let assignment = if details.assignments.is_empty() {
+            "none".to_string()
+        } else {
+            details
+                .assignments
+                .iter()
+                .map(|(convoy, issue)| format!("{} ({})", issue, convoy))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let last_activity = details
+            .last_activity
+            .map(|instant| format!("{} ago", format_elapsed(instant.elapsed())))
+            .unwrap_or_else(|| "unknown".into());
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(6.0))
+            .text_sm()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(6.0))
+                    .child(div().text_color(status_color).child(status_icon))
+                    .child(div().text_color(colors.text).child(details.name.clone())),
+            )
+            .child(row("Role", details.role.to_string()))
+            .child(row(
+                "Instance",
+                details.instance_id.clone().unwrap_or_else(|| "—".into()),
+            ))
+            .child(row(
+                "Path",
+                details
+                    .path
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "—".into()),
+            ))
+            .child(row(
+                "Hook",
+                details
+                    .hook
+                    .as_ref()
+                    .map(|(id, _)| id.clone())
+                    .unwrap_or_else(|| "none".into()),
+            ))
+            .child(row("Assigned", assignment))
+            .child(row("Activity", last_activity))
+            .when(details.attended, |this| {
+                this.child(row("Attended", "a human is attached".into()))
+            })
+            .when_some(details.context_fill, |this, fill| {
+                this.child(row("Context", format!("{}%", (fill * 100.0).round())))
+                    .child(meter(fill, colors.element_background, fill_color(fill, cx)))
+            })
+            .child(row(
+                "Tokens",
+                details
+                    .token_history
+                    .last()
+                    .map(|total| total.to_string())
+                    .unwrap_or_else(|| "—".into()),
+            ))
+            .when(details.token_history.len() > 1, |this| {
+                this.child(sparkline(&details.token_history, colors.text_accent))
+            })
+            .when(!details.recent_errors.is_empty(), |this| {
+                this.child(
+                    div()
+                        .pt(px(6.0))
+                        .text_color(colors.text_muted)
+                        .child("Errors"),
+                )
+                .children(
+                    details.recent_errors.iter().map(|message| {
+                        div().text_color(status_colors.error).child(message.clone())
+                    }),
+                )
+            })
+            .when(!details.interventions.is_empty(), |this| {
+                this.child(
+                    div()
+                        .pt(px(6.0))
+                        .text_color(colors.text_muted)
+                        .child("Interventions"),
+                )
+                .children(details.interventions.iter().map(
+                    |(started, duration)| {
+                        div().text_color(colors.text).child(format!(
+                            "{} ago for {}",
+                            format_elapsed(started.elapsed()),
+                            format_elapsed(*duration)
+                        ))
+                    },
+                ))
+            })
+    }
+
+    fn render_actions(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = matches!(self.action_status, Some(ActionStatus::Running(_)));
+
+        div()
+            .flex()
+            .gap(px(4.0))
+            .pt(px(8.0))
+            .child(
+                Button::new("inspector-nudge", "Nudge")
+                    .style(ButtonStyle::Filled)
+                    .label_size(LabelSize::Small)
+                    .disabled(running)
+                    .on_click(cx.listener(|this, _, _, cx| this.nudge(cx))),
+            )
+            .child(
+                Button::new("inspector-open-worktree", "Open Worktree")
+                    .style(ButtonStyle::Subtle)
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(|this, _, _, cx| this.open_worktree(cx))),
+            )
+            .child(
+                Button::new("inspector-tail-logs", "Tail Logs")
+                    .style(ButtonStyle::Subtle)
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(|this, _, _, cx| this.tail_logs(cx))),
+            )
+            .child(
+                Button::new("inspector-attach", "Attach")
+                    .style(ButtonStyle::Subtle)
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        if let Some(agent) = this.agent.clone() {
+                            cx.emit(AgentInspectorEvent::Attach(agent));
+                        }
+                    })),
+            )
+    }
+
+    fn render_log_tail(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let colors = cx.theme().colors();
+        let tail = self.log_tail.as_ref()?;
+
+        let content = match tail {
+            Ok(tail) if tail.lines.is_empty() => div()
+                .text_color(colors.text_muted)
+                .child(format!("{} is empty", tail.path.display())),
+            Ok(tail) => div()
+                .flex()
+                .flex_col()
+                .font_family("monospace")
+                .text_xs()
+                .text_color(colors.text)
+                .children(tail.lines.iter().map(|line| div().child(line.clone()))),
+            Err(message) => div()
+                .text_color(cx.theme().status().error)
+                .child(message.clone()),
+        };
+
+        Some(
+            div()
+                .mt(px(8.0))
+                .p(px(6.0))
+                .rounded(px(4.0))
+                .bg(colors.editor_background)
+                .child(content),
+        )
+    }
+}
+
+/// Finds the most recently modified `*.log` file in `<agent>/logs/` or `<agent>/`
+pub(crate) fn find_log_file(agent_path: &Path) -> Option<PathBuf> {
+    [agent_path.join(AGENT_LOG_DIR), agent_path.to_path_buf()]
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "log"))
+        .max_by_key(|path| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+}
+
+fn read_log_tail(agent_path: &Path, line_count: usize) -> Result<LogTail, String> {
+    let path = find_log_file(agent_path)
+        .ok_or_else(|| format!("No log files in {}", agent_path.display()))?;
+    let describe = |err: std::io::Error| format!("{}: {}", path.display(), err);
+    let mut file = File::open(&path).map_err(describe)?;
+    let len = file.metadata().map_err(describe)?.len();
+    let start = len.saturating_sub(LOG_TAIL_BYTES);
+    file.seek(SeekFrom::Start(start)).map_err(describe)?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).map_err(describe)?;
+
+    let contents = String::from_utf8_lossy(&bytes);
+    let mut lines: Vec<&str> = contents.lines().collect();
+    // Reading from the middle of the log starts partway through a line
+    if start > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    let first = lines.len().saturating_sub(line_count);
+    Ok(LogTail {
+        lines: lines[first..].iter().map(|line| line.to_string()).collect(),
+        path,
+    })
+}
+
+fn fill_color(fill: f32, cx: &App) -> Hsla {
+    if fill > 0.8 {
+        cx.theme().status().warning
+    } else {
+        cx.theme().status().info
+    }
+}
+
+fn meter(fill: f32, background: Hsla, color: Hsla) -> impl IntoElement {
+    div()
+        .w_full()
+        .h(px(6.0))
+        .rounded(px(3.0))
+        .bg(background)
+        .child(
+            div()
+                .h_full()
+                .w(gpui::relative(fill.clamp(0.0, 1.0)))
+                .rounded(px(3.0))
+                .bg(color),
+        )
+}
+
+/// Bar chart of token totals, scaled to the largest sample
+fn sparkline(samples: &[u64], color: Hsla) -> impl IntoElement {
+    const HEIGHT: f32 = 24.0;
+    let max = samples.iter().copied().max().unwrap_or(0).max(1) as f32;
+
+    div()
+        .flex()
+        .items_end()
+        .gap(px(1.0))
+        .h(px(HEIGHT))
+        .children(samples.iter().map(|sample| {
+            div()
+                .w(px(3.0))
+                .h(px((*sample as f32 / max * HEIGHT).max(1.0)))
+                .bg(color)
+        }))
+}
+
+impl Focusable for AgentInspector {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for AgentInspector {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let container = div()
+            .id("agent-inspector")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .p(px(8.0))
+            .overflow_y_scroll();
+
+        let Some(details) = self.details(cx) else {
+            let message: SharedString = match &self.agent {
+                Some(agent) => format!("{} is no longer part of the town", agent).into(),
+                None => "Select an agent to inspect it".into(),
+            };
+            return container
+                .text_sm()
+                .text_color(colors.text_muted)
+                .child(message);
+        };
+
+        let status = self.action_status.clone().map(|status| {
+            let (message, color) = match status {
+                ActionStatus::Running(message) => (message, colors.text_muted),
+                ActionStatus::Succeeded(message) => (message, cx.theme().status().success),
+                ActionStatus::Failed(message) => (message, cx.theme().status().error),
+            };
+            div().pt(px(4.0)).text_xs().text_color(color).child(message)
+        });
+
+        container
+            .child(self.render_details(details, cx))
+            .child(self.render_actions(cx))
+            .children(status)
+            .children(self.render_log_tail(cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_discovery::AgentDirectory;
+    use crate::dashboard_buffer::{AgentInfo, ConvoyInfo, DashboardData, TokenUsage};
+    use crate::town_model::{ConvoyIssue, Hook, Rig, TownStructure};
+    use collections::HashMap;
+    use gpui::{AppContext as _, TestAppContext};
+    use tempfile::TempDir;
+
+    const TOAST: &str = "frontend/polecats/toast";
+
+    fn snapshot(status: AgentStatus, input_tokens: u64) -> DashboardData {
+        DashboardData {
+            agents: vec![AgentInfo {
+                name: TOAST.into(),
+                status,
+                token_usage: Some(TokenUsage {
+                    input_tokens,
+                    output_tokens: 50,
+                }),
+                context_fill: Some(0.6),
+            }],
+            convoys: vec![ConvoyInfo {
+                id: "hq-cv-1".into(),
+                progress: 0.5,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn test_model(town: &Path, cx: &mut TestAppContext) -> Entity<TownModel> {
+        let structure = TownStructure {
+            rigs: vec![Rig {
+                name: "frontend".into(),
+                path: town.join("frontend"),
+            }],
+            agents: vec![
+                AgentDirectory::from_rig_member(town.join("frontend/polecats/toast")).unwrap(),
+            ],
+            hooks: vec![Hook {
+                name: "toast".into(),
+                rig: "frontend".into(),
+                path: town.join("frontend/hooks/toast"),
+                agent: None,
+            }],
+            convoy_issues: HashMap::from_iter([(
+                "hq-cv-1".to_string(),
+                vec![
+                    ConvoyIssue {
+                        id: "fe-1".into(),
+                        status: "closed".into(),
+                        assignee: Some(TOAST.into()),
+                        ..Default::default()
+                    },
+                    ConvoyIssue {
+                        id: "fe-2".into(),
+                        status: "in_progress".into(),
+                        assignee: Some(TOAST.into()),
+                        ..Default::default()
+                    },
+                ],
+            )]),
+            ..Default::default()
+        };
+
+        cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(structure, cx);
+            model.apply_dashboard_data(snapshot(AgentStatus::Active, 100), cx);
+            model.apply_dashboard_data(snapshot(AgentStatus::Error("stuck".into()), 400), cx);
+            model
+        })
+    }
+
+    #[gpui::test]
+    fn test_inspector_details(cx: &mut TestAppContext) {
+        let town = TempDir::new().unwrap();
+        let model = test_model(town.path(), cx);
+        let inspector = cx.new(|cx| AgentInspector::new(model, GtCli::default(), cx));
+
+        inspector.read_with(cx, |inspector, cx| {
+            assert_eq!(inspector.details(cx), None);
+        });
+        inspector.update(cx, |inspector, cx| {
+            inspector.set_agent(Some(TOAST.into()), cx)
+        });
+
+        inspector.read_with(cx, |inspector, cx| {
+            let details = inspector.details(cx).unwrap();
+            assert_eq!(details.role, AgentRole::Polecat);
+            assert_eq!(details.instance_id.as_deref(), Some("toast"));
+            assert_eq!(
+                details.path.as_deref(),
+                Some(town.path().join("frontend/polecats/toast").as_path())
+            );
+            assert_eq!(
+                details.hook.as_ref().map(|(id, _)| id.as_str()),
+                Some("frontend/toast")
+            );
+            assert_eq!(
+                details.assignments,
+                vec![("hq-cv-1".to_string(), "fe-2".to_string())]
+            );
+            assert_eq!(details.token_history, vec![150, 450]);
+            assert_eq!(details.context_fill, Some(0.6));
+            assert!(details.last_activity.is_some());
+            assert_eq!(details.recent_errors, vec!["stuck".to_string()]);
+            assert!(!details.attended);
+            assert!(details.interventions.is_empty());
+            assert_eq!(
+                inspector.worktree_path(cx),
+                Some(town.path().join("frontend/hooks/toast"))
+            );
+        });
+    }
+
+    #[gpui::test]
+    fn test_tail_logs_reads_newest_log(cx: &mut TestAppContext) {
+        let town = TempDir::new().unwrap();
+        let logs = town.path().join("frontend/polecats/toast/logs");
+        std::fs::create_dir_all(&logs).unwrap();
+        let lines: Vec<String> = (1..=60).map(|line| format!("line {}", line)).collect();
+        std::fs::write(logs.join("session.log"), lines.join("\n")).unwrap();
+
+        let model = test_model(town.path(), cx);
+        let inspector = cx.new(|cx| AgentInspector::new(model, GtCli::default(), cx));
+        inspector.update(cx, |inspector, cx| {
+            inspector.set_agent(Some(TOAST.into()), cx);
+            inspector.tail_logs(cx);
+        });
+        cx.run_until_parked();
+
+        inspector.read_with(cx, |inspector, _| {
+            let tail = inspector.log_tail().unwrap().as_ref().unwrap();
+            assert_eq!(tail.path, logs.join("session.log"));
+            assert_eq!(tail.lines.len(), LOG_TAIL_LINES);
+            assert_eq!(tail.lines.first().unwrap(), "line 11");
+            assert_eq!(tail.lines.last().unwrap(), "line 60");
+        });
+    }
+
+    #[test]
+    fn test_read_log_tail_of_large_log() {
+        let agent = TempDir::new().unwrap();
+        let mut log = vec![b'x'; LOG_TAIL_BYTES as usize];
+        log.extend_from_slice(b"\nbinary \xff\xfe output\nlast line\n");
+        std::fs::write(agent.path().join("session.log"), log).unwrap();
+
+        let tail = read_log_tail(agent.path(), LOG_TAIL_LINES).unwrap();
+        assert_eq!(
+            tail.lines,
+            vec!["binary \u{fffd}\u{fffd} output", "last line"]
+        );
+    }
+
+    #[gpui::test]
+    fn test_tail_logs_without_log_file(cx: &mut TestAppContext) {
+        let town = TempDir::new().unwrap();
+        std::fs::create_dir_all(town.path().join("frontend/polecats/toast")).unwrap();
+
+        let model = test_model(town.path(), cx);
+        let inspector = cx.new(|cx| AgentInspector::new(model, GtCli::default(), cx));
+        inspector.update(cx, |inspector, cx| {
+            inspector.set_agent(Some(TOAST.into()), cx);
+            inspector.tail_logs(cx);
+        });
+        cx.run_until_parked();
+
+        inspector.read_with(cx, |inspector, _| {
+            assert!(matches!(
+                inspector.log_tail(),
+                Some(Err(message)) if message.contains("No log files")
+            ));
+        });
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    fn test_nudge_runs_gt(cx: &mut TestAppContext) {
+        use crate::fake_gt::FakeGt;
+
+        let town = TempDir::new().unwrap();
+        let gt = FakeGt::new().respond(&format!("nudge {} {}", TOAST, DEFAULT_NUDGE_MESSAGE), "");
+        let model = test_model(town.path(), cx);
+        let inspector = cx.new(|cx| AgentInspector::new(model, gt.cli(), cx));
+
+        inspector.update(cx, |inspector, cx| {
+            inspector.set_agent(Some(TOAST.into()), cx);
+            inspector.nudge(cx);
+            assert!(matches!(
+                inspector.action_status(),
+                Some(ActionStatus::Running(_))
+            ));
+        });
+        cx.run_until_parked();
+
+        inspector.read_with(cx, |inspector, _| {
+            assert_eq!(
+                inspector.action_status(),
+                Some(&ActionStatus::Succeeded(format!("Nudged {}", TOAST)))
+            );
+        });
+        assert_eq!(gt.calls().len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    fn test_failed_nudge_is_reported(cx: &mut TestAppContext) {
+        use crate::fake_gt::FakeGt;
+
+        let town = TempDir::new().unwrap();
+        let gt = FakeGt::new().fail(
+            &format!("nudge {} {}", TOAST, DEFAULT_NUDGE_MESSAGE),
+            1,
+            "no tmux session",
+        );
+        let model = test_model(town.path(), cx);
+        let inspector = cx.new(|cx| AgentInspector::new(model, gt.cli(), cx));
+
+        inspector.update(cx, |inspector, cx| {
+            inspector.set_agent(Some(TOAST.into()), cx);
+            inspector.nudge(cx);
+        });
+        cx.run_until_parked();
+
+        inspector.read_with(cx, |inspector, _| {
+            assert!(matches!(
+                inspector.action_status(),
+                Some(ActionStatus::Failed(message)) if message.contains("no tmux session")
+            ));
+        });
+    }
+}
@@ -0,0 +1,317 @@
+use collections::HashMap;
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString, Task,
+    WeakEntity, Window, div, prelude::*, px,
+};
+use terminal::terminal_settings::{AlternateScroll, CursorShape};
+use terminal::{Terminal, TerminalBuilder};
+use terminal_view::TerminalView;
+use ui::ActiveTheme;
+use util::shell::Shell;
+
+use crate::gt_cli::GtCli;
+use crate::town_item::{TownItem, TownItemEvent};
+use crate::town_model::TownModel;
+
+/// Arguments for `tmux` to attach to a session
+pub fn attach_args(session: &str) -> Vec<String> {
+    vec!["attach".into(), "-t".into(), session.into()]
+}
+
+/// Where the attached session is
+pub enum AttachState {
+    Starting,
+    Attached(Entity<Terminal>),
+    /// The tmux client exited, e.g. after detaching with the prefix key and `d`
+    Detached,
+    Failed(String),
+}
+
+/// Center pane item running `tmux attach` on an agent's session, so a human can
+/// take over a stuck agent by hand.
+///
+/// The agent counts as attended once tmux is running. Detaching, or
+/// closing the tab, returns it to unattended mode and records the intervention in
+/// its activity.
+pub struct AgentTerminal {
+    agent: String,
+    session: String,
+    model: Entity<TownModel>,
+    state: AttachState,
+    /// Created on first render, since it needs a window
+    terminal_view: Option<Entity<TerminalView>>,
+    focus_handle: FocusHandle,
+    _attach_task: Task<()>,
+}
+
+impl AgentTerminal {
+    /// Attaches to `session`, the tmux session `agent` runs in. `tmux` checks the
+    /// session exists first, and its binary is the one that attaches.
+    pub fn new(
+        agent: String,
+        session: String,
+        tmux: GtCli,
+        model: Entity<TownModel>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let program = tmux.binary().to_string_lossy().into_owned();
+        let attach_task = cx.spawn({
+            let session = session.clone();
+            async move |this, cx| {
+                let check = {
+                    let session = session.clone();
+                    cx.background_spawn(async move { tmux.run(["has-session", "-t", &session]) })
+                };
+                if let Err(err) = check.await {
+                    this.update(cx, |this, cx| this.fail(err.to_string(), cx))
+                        .ok();
+                    return;
+                }
+
+                let (completion_tx, completion_rx) = smol::channel::unbounded();
+                let Ok(build) = this.update(cx, |_, cx| {
+                    TerminalBuilder::new(
+                        None,
+                        None,
+                        Shell::WithArguments {
+                            program,
+                            args: attach_args(&session),
+                            title_override: Some(session.clone()),
+                        },
+                        HashMap::default(),
+                        CursorShape::default(),
+                        AlternateScroll::On,
+                        None,
+                        Vec::new(),
+                        0,
+                        false,
+                        0,
+                        Some(completion_tx),
+                        cx,
+                        Vec::new(),
+                    )
+                }) else {
+                    return;
+                };
+                let builder = match build.await {
+                    Ok(builder) => builder,
+                    Err(err) => {
+                        this.update(cx, |this, cx| this.fail(err.to_string(), cx))
+                            .ok();
+                        return;
+                    }
+                };
+                if this
+                    .update(cx, |this, cx| {
+                        let terminal = cx.new(|cx| builder.subscribe(cx));
+                        this.state = AttachState::Attached(terminal);
+                        let agent = this.agent.clone();
+                        this.model
+                            .update(cx, |model, cx| model.begin_intervention(&agent, cx));
+                        cx.notify();
+                    })
+                    .is_err()
+                {
+                    return;
+                }
+
+                let status = completion_rx.recv().await.ok().flatten();
+                this.update(cx, |this, cx| match status {
+                    Some(status) if !status.success() => {
+                        // tmux never attached, e.g. the session ended in between
+                        let output = match &this.state {
+                            AttachState::Attached(terminal) => terminal.read(cx).get_content(),
+                            _ => String::new(),
+                        };
+                        let reason = output
+                            .lines()
+                            .rev()
+                            .map(str::trim)
+                            .find(|line| !line.is_empty())
+                            .map(str::to_string)
+                            .unwrap_or_else(|| format!("tmux exited with {}", status));
+                        let agent = this.agent.clone();
+                        this.model
+                            .update(cx, |model, cx| model.discard_intervention(&agent, cx));
+                        this.fail(reason, cx);
+                    }
+                    _ => {
+                        this.state = AttachState::Detached;
+                        this.terminal_view = None;
+                        this.finish(cx);
+                    }
+                })
+                .ok();
+            }
+        });
+
+        Self {
+            agent,
+            session,
+            model,
+            state: AttachState::Starting,
+            terminal_view: None,
+            focus_handle: cx.focus_handle(),
+            _attach_task: attach_task,
+        }
+    }
+
+    pub fn agent(&self) -> &str {
+        &self.agent
+    }
+
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    pub fn state(&self) -> &AttachState {
+        &self.state
+    }
+
+    fn fail(&mut self, error: String, cx: &mut Context<Self>) {
+        self.state = AttachState::Failed(error);
+        self.terminal_view = None;
+        cx.emit(TownItemEvent::UpdateTab);
+        cx.notify();
+    }
+
+    /// Ends the intervention, returning the agent to unattended mode. Called when
+    /// the tmux client exits and when the tab is closed.
+    pub fn finish(&mut self, cx: &mut Context<Self>) {
+        let agent = self.agent.clone();
+        self.model
+            .update(cx, |model, cx| model.end_intervention(&agent, cx));
+        cx.emit(TownItemEvent::UpdateTab);
+        cx.notify();
+    }
+
+    fn terminal_view(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<TerminalView>> {
+        let AttachState::Attached(terminal) = &self.state else {
+            return None;
+        };
+        let terminal = terminal.clone();
+        Some(
+            self.terminal_view
+                .get_or_insert_with(|| {
+                    cx.new(|cx| {
+                        TerminalView::new(
+                            terminal,
+                            WeakEntity::new_invalid(),
+                            None,
+                            WeakEntity::new_invalid(),
+                            window,
+                            cx,
+                        )
+                    })
+                })
+                .clone(),
+        )
+    }
+}
+
+impl EventEmitter<TownItemEvent> for AgentTerminal {}
+
+impl Focusable for AgentTerminal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TownItem for AgentTerminal {
+    type Event = TownItemEvent;
+
+    fn tab_content_text(&self, _cx: &App) -> SharedString {
+        match &self.state {
+            AttachState::Detached => format!("{} (detached)", self.agent).into(),
+            _ => format!("Attach {}", self.agent).into(),
+        }
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(format!("tmux attach -t {}", self.session).into())
+    }
+
+    fn to_town_item_events(event: &Self::Event, mut f: impl FnMut(TownItemEvent)) {
+        f(*event);
+    }
+}
+
+impl Render for AgentTerminal {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let terminal_view = self.terminal_view(window, cx);
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+
+        let message = match &self.state {
+            AttachState::Starting => {
+                Some((format!("Attaching to {}…", self.session), colors.text_muted))
+            }
+            AttachState::Attached(_) => None,
+            AttachState::Detached => Some((
+                format!(
+                    "Detached from {}; {} is unattended again",
+                    self.session, self.agent
+                ),
+                colors.text_muted,
+            )),
+            AttachState::Failed(error) => Some((
+                format!("Couldn't attach to {}: {}", self.session, error),
+                status.error,
+            )),
+        };
+
+        div()
+            .id("agent-terminal")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(colors.terminal_background)
+            .when_some(message, |this, (message, color)| {
+                this.child(div().p(px(12.0)).text_sm().text_color(color).child(message))
+            })
+            .children(terminal_view)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::fake_gt::FakeGt;
+    use gpui::{AppContext as _, TestAppContext};
+
+    #[gpui::test]
+    fn test_missing_session_fails_without_intervention(cx: &mut TestAppContext) {
+        let tmux = FakeGt::new().fail(
+            "has-session -t gt-frontend-polecats-toast",
+            1,
+            "can't find session: gt-frontend-polecats-toast",
+        );
+        let model = cx.new(|_| TownModel::new());
+        let terminal = cx.new(|cx| {
+            AgentTerminal::new(
+                "frontend/polecats/toast".into(),
+                "gt-frontend-polecats-toast".into(),
+                tmux.cli(),
+                model.clone(),
+                cx,
+            )
+        });
+        cx.run_until_parked();
+
+        terminal.read_with(cx, |terminal, _| {
+            assert!(matches!(
+                terminal.state(),
+                AttachState::Failed(message) if message.contains("can't find session")
+            ));
+        });
+        model.read_with(cx, |model, _| {
+            assert!(!model.is_attended("frontend/polecats/toast"));
+            assert!(model.activity("frontend/polecats/toast").is_none());
+        });
+    }
+}
@@ -0,0 +1,638 @@
+use collections::HashMap;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::dashboard_buffer::DashboardError;
+
+/// Issue store, relative to a `.beads/` directory
+pub const BEADS_ISSUES_FILE: &str = "issues.jsonl";
+
+/// Errors produced when reading a `.beads/` store
+#[derive(Debug, Clone)]
+pub enum BeadsError {
+    /// The issue store exists but could not be read
+    Io { path: PathBuf, message: String },
+    /// A line of the issue store is not a valid issue
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    /// No issue with this id exists in the issue store
+    UnknownIssue { path: PathBuf, id: String },
+}
+
+impl std::fmt::Display for BeadsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BeadsError::Io { path, message } => write!(f, "{}: {}", path.display(), message),
+            BeadsError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            BeadsError::UnknownIssue { path, id } => {
+                write!(f, "{}: no issue '{}'", path.display(), id)
+            }
+        }
+    }
+}
+
+impl From<BeadsError> for DashboardError {
+    fn from(error: BeadsError) -> Self {
+        match error {
+            BeadsError::Io { .. } | BeadsError::UnknownIssue { .. } => {
+                DashboardError::FetchFailed(error.to_string())
+            }
+            BeadsError::Parse { .. } => DashboardError::ParseError(error.to_string()),
+        }
+    }
+}
+
+/// Workflow state of an issue
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(from = "String")]
+pub enum IssueStatus {
+    #[default]
+    Open,
+    InProgress,
+    Blocked,
+    /// Slung onto an agent's hook but not started yet
+    Hooked,
+    Deferred,
+    Closed,
+    Other(String),
+}
+
+impl IssueStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            IssueStatus::Open => "open",
+            IssueStatus::InProgress => "in_progress",
+            IssueStatus::Blocked => "blocked",
+            IssueStatus::Hooked => "hooked",
+            IssueStatus::Deferred => "deferred",
+            IssueStatus::Closed => "closed",
+            IssueStatus::Other(status) => status,
+        }
+    }
+}
+
+impl From<String> for IssueStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "open" => IssueStatus::Open,
+            "in_progress" => IssueStatus::InProgress,
+            "blocked" => IssueStatus::Blocked,
+            "hooked" => IssueStatus::Hooked,
+            "deferred" => IssueStatus::Deferred,
+            "closed" => IssueStatus::Closed,
+            _ => IssueStatus::Other(status),
+        }
+    }
+}
+
+impl std::fmt::Display for IssueStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Kind of work an issue tracks
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(from = "String")]
+pub enum IssueType {
+    Bug,
+    Feature,
+    #[default]
+    Task,
+    Epic,
+    Chore,
+    /// A batch of issues worked on together, tracking its members as dependencies
+    Convoy,
+    Other(String),
+}
+
+impl IssueType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            IssueType::Bug => "bug",
+            IssueType::Feature => "feature",
+            IssueType::Task => "task",
+            IssueType::Epic => "epic",
+            IssueType::Chore => "chore",
+            IssueType::Convoy => "convoy",
+            IssueType::Other(issue_type) => issue_type,
+        }
+    }
+}
+
+impl From<String> for IssueType {
+    fn from(issue_type: String) -> Self {
+        match issue_type.as_str() {
+            "bug" => IssueType::Bug,
+            "feature" => IssueType::Feature,
+            "task" => IssueType::Task,
+            "epic" => IssueType::Epic,
+            "chore" => IssueType::Chore,
+            "convoy" => IssueType::Convoy,
+            _ => IssueType::Other(issue_type),
+        }
+    }
+}
+
+impl std::fmt::Display for IssueType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How an issue relates to the issue it depends on
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(from = "String")]
+pub enum DependencyType {
+    /// Untyped dependencies block, like `bd dep add` without `--type`
+    #[default]
+    Blocks,
+    Related,
+    ParentChild,
+    DiscoveredFrom,
+    Other(String),
+}
+
+impl DependencyType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DependencyType::Blocks => "blocks",
+            DependencyType::Related => "related",
+            DependencyType::ParentChild => "parent-child",
+            DependencyType::DiscoveredFrom => "discovered-from",
+            DependencyType::Other(dependency_type) => dependency_type,
+        }
+    }
+}
+
+impl From<String> for DependencyType {
+    fn from(dependency_type: String) -> Self {
+        match dependency_type.as_str() {
+            "blocks" => DependencyType::Blocks,
+            "related" => DependencyType::Related,
+            "parent-child" => DependencyType::ParentChild,
+            "discovered-from" => DependencyType::DiscoveredFrom,
+            _ => DependencyType::Other(dependency_type),
+        }
+    }
+}
+
+/// An edge from an issue to an issue it depends on
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct BeadsDependency {
+    pub depends_on_id: String,
+    #[serde(default, rename = "type")]
+    pub dependency_type: DependencyType,
+}
+
+/// A single line of a beads `issues.jsonl` file
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct BeadsIssue {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub status: IssueStatus,
+    #[serde(default)]
+    pub issue_type: IssueType,
+    #[serde(default)]
+    pub priority: Option<u8>,
+    #[serde(default)]
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub dependencies: Vec<BeadsDependency>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub closed_at: Option<String>,
+}
+
+impl BeadsIssue {
+    pub fn is_closed(&self) -> bool {
+        self.status == IssueStatus::Closed
+    }
+
+    /// The title, unless the issue has none
+    pub fn title(&self) -> Option<&str> {
+        (!self.title.is_empty()).then_some(self.title.as_str())
+    }
+
+    /// Ids of the issues that have to close before this one can
+    pub fn blockers(&self) -> impl Iterator<Item = &str> {
+        self.dependencies
+            .iter()
+            .filter(|dependency| dependency.dependency_type == DependencyType::Blocks)
+            .map(|dependency| dependency.depends_on_id.as_str())
+    }
+
+    /// When the issue was slung to its assignee.
+    ///
+    /// Beads has no sling timestamp, so this is the issue's start time, or its last
+    /// update while it is assigned and in progress.
+    pub fn slung_at(&self) -> Option<String> {
+        self.assignee.as_ref()?;
+        if let Some(started_at) = &self.started_at {
+            return Some(started_at.clone());
+        }
+        match self.status {
+            IssueStatus::Open | IssueStatus::Closed => None,
+            _ => self.updated_at.clone(),
+        }
+    }
+}
+
+/// The issues of a single `.beads/` directory.
+///
+/// `reload` only re-reads the issue store when its modification time or size
+/// changed since the last read.
+#[derive(Debug)]
+pub struct BeadsStore {
+    dir: PathBuf,
+    /// Modification time and size of the issue store when it was last read
+    stamp: Option<(SystemTime, u64)>,
+    issues: Vec<BeadsIssue>,
+    /// Position of each issue in `issues` by id
+    positions: HashMap<String, usize>,
+}
+
+impl BeadsStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            stamp: None,
+            issues: Vec::new(),
+            positions: HashMap::default(),
+        }
+    }
+
+    /// Reads a store once, e.g. for a one-off scan
+    pub fn load(dir: impl Into<PathBuf>) -> Result<Self, BeadsError> {
+        let mut store = Self::new(dir);
+        store.reload()?;
+        Ok(store)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn issues_path(&self) -> PathBuf {
+        self.dir.join(BEADS_ISSUES_FILE)
+    }
+
+    pub fn issues(&self) -> &[BeadsIssue] {
+        &self.issues
+    }
+
+    pub fn issue(&self, id: &str) -> Option<&BeadsIssue> {
+        self.positions.get(id).map(|&index| &self.issues[index])
+    }
+
+    /// Re-reads the issue store if it changed on disk, returning whether it did.
+    ///
+    /// A missing store holds no issues. On error the previously read issues are kept.
+    pub fn reload(&mut self) -> Result<bool, BeadsError> {
+        let path = self.issues_path();
+        let stamp = std::fs::metadata(&path)
+            .ok()
+            .and_then(|metadata| Some((metadata.modified().ok()?, metadata.len())));
+        if stamp.is_some() && stamp == self.stamp {
+            return Ok(false);
+        }
+
+        let issues = match std::fs::read_to_string(&path) {
+            Ok(contents) => parse_issues(&path, &contents)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => {
+                return Err(BeadsError::Io {
+                    path,
+                    message: err.to_string(),
+                });
+            }
+        };
+
+        self.stamp = stamp;
+        if issues == self.issues {
+            return Ok(false);
+        }
+        self.positions = issues
+            .iter()
+            .enumerate()
+            .map(|(index, issue)| (issue.id.clone(), index))
+            .collect();
+        self.issues = issues;
+        Ok(true)
+    }
+}
+
+/// The issues of several `.beads/` directories, e.g. a town's and each of its rigs'
+#[derive(Debug, Default)]
+pub struct BeadsIndex {
+    stores: Vec<BeadsStore>,
+}
+
+impl BeadsIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `.beads/` directories to read, in priority order.
+    ///
+    /// Stores already in the index keep their issues, so only new or changed
+    /// directories are read on the next `reload`.
+    pub fn set_dirs(&mut self, dirs: impl IntoIterator<Item = PathBuf>) {
+        let mut previous = std::mem::take(&mut self.stores);
+        for dir in dirs {
+            let store = match previous.iter().position(|store| store.dir == dir) {
+                Some(index) => previous.swap_remove(index),
+                None => BeadsStore::new(dir),
+            };
+            self.stores.push(store);
+        }
+    }
+
+    pub fn stores(&self) -> &[BeadsStore] {
+        &self.stores
+    }
+
+    /// Reloads every store that changed on disk, returning whether any did
+    pub fn reload(&mut self) -> Result<bool, BeadsError> {
+        let mut changed = false;
+        for store in &mut self.stores {
+            changed |= store.reload()?;
+        }
+        Ok(changed)
+    }
+
+    pub fn issues(&self) -> impl Iterator<Item = &BeadsIssue> {
+        self.stores.iter().flat_map(|store| store.issues.iter())
+    }
+
+    /// The first issue with the given id, in store order
+    pub fn issue(&self, id: &str) -> Option<&BeadsIssue> {
+        self.stores.iter().find_map(|store| store.issue(id))
+    }
+
+    pub fn convoys(&self) -> impl Iterator<Item = &BeadsIssue> {
+        self.issues()
+            .filter(|issue| issue.issue_type == IssueType::Convoy)
+    }
+}
+
+/// Sets an issue's status in the issue store of `beads_dir`.
+///
+/// Only the issue's line is rewritten, keeping every other line and field as is.
+/// `updated_at` becomes `timestamp` (RFC 3339), and `closed_at` is set or cleared to
+/// match the new status. `bd` picks the change up on its next import.
+pub fn write_status(
+    beads_dir: &Path,
+    id: &str,
+    status: &IssueStatus,
+    timestamp: &str,
+) -> Result<(), BeadsError> {
+    let path = beads_dir.join(BEADS_ISSUES_FILE);
+    let io_error = |err: std::io::Error| BeadsError::Io {
+        path: path.clone(),
+        message: err.to_string(),
+    };
+    let contents = std::fs::read_to_string(&path).map_err(io_error)?;
+
+    let mut found = false;
+    let mut lines = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let parse_error = |err: serde_json::Error| BeadsError::Parse {
+            path: path.clone(),
+            line: index + 1,
+            message: err.to_string(),
+        };
+        if found || line.trim().is_empty() {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        let mut issue: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(line).map_err(parse_error)?;
+        if issue.get("id").and_then(|id| id.as_str()) != Some(id) {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        found = true;
+        issue.insert("status".into(), status.as_str().into());
+        issue.insert("updated_at".into(), timestamp.into());
+        if *status == IssueStatus::Closed {
+            issue.insert("closed_at".into(), timestamp.into());
+        } else {
+            issue.remove("closed_at");
+        }
+        lines.push(serde_json::to_string(&issue).map_err(parse_error)?);
+    }
+
+    if !found {
+        return Err(BeadsError::UnknownIssue {
+            path: path.clone(),
+            id: id.to_string(),
+        });
+    }
+
+    // Write next to the store and rename, so readers never see a partial file
+    let temp_path = path.with_extension("jsonl.tmp");
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    std::fs::write(&temp_path, contents).map_err(io_error)?;
+    std::fs::rename(&temp_path, &path).map_err(io_error)
+}
+
+fn parse_issues(path: &Path, contents: &str) -> Result<Vec<BeadsIssue>, BeadsError> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|err| BeadsError::Parse {
+                path: path.to_path_buf(),
+                line: index + 1,
+                message: err.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    pub(crate) fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("test_fixtures/beads")
+            .join(name)
+            .join(".beads")
+    }
+
+    /// Copies a fixture store into a temporary directory so tests can modify it
+    pub(crate) fn copy_fixture(name: &str) -> (TempDir, PathBuf) {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join(".beads");
+        fs::create_dir_all(&dir).unwrap();
+        fs::copy(
+            fixture(name).join(BEADS_ISSUES_FILE),
+            dir.join(BEADS_ISSUES_FILE),
+        )
+        .unwrap();
+        (temp, dir)
+    }
+
+    #[test]
+    fn test_load_typed_issues() {
+        let store = BeadsStore::load(fixture("frontend")).unwrap();
+
+        let ids: Vec<&str> = store
+            .issues()
+            .iter()
+            .map(|issue| issue.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["fe-1", "fe-2", "fe-3", "fe-4"]);
+
+        let login = store.issue("fe-1").unwrap();
+        assert_eq!(login.title(), Some("Add login form"));
+        assert_eq!(login.status, IssueStatus::Closed);
+        assert_eq!(login.issue_type, IssueType::Feature);
+        assert_eq!(login.priority, Some(1));
+        assert_eq!(login.assignee.as_deref(), Some("frontend/polecats/nux"));
+        assert_eq!(login.labels, vec!["auth".to_string(), "ui".to_string()]);
+
+        let session = store.issue("fe-2").unwrap();
+        assert_eq!(session.status, IssueStatus::InProgress);
+        assert_eq!(session.issue_type, IssueType::Bug);
+        assert_eq!(session.dependencies.len(), 2);
+        assert_eq!(
+            session.dependencies[1].dependency_type,
+            DependencyType::Related
+        );
+        assert_eq!(session.blockers().collect::<Vec<_>>(), vec!["fe-1"]);
+        assert_eq!(session.slung_at().as_deref(), Some("2026-01-05T12:00:00Z"));
+
+        let spike = store.issue("fe-4").unwrap();
+        assert_eq!(spike.status, IssueStatus::Other("review".into()));
+        assert_eq!(spike.issue_type, IssueType::Other("spike".into()));
+        assert_eq!(spike.title(), None);
+    }
+
+    #[test]
+    fn test_index_spans_stores() {
+        let mut index = BeadsIndex::new();
+        index.set_dirs([fixture("town"), fixture("frontend")]);
+        assert!(index.reload().unwrap());
+
+        let convoys: Vec<&str> = index.convoys().map(|issue| issue.id.as_str()).collect();
+        assert_eq!(convoys, vec!["hq-cv-1"]);
+
+        let convoy = index.issue("hq-cv-1").unwrap();
+        let titles: Vec<Option<&str>> = convoy
+            .dependencies
+            .iter()
+            .map(|dependency| {
+                index
+                    .issue(&dependency.depends_on_id)
+                    .and_then(|i| i.title())
+            })
+            .collect();
+        assert_eq!(
+            titles,
+            vec![Some("Add login form"), Some("Session expires too early")]
+        );
+    }
+
+    #[test]
+    fn test_missing_store_is_empty() {
+        let mut store = BeadsStore::new("/nonexistent/.beads");
+
+        assert!(!store.reload().unwrap());
+        assert!(store.issues().is_empty());
+    }
+
+    #[test]
+    fn test_reload_only_reads_changed_stores() {
+        let (_temp, dir) = copy_fixture("frontend");
+        let mut index = BeadsIndex::new();
+        index.set_dirs([dir.clone(), fixture("town")]);
+        assert!(index.reload().unwrap());
+        assert!(!index.reload().unwrap());
+
+        let mut contents = fs::read_to_string(dir.join(BEADS_ISSUES_FILE)).unwrap();
+        contents.push_str(r#"{"id": "fe-5", "title": "Dark mode", "status": "open"}"#);
+        contents.push('\n');
+        fs::write(dir.join(BEADS_ISSUES_FILE), contents).unwrap();
+
+        assert!(index.reload().unwrap());
+        assert_eq!(index.issue("fe-5").unwrap().title(), Some("Dark mode"));
+
+        // Re-registering the same directories keeps what was already read
+        index.set_dirs([dir.clone(), fixture("town")]);
+        assert!(!index.reload().unwrap());
+        assert_eq!(index.issues().count(), 7);
+    }
+
+    #[test]
+    fn test_write_status_rewrites_one_line() {
+        let (_temp, dir) = copy_fixture("frontend");
+        let original = fs::read_to_string(dir.join(BEADS_ISSUES_FILE)).unwrap();
+        let mut store = BeadsStore::load(&dir).unwrap();
+
+        write_status(&dir, "fe-3", &IssueStatus::Closed, "2026-01-07T10:00:00Z").unwrap();
+        assert!(store.reload().unwrap());
+        let issue = store.issue("fe-3").unwrap();
+        assert_eq!(issue.status, IssueStatus::Closed);
+        assert_eq!(issue.updated_at.as_deref(), Some("2026-01-07T10:00:00Z"));
+        assert_eq!(issue.closed_at.as_deref(), Some("2026-01-07T10:00:00Z"));
+        assert_eq!(issue.title(), Some("Remember me checkbox"));
+
+        let contents = fs::read_to_string(dir.join(BEADS_ISSUES_FILE)).unwrap();
+        let unchanged = |contents: &str| -> Vec<String> {
+            contents
+                .lines()
+                .filter(|line| !line.contains("\"fe-3\""))
+                .map(str::to_string)
+                .collect()
+        };
+        assert_eq!(unchanged(&contents), unchanged(&original));
+
+        write_status(&dir, "fe-3", &IssueStatus::Open, "2026-01-07T11:00:00Z").unwrap();
+        store.reload().unwrap();
+        assert_eq!(store.issue("fe-3").unwrap().closed_at, None);
+
+        assert!(matches!(
+            write_status(&dir, "fe-9", &IssueStatus::Open, "2026-01-07T11:00:00Z"),
+            Err(BeadsError::UnknownIssue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_error_keeps_previous_issues() {
+        let (_temp, dir) = copy_fixture("frontend");
+        let mut store = BeadsStore::load(&dir).unwrap();
+
+        fs::write(dir.join(BEADS_ISSUES_FILE), "{\"id\": \"fe-1\"}\n{oops\n").unwrap();
+
+        let error = store.reload().unwrap_err();
+        assert!(matches!(error, BeadsError::Parse { line: 2, .. }));
+        assert!(error.to_string().contains("issues.jsonl:2"));
+        assert_eq!(store.issues().len(), 4);
+    }
+}
@@ -1,14 +1,76 @@
+pub mod agent_discovery;
+pub mod agent_inspector;
 mod agent_section;
+pub mod agent_terminal;
+pub mod beads;
+pub mod cli;
+pub mod composer;
+mod conflict_section;
+pub mod convoy_editor;
 mod convoy_section;
-mod dashboard_buffer;
+pub mod convoy_view;
+pub mod dashboard_buffer;
+pub mod formula;
+pub mod formula_form;
+pub mod formula_view;
+pub mod fs_data_source;
+pub mod gt_cli;
+pub mod gt_data_source;
+pub mod hook_conflicts;
+pub mod hook_lifecycle;
+pub mod hook_review;
+pub mod http_data_source;
+pub mod kanban_board;
+pub mod mail;
+pub mod mail_view;
+pub mod mayor_chat;
+pub mod node_detail;
+pub mod polecat_output;
 mod rig_section;
-pub mod agent_discovery;
+pub mod sling;
 pub mod town;
 pub mod town_item;
+pub mod town_model;
+pub mod town_tree;
+pub mod watch;
 
 #[cfg(test)]
 mod dashboard_buffer_tests;
+#[cfg(all(test, unix))]
+mod fake_gt;
+#[cfg(test)]
+mod test_git;
 
 pub use agent_discovery::{AgentDirectory, AgentDiscovery, AgentRole};
+pub use agent_inspector::{AgentInspector, AgentInspectorEvent};
+pub use agent_terminal::{AgentTerminal, AttachState};
+pub use beads::{BeadsError, BeadsIndex, BeadsIssue, BeadsStore, IssueStatus, IssueType};
+pub use cli::{Args, Command, DataSourceKind, OpenTarget};
+pub use composer::{Activity, Composer, Delivery, Recipient};
+pub use convoy_editor::{ConvoyCommand, ConvoyEditor, ConvoyEditorEvent};
+pub use convoy_view::{ConvoyView, ConvoyViewEvent};
+pub use dashboard_buffer::{DashboardData, DashboardDataSource, DashboardError, DashboardView};
+pub use formula::{Formula, FormulaError, VarError};
+pub use formula_form::{FormulaForm, FormulaFormEvent, FormulaRun, FormulaRunMode};
+pub use formula_view::{FormulaView, FormulaViewEvent};
+pub use fs_data_source::FsDataSource;
+pub use gt_cli::{GtCli, GtCommandError};
+pub use gt_data_source::GtDataSource;
+pub use hook_conflicts::{ConflictKind, ConflictMatrix, ConflictReport, HookConflict, HookDiff};
+pub use hook_lifecycle::{HookLifecycleEvent, HookLifecycleView, HookRecord, HookState};
+pub use hook_review::{ChangeKind, ChangedFile, HookChanges, HookReview, HookReviewEvent};
+pub use http_data_source::HttpDataSource;
+pub use kanban_board::{BoardScope, KanbanBoard};
+pub use mail::{MailFolder, MailIndex, MailMessage, MailThread};
+pub use mail_view::MailView;
+pub use mayor_chat::{MayorChat, MayorSession};
+pub use node_detail::NodeDetail;
+pub use polecat_output::{OutputSource, PolecatOutput, PolecatPane};
+pub use sling::{SlingRequest, SlingStatus};
 pub use town::Town;
-pub use town_item::{TownItem, TownItemEvent, TabContentParams};
+pub use town_item::{TabContentParams, TownItem, TownItemEvent};
+pub use town_model::{
+    Agent, Convoy, ConvoyIssue, Crew, FormulaFile, Hook, Issue, Rig, TownModel, TownModelEvent,
+};
+pub use town_tree::{TownNode, TownTree, TownTreeEvent};
+pub use watch::{DashboardWatch, DashboardWatchEvent};
@@ -0,0 +1,353 @@
+use clap::{CommandFactory as _, Parser, Subcommand, ValueEnum, error::ErrorKind};
+use http_client::{HttpClient, Url};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::dashboard_buffer::{
+    DEFAULT_REFRESH_INTERVAL, DashboardData, DashboardDataSource, DashboardFormatter,
+};
+use crate::fs_data_source::FsDataSource;
+use crate::gt_cli::GtCli;
+use crate::gt_data_source::GtDataSource;
+use crate::http_data_source::{DEFAULT_DASHBOARD_URL, HttpDataSource};
+
+/// Command-line arguments of the `belvedere` binary
+#[derive(Debug, Parser)]
+#[command(
+    name = "belvedere",
+    version,
+    about = "Multi-agent development workspace for Gastown"
+)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Town directory to open [default: ~/gt]
+    #[arg(long, value_name = "PATH", global = true)]
+    pub town: Option<PathBuf>,
+
+    /// Item to open once the town is loaded: `convoy:<id>`, `agent:<name>` or `rig:<name>`
+    #[arg(long, value_name = "TARGET")]
+    pub open: Option<OpenTarget>,
+
+    /// Where the dashboard reads agents, convoys and rigs from
+    #[arg(long, value_enum, default_value_t = DataSourceKind::Fs, global = true)]
+    pub data_source: DataSourceKind,
+
+    /// Address of the `gt dashboard` server, for `--data-source http`
+    #[arg(long, value_name = "URL", global = true)]
+    pub url: Option<Url>,
+
+    /// Seconds between dashboard refreshes [default: 5]
+    #[arg(long, value_name = "SECONDS", value_parser = parse_interval, global = true)]
+    pub refresh_interval: Option<Duration>,
+
+    /// Print the dashboard as text and exit, without opening a window
+    #[arg(long, conflicts_with = "json")]
+    pub print: bool,
+
+    /// Print the dashboard as JSON and exit, without opening a window
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// Redraw the text dashboard in the terminal on every refresh, exiting with an
+    /// error once the town becomes unavailable
+    Watch {
+        /// Draw without ANSI colors
+        #[arg(long)]
+        no_color: bool,
+    },
+}
+
+/// Where dashboard data comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DataSourceKind {
+    /// Read the town directory directly
+    Fs,
+    /// Run `gt ... --json` commands in the town
+    Gt,
+    /// Query the `gt dashboard` web server
+    Http,
+}
+
+/// An item to open in the center pane on startup
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OpenTarget {
+    Convoy(String),
+    Agent(String),
+    Rig(String),
+}
+
+impl FromStr for OpenTarget {
+    type Err = String;
+
+    fn from_str(target: &str) -> Result<Self, Self::Err> {
+        let (kind, name) = target
+            .split_once(':')
+            .ok_or_else(|| format!("expected `<kind>:<name>`, got `{}`", target))?;
+        if name.is_empty() {
+            return Err(format!("missing name after `{}:`", kind));
+        }
+        match kind {
+            "convoy" => Ok(Self::Convoy(name.into())),
+            "agent" => Ok(Self::Agent(name.into())),
+            "rig" => Ok(Self::Rig(name.into())),
+            _ => Err(format!(
+                "unknown kind `{}`, expected `convoy`, `agent` or `rig`",
+                kind
+            )),
+        }
+    }
+}
+
+fn parse_interval(seconds: &str) -> Result<Duration, String> {
+    let seconds: f64 = seconds
+        .parse()
+        .map_err(|_| format!("`{}` isn't a number of seconds", seconds))?;
+    if !seconds.is_finite() || seconds <= 0.0 {
+        return Err("the refresh interval must be positive".into());
+    }
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+impl Args {
+    /// Parses the process arguments, exiting with usage on error
+    pub fn parse_env() -> Self {
+        let args = Self::parse();
+        if let Err(message) = args.validate() {
+            Self::command()
+                .error(ErrorKind::ArgumentConflict, message)
+                .exit();
+        }
+        args
+    }
+
+    /// Checks combinations of arguments clap can't express
+    pub fn validate(&self) -> Result<(), String> {
+        if self.url.is_some() && self.data_source != DataSourceKind::Http {
+            return Err("`--url` requires `--data-source http`".into());
+        }
+        if self.command.is_some() && self.is_headless() {
+            return Err("`watch` can't be combined with `--print` or `--json`".into());
+        }
+        // Nothing opens without a window
+        if self.open.is_some() && (self.command.is_some() || self.is_headless()) {
+            return Err("`--open` can't be combined with `watch`, `--print` or `--json`".into());
+        }
+        Ok(())
+    }
+
+    pub fn town_path(&self) -> PathBuf {
+        self.town.clone().unwrap_or_else(|| {
+            dirs::home_dir()
+                .map(|home| home.join("gt"))
+                .unwrap_or_else(|| PathBuf::from("gt"))
+        })
+    }
+
+    pub fn refresh_interval(&self) -> Duration {
+        self.refresh_interval.unwrap_or(DEFAULT_REFRESH_INTERVAL)
+    }
+
+    /// Whether to print the dashboard instead of opening a window
+    pub fn is_headless(&self) -> bool {
+        self.print || self.json
+    }
+
+    /// The dashboard data source for `town`
+    pub fn data_source(
+        &self,
+        town: &Path,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Arc<dyn DashboardDataSource> {
+        match self.data_source {
+            DataSourceKind::Fs => Arc::new(FsDataSource::new(town)),
+            DataSourceKind::Gt => Arc::new(GtDataSource::new(GtCli::default().town_path(town))),
+            DataSourceKind::Http => {
+                let url = self.url.clone().unwrap_or_else(|| {
+                    Url::parse(DEFAULT_DASHBOARD_URL).expect("default dashboard URL is valid")
+                });
+                Arc::new(HttpDataSource::new(http_client, url))
+            }
+        }
+    }
+
+    /// Renders `data` the way `--print` or `--json` asked for
+    pub fn format(&self, data: &DashboardData) -> String {
+        if self.json {
+            let mut json = serde_json::to_string_pretty(data).unwrap_or_default();
+            json.push('\n');
+            json
+        } else {
+            DashboardFormatter::format(data)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard_buffer::{AgentInfo, AgentStatus, RigInfo};
+    use http_client::{AsyncBody, FakeHttpClient, Response};
+    use std::sync::Mutex;
+
+    fn parse(args: &[&str]) -> Result<Args, clap::Error> {
+        Args::try_parse_from(std::iter::once("belvedere").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_defaults() {
+        let args = parse(&[]).unwrap();
+        assert_eq!(args.command, None);
+        assert_eq!(args.town, None);
+        assert_eq!(args.open, None);
+        assert_eq!(args.data_source, DataSourceKind::Fs);
+        assert_eq!(args.refresh_interval(), DEFAULT_REFRESH_INTERVAL);
+        assert!(!args.is_headless());
+        assert!(args.town_path().ends_with("gt"));
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        let args = parse(&[
+            "--town",
+            "/srv/town",
+            "--open",
+            "convoy:hq-cv-1",
+            "--data-source",
+            "http",
+            "--url",
+            "http://tower:8080",
+            "--refresh-interval",
+            "0.5",
+            "--json",
+        ])
+        .unwrap();
+        assert_eq!(args.town_path(), PathBuf::from("/srv/town"));
+        assert_eq!(args.open, Some(OpenTarget::Convoy("hq-cv-1".into())));
+        assert_eq!(args.data_source, DataSourceKind::Http);
+        assert_eq!(
+            args.url.as_ref().map(Url::as_str),
+            Some("http://tower:8080/")
+        );
+        assert_eq!(args.refresh_interval(), Duration::from_millis(500));
+        assert!(args.is_headless());
+        assert_eq!(args.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_rejects_invalid_arguments() {
+        assert!(parse(&["--print", "--json"]).is_err());
+        assert!(parse(&["--refresh-interval", "0"]).is_err());
+        assert!(parse(&["--refresh-interval", "soon"]).is_err());
+        assert!(parse(&["--data-source", "carrier-pigeon"]).is_err());
+        assert!(parse(&["--open", "hook:frontend/toast"]).is_err());
+
+        let args = parse(&["--url", "http://tower:8080"]).unwrap();
+        assert!(args.validate().is_err());
+    }
+
+    #[test]
+    fn test_watch_command() {
+        let args = parse(&["watch", "--town", "/srv/town", "--refresh-interval", "2"]).unwrap();
+        assert_eq!(args.command, Some(Command::Watch { no_color: false }));
+        assert_eq!(args.town_path(), PathBuf::from("/srv/town"));
+        assert_eq!(args.refresh_interval(), Duration::from_secs(2));
+        assert_eq!(args.validate(), Ok(()));
+
+        let args = parse(&["--data-source", "gt", "watch", "--no-color"]).unwrap();
+        assert_eq!(args.command, Some(Command::Watch { no_color: true }));
+        assert_eq!(args.data_source, DataSourceKind::Gt);
+
+        let args = parse(&["--print", "watch"]).unwrap();
+        assert!(args.validate().is_err());
+    }
+
+    #[test]
+    fn test_open_requires_window() {
+        let args = parse(&["--open", "rig:frontend"]).unwrap();
+        assert_eq!(args.validate(), Ok(()));
+
+        for headless in [
+            &["--open", "rig:frontend", "--print"][..],
+            &["--open", "rig:frontend", "--json"],
+            &["--open", "rig:frontend", "watch"],
+        ] {
+            let args = parse(headless).unwrap();
+            assert_eq!(
+                args.validate(),
+                Err("`--open` can't be combined with `watch`, `--print` or `--json`".into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_open_target() {
+        assert_eq!(
+            "agent:frontend/polecats/toast".parse(),
+            Ok(OpenTarget::Agent("frontend/polecats/toast".into()))
+        );
+        assert_eq!(
+            "rig:frontend".parse(),
+            Ok(OpenTarget::Rig("frontend".into()))
+        );
+        assert!("convoy".parse::<OpenTarget>().is_err());
+        assert!("convoy:".parse::<OpenTarget>().is_err());
+    }
+
+    #[test]
+    fn test_http_data_source_defaults_to_local_dashboard() {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let client = FakeHttpClient::create({
+            let requested = requested.clone();
+            move |request| {
+                requested.lock().unwrap().push(request.uri().to_string());
+                async move {
+                    Ok(Response::builder()
+                        .status(404)
+                        .body(AsyncBody::empty())
+                        .unwrap())
+                }
+            }
+        });
+
+        let args = parse(&["--data-source", "http"]).unwrap();
+        let source = args.data_source(Path::new("/srv/town"), client);
+        assert!(source.fetch().is_err());
+        assert_eq!(
+            *requested.lock().unwrap(),
+            vec!["http://localhost:8080/api/dashboard".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_format_output() {
+        let data = DashboardData {
+            agents: vec![AgentInfo {
+                name: "mayor".into(),
+                status: AgentStatus::Error("stuck".into()),
+                token_usage: None,
+                context_fill: Some(0.5),
+            }],
+            convoys: Vec::new(),
+            rigs: vec![RigInfo {
+                name: "frontend".into(),
+                path: "/srv/town/frontend".into(),
+            }],
+        };
+
+        let text = parse(&["--print"]).unwrap().format(&data);
+        assert_eq!(text, DashboardFormatter::format(&data));
+
+        let json = parse(&["--json"]).unwrap().format(&data);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["agents"][0]["name"], "mayor");
+        assert_eq!(value["agents"][0]["status"]["error"], "stuck");
+        assert_eq!(value["rigs"][0]["path"], "/srv/town/frontend");
+    }
+}
@@ -0,0 +1,654 @@
+use collections::HashMap;
+use editor::Editor;
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString, Task, Window,
+    div, prelude::*, px,
+};
+use std::time::Instant;
+use ui::{
+    ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, ContextMenu, Disableable,
+    DropdownMenu, LabelSize,
+};
+
+use crate::agent_discovery::AgentRole;
+use crate::dashboard_buffer::format_elapsed;
+use crate::gt_cli::GtCli;
+use crate::town_item::{TownItem, TownItemEvent};
+use crate::town_model::TownModel;
+
+/// Who a message is sent to
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Recipient {
+    /// One agent, by address
+    Agent(String),
+    /// Every agent with a role, e.g. all polecats
+    Role(AgentRole),
+    /// Every agent working in a rig
+    Rig(String),
+    /// Every agent in the town, with `gt broadcast`
+    Everyone,
+}
+
+impl Recipient {
+    pub fn label(&self) -> String {
+        match self {
+            Recipient::Agent(address) => address.clone(),
+            Recipient::Role(role) => format!("Every {}", role),
+            Recipient::Rig(rig) => format!("Rig {}", rig),
+            Recipient::Everyone => "Everyone".into(),
+        }
+    }
+
+    /// The agents nudged for this recipient. Empty for `Everyone`, which is a
+    /// single broadcast.
+    pub fn addresses(&self, model: &TownModel) -> Vec<String> {
+        match self {
+            Recipient::Agent(address) => vec![address.clone()],
+            Recipient::Role(role) => model
+                .agents()
+                .filter(|agent| agent.role == *role)
+                .map(|agent| agent.name.clone())
+                .collect(),
+            Recipient::Rig(rig) => model
+                .agents_in_rig(rig)
+                .map(|agent| agent.name.clone())
+                .collect(),
+            Recipient::Everyone => Vec::new(),
+        }
+    }
+}
+
+/// The result of sending a message to one target
+#[derive(Clone, Debug, PartialEq)]
+pub struct Delivery {
+    /// An agent address, or `everyone` for a broadcast
+    pub target: String,
+    pub result: Result<(), String>,
+}
+
+/// Sends `message` with `gt broadcast` for everyone, otherwise with one `gt nudge`
+/// per address. Delivery carries on past failures so each target gets a result.
+pub fn deliver(
+    gt: &GtCli,
+    recipient: &Recipient,
+    addresses: &[String],
+    message: &str,
+) -> Vec<Delivery> {
+    if *recipient == Recipient::Everyone {
+        return vec![Delivery {
+            target: "everyone".into(),
+            result: gt
+                .run(["broadcast", message])
+                .map(|_| ())
+                .map_err(|err| err.to_string()),
+        }];
+    }
+
+    if addresses.is_empty() {
+        return vec![Delivery {
+            target: recipient.label(),
+            result: Err("No agents to deliver to".into()),
+        }];
+    }
+
+    addresses
+        .iter()
+        .map(|address| Delivery {
+            target: address.clone(),
+            result: gt
+                .run(["nudge", address.as_str(), message])
+                .map(|_| ())
+                .map_err(|err| err.to_string()),
+        })
+        .collect()
+}
+
+/// A sent message in the activity stream
+#[derive(Clone, Debug)]
+pub struct Activity {
+    pub recipient: Recipient,
+    pub message: String,
+    pub sent_at: Instant,
+    id: usize,
+    /// `None` while the message is being delivered
+    pub deliveries: Option<Vec<Delivery>>,
+}
+
+impl Activity {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &Delivery> {
+        self.deliveries
+            .iter()
+            .flatten()
+            .filter(|delivery| delivery.result.is_err())
+    }
+}
+
+/// Center pane item for nudging agents and broadcasting to the town
+pub struct Composer {
+    model: Entity<TownModel>,
+    gt: GtCli,
+    recipient: Recipient,
+    /// Created on first render, since editors need a window
+    editor: Option<Entity<Editor>>,
+    /// Newest first
+    activity: Vec<Activity>,
+    next_activity_id: usize,
+    /// Deliveries in flight, by activity id
+    send_tasks: HashMap<usize, Task<()>>,
+    focus_handle: FocusHandle,
+}
+
+impl EventEmitter<TownItemEvent> for Composer {}
+
+impl Composer {
+    pub fn new(
+        recipient: Recipient,
+        model: Entity<TownModel>,
+        gt: GtCli,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self {
+            model,
+            gt,
+            recipient,
+            editor: None,
+            activity: Vec::new(),
+            next_activity_id: 0,
+            send_tasks: HashMap::default(),
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    pub fn recipient(&self) -> &Recipient {
+        &self.recipient
+    }
+
+    pub fn set_recipient(&mut self, recipient: Recipient, cx: &mut Context<Self>) {
+        self.recipient = recipient;
+        cx.notify();
+    }
+
+    pub fn activity(&self) -> &[Activity] {
+        &self.activity
+    }
+
+    /// Every recipient that can be picked: everyone, each role, each rig and each
+    /// agent
+    pub fn recipients(&self, cx: &App) -> Vec<Recipient> {
+        let model = self.model.read(cx);
+        let mut recipients = vec![Recipient::Everyone];
+        recipients.extend(
+            [
+                AgentRole::Mayor,
+                AgentRole::Deacon,
+                AgentRole::Witness,
+                AgentRole::Polecat,
+                AgentRole::Crew,
+            ]
+            .into_iter()
+            .map(Recipient::Role),
+        );
+        recipients.extend(model.rigs().map(|rig| Recipient::Rig(rig.name.clone())));
+        recipients.extend(
+            model
+                .agents()
+                .map(|agent| Recipient::Agent(agent.name.clone())),
+        );
+        recipients
+    }
+
+    /// Sends `message` to the current recipient, recording the outcome in the
+    /// activity stream
+    pub fn send(&mut self, message: String, cx: &mut Context<Self>) {
+        let recipient = self.recipient.clone();
+        let addresses = recipient.addresses(self.model.read(cx));
+        self.start_delivery(recipient, addresses, message, cx);
+    }
+
+    /// Sends an activity's message again to the targets it failed to reach
+    pub fn retry(&mut self, id: usize, cx: &mut Context<Self>) {
+        let Some(activity) = self.activity.iter().find(|activity| activity.id == id) else {
+            return;
+        };
+        let recipient = activity.recipient.clone();
+        let message = activity.message.clone();
+        let failed: Vec<String> = activity
+            .failures()
+            .map(|delivery| delivery.target.clone())
+            .collect();
+        if failed.is_empty() {
+            return;
+        }
+        // A broadcast, or a recipient that matched no agents, is sent again in full
+        let addresses = match &recipient {
+            Recipient::Everyone => Vec::new(),
+            _ if failed == [recipient.label()] => recipient.addresses(self.model.read(cx)),
+            _ => failed,
+        };
+        self.start_delivery(recipient, addresses, message, cx);
+    }
+
+    fn start_delivery(
+        &mut self,
+        recipient: Recipient,
+        addresses: Vec<String>,
+        message: String,
+        cx: &mut Context<Self>,
+    ) {
+        let id = self.next_activity_id;
+        self.next_activity_id += 1;
+        self.activity.insert(
+            0,
+            Activity {
+                recipient: recipient.clone(),
+                message: message.clone(),
+                sent_at: Instant::now(),
+                id,
+                deliveries: None,
+            },
+        );
+
+        let gt = self.gt.clone();
+        let send =
+            cx.background_spawn(async move { deliver(&gt, &recipient, &addresses, &message) });
+        let task = cx.spawn(async move |this, cx| {
+            let deliveries = send.await;
+            this.update(cx, |this, cx| {
+                this.send_tasks.remove(&id);
+                if let Some(activity) = this.activity.iter_mut().find(|a| a.id == id) {
+                    activity.deliveries = Some(deliveries);
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+        self.send_tasks.insert(id, task);
+        cx.notify();
+    }
+
+    fn send_from_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(editor) = self.editor.clone() else {
+            return;
+        };
+        let message = editor.read(cx).text(cx);
+        let message = message.trim();
+        if message.is_empty() {
+            return;
+        }
+        self.send(message.to_string(), cx);
+        editor.update(cx, |editor, cx| editor.clear(window, cx));
+    }
+
+    fn editor(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<Editor> {
+        self.editor
+            .get_or_insert_with(|| {
+                cx.new(|cx| {
+                    let mut editor = Editor::auto_height(4, 12, window, cx);
+                    editor.set_placeholder_text("Message…", window, cx);
+                    editor
+                })
+            })
+            .clone()
+    }
+
+    fn render_recipient_picker(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let recipients = self.recipients(cx);
+        let view = cx.entity().downgrade();
+        let menu = ContextMenu::build(window, cx, move |mut menu, _, _| {
+            let mut last_kind = None;
+            for recipient in recipients {
+                let kind = match &recipient {
+                    Recipient::Everyone => "Town",
+                    Recipient::Role(_) => "Roles",
+                    Recipient::Rig(_) => "Rigs",
+                    Recipient::Agent(_) => "Agents",
+                };
+                if last_kind != Some(kind) {
+                    menu = menu.header(kind);
+                    last_kind = Some(kind);
+                }
+                let view = view.clone();
+                menu = menu.entry(recipient.label(), None, move |_, cx| {
+                    view.update(cx, |view, cx| view.set_recipient(recipient.clone(), cx))
+                        .ok();
+                });
+            }
+            menu
+        });
+        DropdownMenu::new("composer-recipient", self.recipient.label(), menu)
+    }
+
+    fn render_activity(&self, activity: &Activity, cx: &Context<Self>) -> impl IntoElement {
+        let id = activity.id;
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+        let (summary, color) = match &activity.deliveries {
+            None => ("Sending…".to_string(), colors.text_muted),
+            Some(deliveries) => {
+                let failed = activity.failures().count();
+                if failed == 0 {
+                    (
+                        format!("Delivered to {}", activity.recipient.label()),
+                        status.success,
+                    )
+                } else {
+                    (
+                        format!("{} of {} deliveries failed", failed, deliveries.len()),
+                        status.error,
+                    )
+                }
+            }
+        };
+        let failures: Vec<String> = activity
+            .failures()
+            .filter_map(|delivery| {
+                let error = delivery.result.as_ref().err()?;
+                Some(format!("{}: {}", delivery.target, error))
+            })
+            .collect();
+        let can_retry = !failures.is_empty();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(2.0))
+            .p(px(8.0))
+            .border_b_1()
+            .border_color(colors.border_variant)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .child(
+                        div()
+                            .text_color(colors.text)
+                            .child(activity.recipient.label()),
+                    )
+                    .child(div().text_color(color).child(summary))
+                    .child(div().flex_1())
+                    .child(div().text_xs().text_color(colors.text_muted).child(format!(
+                        "{} ago",
+                        format_elapsed(activity.sent_at.elapsed())
+                    )))
+                    .when(can_retry, |this| {
+                        this.child(
+                            Button::new(SharedString::from(format!("retry-{}", id)), "Retry")
+                                .style(ButtonStyle::Subtle)
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(move |this, _, _, cx| this.retry(id, cx))),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .text_color(colors.text_muted)
+                    .child(activity.message.clone()),
+            )
+            .children(
+                failures
+                    .into_iter()
+                    .map(|failure| div().text_xs().text_color(status.error).child(failure)),
+            )
+    }
+}
+
+impl Focusable for Composer {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TownItem for Composer {
+    type Event = TownItemEvent;
+
+    fn tab_content_text(&self, _cx: &App) -> SharedString {
+        "Compose".into()
+    }
+
+    fn to_town_item_events(event: &Self::Event, mut f: impl FnMut(TownItemEvent)) {
+        f(*event);
+    }
+}
+
+impl Render for Composer {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let editor = self.editor(window, cx);
+        let recipient_picker = self.render_recipient_picker(window, cx);
+        let colors = cx.theme().colors();
+        let sending = !self.send_tasks.is_empty();
+        let command = match &self.recipient {
+            Recipient::Everyone => "gt broadcast".to_string(),
+            recipient => {
+                let count = recipient.addresses(self.model.read(cx)).len();
+                format!(
+                    "gt nudge · {} agent{}",
+                    count,
+                    if count == 1 { "" } else { "s" }
+                )
+            }
+        };
+
+        div()
+            .id("composer")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .text_sm()
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(px(8.0))
+                    .p(px(12.0))
+                    .border_b_1()
+                    .border_color(colors.border_variant)
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(px(8.0))
+                            .child(div().text_color(colors.text_muted).child("To"))
+                            .child(recipient_picker)
+                            .child(div().text_xs().text_color(colors.text_muted).child(command)),
+                    )
+                    .child(
+                        div()
+                            .p(px(6.0))
+                            .rounded(px(4.0))
+                            .border_1()
+                            .border_color(colors.border)
+                            .bg(colors.editor_background)
+                            .child(editor),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(px(8.0))
+                            .when(sending, |this| {
+                                this.child(div().text_color(colors.text_muted).child("Sending…"))
+                            })
+                            .child(div().flex_1())
+                            .child(
+                                Button::new("send-message", "Send")
+                                    .style(ButtonStyle::Filled)
+                                    .label_size(LabelSize::Small)
+                                    .disabled(self.editor.as_ref().is_none_or(|editor| {
+                                        editor.read(cx).text(cx).trim().is_empty()
+                                    }))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.send_from_editor(window, cx)
+                                    })),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .id("composer-activity")
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .when(self.activity.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .p(px(12.0))
+                                .text_color(colors.text_muted)
+                                .child("Nothing sent yet"),
+                        )
+                    })
+                    .children(
+                        self.activity
+                            .iter()
+                            .map(|activity| self.render_activity(activity, cx)),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_discovery::AgentDirectory;
+    use crate::town_model::{Rig, TownStructure};
+    use gpui::TestAppContext;
+    use std::path::Path;
+
+    fn town_model(cx: &mut TestAppContext) -> Entity<TownModel> {
+        let rig = Path::new("/gt/frontend");
+        cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(
+                TownStructure {
+                    rigs: vec![Rig {
+                        name: "frontend".into(),
+                        path: rig.to_path_buf(),
+                    }],
+                    agents: vec![
+                        AgentDirectory::from_rig_member(rig.join("polecats/nux")).unwrap(),
+                        AgentDirectory::from_rig_member(rig.join("polecats/toast")).unwrap(),
+                        AgentDirectory::from_rig_member(rig.join("crew/alice")).unwrap(),
+                    ],
+                    ..Default::default()
+                },
+                cx,
+            );
+            model
+        })
+    }
+
+    #[gpui::test]
+    fn test_recipient_addresses(cx: &mut TestAppContext) {
+        let model = town_model(cx);
+        model.read_with(cx, |model, _| {
+            assert_eq!(
+                Recipient::Role(AgentRole::Polecat).addresses(model),
+                vec!["frontend/polecats/nux", "frontend/polecats/toast"]
+            );
+            assert_eq!(Recipient::Rig("frontend".into()).addresses(model).len(), 3);
+            assert_eq!(Recipient::Rig("backend".into()).addresses(model).len(), 0);
+            assert!(Recipient::Everyone.addresses(model).is_empty());
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_deliver_reports_each_target() {
+        use crate::fake_gt::FakeGt;
+
+        let gt = FakeGt::new()
+            .respond("broadcast Standup in 5", "")
+            .respond("nudge frontend/polecats/nux Rebase on main", "")
+            .fail(
+                "nudge frontend/polecats/toast Rebase on main",
+                1,
+                "no tmux session",
+            );
+
+        assert_eq!(
+            deliver(&gt.cli(), &Recipient::Everyone, &[], "Standup in 5"),
+            vec![Delivery {
+                target: "everyone".into(),
+                result: Ok(()),
+            }]
+        );
+
+        let deliveries = deliver(
+            &gt.cli(),
+            &Recipient::Role(AgentRole::Polecat),
+            &[
+                "frontend/polecats/nux".to_string(),
+                "frontend/polecats/toast".to_string(),
+            ],
+            "Rebase on main",
+        );
+        assert_eq!(deliveries[0].result, Ok(()));
+        assert_eq!(
+            deliveries[1].result,
+            Err(
+                "`gt nudge frontend/polecats/toast Rebase on main` exited with status 1: \
+                 no tmux session"
+                    .into()
+            )
+        );
+
+        let deliveries = deliver(&gt.cli(), &Recipient::Rig("backend".into()), &[], "Hi");
+        assert!(deliveries[0].result.is_err());
+        assert_eq!(gt.calls().len(), 3);
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    fn test_send_records_activity(cx: &mut TestAppContext) {
+        use crate::fake_gt::FakeGt;
+
+        let gt = FakeGt::new()
+            .respond("nudge frontend/crew/alice Check CI", "")
+            .fail("nudge frontend/polecats/nux Check CI", 1, "no tmux session")
+            .respond("nudge frontend/polecats/toast Check CI", "");
+        let model = town_model(cx);
+        let composer =
+            cx.new(|cx| Composer::new(Recipient::Rig("frontend".into()), model, gt.cli(), cx));
+
+        composer.update(cx, |composer, cx| composer.send("Check CI".into(), cx));
+        composer.read_with(cx, |composer, _| {
+            assert!(composer.activity()[0].deliveries.is_none());
+        });
+        cx.run_until_parked();
+
+        composer.read_with(cx, |composer, _| {
+            let failures: Vec<&str> = composer.activity()[0]
+                .failures()
+                .map(|delivery| delivery.target.as_str())
+                .collect();
+            assert_eq!(failures, vec!["frontend/polecats/nux"]);
+        });
+
+        let id = composer.read_with(cx, |composer, _| composer.activity()[0].id());
+        composer.update(cx, |composer, cx| composer.retry(id, cx));
+        cx.run_until_parked();
+        composer.read_with(cx, |composer, _| {
+            assert_eq!(composer.activity().len(), 2);
+            assert_eq!(composer.activity()[0].failures().count(), 1);
+            assert_eq!(composer.activity()[1].id(), id);
+        });
+        assert_eq!(gt.calls().len(), 4);
+
+        // The retried message moved down the list, but its id still finds it
+        composer.update(cx, |composer, cx| composer.retry(id, cx));
+        cx.run_until_parked();
+        let calls = gt.calls();
+        assert_eq!(calls.len(), 5);
+        assert_eq!(calls[4].1, "nudge frontend/polecats/nux Check CI");
+    }
+}
@@ -0,0 +1,242 @@
+use gpui::{
+    ClickEvent, Hsla, InteractiveElement, IntoElement, ParentElement, StatefulInteractiveElement,
+    Styled, div, px,
+};
+use std::sync::Arc;
+
+use crate::hook_conflicts::{ConflictKind, ConflictMatrix, ConflictReport};
+
+type ToggleHandler = Arc<dyn Fn(&ClickEvent, &mut gpui::Window, &mut gpui::App) + 'static>;
+
+/// Width of a matrix cell and of the owner column in front of it
+const CELL_WIDTH: f32 = 28.0;
+const OWNER_WIDTH: f32 = 220.0;
+
+/// Warnings about hooks changing the same files, with a matrix of which agents
+/// conflict with which
+pub struct ConflictSection {
+    report: ConflictReport,
+    palette: ConflictSectionPalette,
+    expanded: bool,
+    on_toggle: Option<ToggleHandler>,
+}
+
+#[derive(Clone, Copy)]
+pub struct ConflictSectionPalette {
+    pub panel_bg: Hsla,
+    pub border_variant: Hsla,
+    pub text: Hsla,
+    pub text_muted: Hsla,
+    pub accent_warning: Hsla,
+    pub accent_error: Hsla,
+    pub element_bg: Hsla,
+}
+
+impl ConflictSection {
+    pub fn new(report: &ConflictReport, palette: ConflictSectionPalette) -> Self {
+        Self {
+            report: report.clone(),
+            palette,
+            expanded: true,
+            on_toggle: None,
+        }
+    }
+
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    pub fn on_toggle(
+        mut self,
+        on_toggle: impl Fn(&ClickEvent, &mut gpui::Window, &mut gpui::App) + 'static,
+    ) -> Self {
+        self.on_toggle = Some(Arc::new(on_toggle));
+        self
+    }
+
+    fn kind_color(&self, kind: ConflictKind) -> Hsla {
+        match kind {
+            ConflictKind::SameFile => self.palette.accent_warning,
+            ConflictKind::OverlappingHunks => self.palette.accent_error,
+        }
+    }
+
+    fn render_matrix(&self, matrix: &ConflictMatrix) -> gpui::Div {
+        let palette = self.palette;
+        let column_headers = (0..matrix.owners.len()).map(|column| {
+            div()
+                .w(px(CELL_WIDTH))
+                .flex_shrink_0()
+                .text_center()
+                .child((column + 1).to_string())
+        });
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(2.0))
+            .text_sm()
+            .child(
+                div()
+                    .flex()
+                    .text_color(palette.text_muted)
+                    .child(div().w(px(OWNER_WIDTH)).flex_shrink_0())
+                    .children(column_headers),
+            )
+            .children(matrix.owners.iter().enumerate().map(|(row, owner)| {
+                div()
+                    .flex()
+                    .items_center()
+                    .child(
+                        div()
+                            .w(px(OWNER_WIDTH))
+                            .flex_shrink_0()
+                            .truncate()
+                            .text_color(palette.text)
+                            .child(format!("{} {}", row + 1, owner)),
+                    )
+                    .children(matrix.cells[row].iter().map(|cell| {
+                        let cell_div = div()
+                            .w(px(CELL_WIDTH))
+                            .h(px(20.0))
+                            .flex_shrink_0()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .rounded(px(2.0));
+                        match cell {
+                            Some(cell) => cell_div
+                                .bg(self.kind_color(cell.kind).opacity(0.3))
+                                .text_color(self.kind_color(cell.kind))
+                                .child(cell.files.to_string()),
+                            None => cell_div.bg(palette.element_bg),
+                        }
+                    }))
+            }))
+    }
+}
+
+impl IntoElement for ConflictSection {
+    type Element = gpui::Div;
+
+    fn into_element(self) -> Self::Element {
+        let palette = self.palette;
+        let disclosure = if self.expanded { "▾" } else { "▸" };
+
+        let mut items: Vec<gpui::AnyElement> = Vec::new();
+        if self.report.conflicts.is_empty() {
+            items.push(
+                div()
+                    .text_color(palette.text_muted)
+                    .text_sm()
+                    .child("No hooks change the same files")
+                    .into_any_element(),
+            );
+        } else {
+            items.extend(self.report.conflicts.iter().map(|conflict| {
+                div()
+                    .flex()
+                    .gap(px(8.0))
+                    .text_sm()
+                    .child(
+                        div()
+                            .text_color(self.kind_color(conflict.kind))
+                            .child(format!("⚠ {}", conflict.kind.label())),
+                    )
+                    .child(div().text_color(palette.text).child(conflict.description()))
+                    .into_any_element()
+            }));
+            items.push(
+                self.render_matrix(&self.report.matrix())
+                    .pt(px(4.0))
+                    .into_any_element(),
+            );
+        }
+        items.extend(self.report.errors.iter().map(|(hook, error)| {
+            div()
+                .text_sm()
+                .text_color(palette.text_muted)
+                .child(format!("{}: {}", hook, error))
+                .into_any_element()
+        }));
+
+        let header = div()
+            .id("conflicts-header")
+            .flex()
+            .items_center()
+            .gap(px(4.0))
+            .text_color(if self.report.conflicts.is_empty() {
+                palette.text
+            } else {
+                palette.accent_warning
+            })
+            .pb(px(4.0))
+            .cursor_pointer()
+            .child(disclosure)
+            .child(format!("Conflicts ({})", self.report.conflicts.len()));
+
+        let header = if let Some(on_toggle) = self.on_toggle {
+            header.on_click(move |event, window, cx| on_toggle(event, window, cx))
+        } else {
+            header
+        };
+
+        let section = div()
+            .flex()
+            .flex_col()
+            .gap(px(8.0))
+            .p(px(12.0))
+            .rounded(px(6.0))
+            .bg(palette.panel_bg)
+            .border_1()
+            .border_color(palette.border_variant)
+            .child(header);
+
+        if self.expanded {
+            section.children(items)
+        } else {
+            section
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hook_conflicts::HookConflict;
+
+    fn test_palette() -> ConflictSectionPalette {
+        use gpui::rgb;
+        ConflictSectionPalette {
+            panel_bg: rgb(0x2f343e).into(),
+            border_variant: rgb(0x363c46).into(),
+            text: rgb(0xdce0e5).into(),
+            text_muted: rgb(0xa9afbc).into(),
+            accent_warning: rgb(0xdec184).into(),
+            accent_error: rgb(0xd07277).into(),
+            element_bg: rgb(0x2e343e).into(),
+        }
+    }
+
+    #[test]
+    fn test_conflict_section_creation() {
+        let report = ConflictReport {
+            conflicts: vec![HookConflict {
+                path: "src/main.rs".into(),
+                hooks: ("frontend/nux".into(), "frontend/toast".into()),
+                owners: ("nux".into(), "toast".into()),
+                kind: ConflictKind::OverlappingHunks,
+                rows: vec![2..4],
+            }],
+            ..Default::default()
+        };
+
+        let _section = ConflictSection::new(&report, test_palette()).expanded(false);
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let _section = ConflictSection::new(&ConflictReport::default(), test_palette());
+    }
+}
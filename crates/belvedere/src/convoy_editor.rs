@@ -0,0 +1,711 @@
+use anyhow::{Result, anyhow};
+use collections::BTreeSet;
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString, Subscription,
+    Task, Window, div, prelude::*, px,
+};
+use ui::{ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, Disableable, LabelSize};
+use ui_input::InputField;
+
+use crate::agent_inspector::ActionStatus;
+use crate::beads::IssueType;
+use crate::gt_cli::GtCli;
+use crate::town_item::{TownItem, TownItemEvent};
+use crate::town_model::{TownModel, TownModelEvent};
+
+/// A change to a convoy made through `gt convoy`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConvoyCommand {
+    /// `gt convoy create <name> [issues...] [--human]`
+    Create {
+        name: String,
+        issues: Vec<String>,
+        human: bool,
+    },
+    /// `gt convoy add-issue <issues...> --convoy <id>`
+    AddIssues { convoy: String, issues: Vec<String> },
+    /// `gt convoy remove-issue <issue> --convoy <id>`
+    RemoveIssue { convoy: String, issue: String },
+    /// `gt convoy update <id> --human` or `--auto`
+    SetHuman { convoy: String, human: bool },
+    /// `gt convoy refresh <id>`
+    Refresh { convoy: String },
+}
+
+impl ConvoyCommand {
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec!["convoy".to_string()];
+        match self {
+            ConvoyCommand::Create {
+                name,
+                issues,
+                human,
+            } => {
+                args.push("create".into());
+                args.push(name.clone());
+                args.extend(issues.iter().cloned());
+                if *human {
+                    args.push("--human".into());
+                }
+            }
+            ConvoyCommand::AddIssues { convoy, issues } => {
+                args.push("add-issue".into());
+                args.extend(issues.iter().cloned());
+                args.push("--convoy".into());
+                args.push(convoy.clone());
+            }
+            ConvoyCommand::RemoveIssue { convoy, issue } => {
+                args.push("remove-issue".into());
+                args.push(issue.clone());
+                args.push("--convoy".into());
+                args.push(convoy.clone());
+            }
+            ConvoyCommand::SetHuman { convoy, human } => {
+                args.push("update".into());
+                args.push(convoy.clone());
+                args.push(if *human { "--human" } else { "--auto" }.into());
+            }
+            ConvoyCommand::Refresh { convoy } => {
+                args.push("refresh".into());
+                args.push(convoy.clone());
+            }
+        }
+        args
+    }
+
+    /// What the command did, for the editor's status line
+    pub fn summary(&self) -> String {
+        match self {
+            ConvoyCommand::Create { name, issues, .. } => {
+                format!("Created convoy {} with {} issues", name, issues.len())
+            }
+            ConvoyCommand::AddIssues { convoy, issues } => {
+                format!("Added {} to {}", issues.join(", "), convoy)
+            }
+            ConvoyCommand::RemoveIssue { convoy, issue } => {
+                format!("Removed {} from {}", issue, convoy)
+            }
+            ConvoyCommand::SetHuman { convoy, human } => format!(
+                "{} is now {}",
+                convoy,
+                if *human {
+                    "managed by a human"
+                } else {
+                    "automatic"
+                }
+            ),
+            ConvoyCommand::Refresh { convoy } => format!("Refreshed {}", convoy),
+        }
+    }
+
+    pub fn run(&self, gt: &GtCli) -> Result<()> {
+        gt.run(self.args())
+            .map(|_| ())
+            .map_err(|err| anyhow!(err.to_string()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConvoyEditorEvent {
+    /// A `gt convoy` command changed a convoy
+    Changed,
+}
+
+/// Center pane item creating a convoy from picked issues, or editing an existing
+/// one's issues and human/auto flag
+pub struct ConvoyEditor {
+    /// `None` while creating a new convoy
+    convoy_id: Option<String>,
+    model: Entity<TownModel>,
+    gt: GtCli,
+    /// Created on first render, since input fields need a window
+    name: Option<Entity<InputField>>,
+    /// Issues picked to go into the convoy
+    selected: BTreeSet<String>,
+    /// The flag a new convoy is created with
+    human: bool,
+    action_status: Option<ActionStatus>,
+    action_task: Option<Task<()>>,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<TownItemEvent> for ConvoyEditor {}
+impl EventEmitter<ConvoyEditorEvent> for ConvoyEditor {}
+
+impl ConvoyEditor {
+    pub fn new(
+        convoy_id: Option<String>,
+        model: Entity<TownModel>,
+        gt: GtCli,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&model, |this, model, event: &TownModelEvent, cx| {
+            if matches!(
+                event,
+                TownModelEvent::ConvoysChanged | TownModelEvent::IssuesChanged
+            ) {
+                // Picked issues that joined the convoy since are no longer candidates
+                let candidates = this.candidates(model.read(cx));
+                this.selected.retain(|id| candidates.contains(id));
+                cx.notify();
+            }
+        });
+
+        Self {
+            convoy_id,
+            model,
+            gt,
+            name: None,
+            selected: BTreeSet::new(),
+            human: false,
+            action_status: None,
+            action_task: None,
+            focus_handle: cx.focus_handle(),
+            _subscription: subscription,
+        }
+    }
+
+    pub fn convoy_id(&self) -> Option<&str> {
+        self.convoy_id.as_deref()
+    }
+
+    pub fn selected(&self) -> &BTreeSet<String> {
+        &self.selected
+    }
+
+    pub fn action_status(&self) -> Option<&ActionStatus> {
+        self.action_status.as_ref()
+    }
+
+    /// Open issues that could join the convoy, by id
+    pub fn candidates(&self, model: &TownModel) -> BTreeSet<String> {
+        let convoy = self.convoy_id.as_deref().and_then(|id| model.convoy(id));
+        model
+            .issues()
+            .filter(|issue| issue.bead.issue_type != IssueType::Convoy && !issue.bead.is_closed())
+            .filter(|issue| !convoy.is_some_and(|convoy| convoy.has_issue(issue.id())))
+            .map(|issue| issue.id().to_string())
+            .collect()
+    }
+
+    pub fn toggle_issue(&mut self, issue_id: String, cx: &mut Context<Self>) {
+        if !self.selected.remove(&issue_id) {
+            self.selected.insert(issue_id);
+        }
+        cx.notify();
+    }
+
+    /// Sets whether a human manages the convoy, right away for an existing convoy
+    pub fn set_human(&mut self, human: bool, cx: &mut Context<Self>) {
+        match self.convoy_id.clone() {
+            Some(convoy) => self.run(ConvoyCommand::SetHuman { convoy, human }, cx),
+            None => {
+                self.human = human;
+                cx.notify();
+            }
+        }
+    }
+
+    /// Creates a convoy named `name` from the picked issues
+    pub fn create(&mut self, name: String, cx: &mut Context<Self>) {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            self.action_status = Some(ActionStatus::Failed("The convoy needs a name".into()));
+            cx.notify();
+            return;
+        }
+        let command = ConvoyCommand::Create {
+            name,
+            issues: self.selected.iter().cloned().collect(),
+            human: self.human,
+        };
+        self.run(command, cx);
+    }
+
+    /// Adds the picked issues to the convoy
+    pub fn add_selected(&mut self, cx: &mut Context<Self>) {
+        let Some(convoy) = self.convoy_id.clone() else {
+            return;
+        };
+        if self.selected.is_empty() {
+            return;
+        }
+        let issues = self.selected.iter().cloned().collect();
+        self.run(ConvoyCommand::AddIssues { convoy, issues }, cx);
+    }
+
+    pub fn remove_issue(&mut self, issue: String, cx: &mut Context<Self>) {
+        if let Some(convoy) = self.convoy_id.clone() {
+            self.run(ConvoyCommand::RemoveIssue { convoy, issue }, cx);
+        }
+    }
+
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        if let Some(convoy) = self.convoy_id.clone() {
+            self.run(ConvoyCommand::Refresh { convoy }, cx);
+        }
+    }
+
+    fn run(&mut self, command: ConvoyCommand, cx: &mut Context<Self>) {
+        let gt = self.gt.clone();
+        let run = {
+            let command = command.clone();
+            cx.background_spawn(async move { command.run(&gt) })
+        };
+
+        self.action_status = Some(ActionStatus::Running(format!(
+            "Running gt {}…",
+            command.args().join(" ")
+        )));
+        self.action_task = Some(cx.spawn(async move |this, cx| {
+            let result = run.await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(()) => {
+                        this.action_status = Some(ActionStatus::Succeeded(command.summary()));
+                        match command {
+                            ConvoyCommand::Create { .. } => {
+                                // Start the next convoy from an empty form
+                                this.name = None;
+                                this.selected.clear();
+                            }
+                            ConvoyCommand::AddIssues { .. } => this.selected.clear(),
+                            _ => {}
+                        }
+                        cx.emit(ConvoyEditorEvent::Changed);
+                    }
+                    Err(err) => {
+                        this.action_status = Some(ActionStatus::Failed(format!("{:#}", err)))
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn is_running(&self) -> bool {
+        matches!(self.action_status, Some(ActionStatus::Running(_)))
+    }
+
+    fn render_candidates(&self, cx: &Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let model = self.model.read(cx);
+        let candidates = self.candidates(model);
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(2.0))
+            .when(candidates.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_color(colors.text_muted)
+                        .child("No open issues to add"),
+                )
+            })
+            .children(candidates.into_iter().map(|id| {
+                let selected = self.selected.contains(&id);
+                let issue = model.issue(&id);
+                let title = issue
+                    .and_then(|issue| issue.bead.title())
+                    .unwrap_or("Untitled")
+                    .to_string();
+                let rig = issue.and_then(|issue| issue.rig.clone());
+                let toggle_id = id.clone();
+                div()
+                    .id(SharedString::from(format!("convoy-candidate-{}", id)))
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .px(px(6.0))
+                    .py(px(2.0))
+                    .rounded(px(4.0))
+                    .cursor_pointer()
+                    .when(selected, |this| this.bg(colors.element_selected))
+                    .hover(|style| style.bg(colors.element_hover))
+                    .child(div().text_color(colors.text_accent).child(if selected {
+                        "☑"
+                    } else {
+                        "☐"
+                    }))
+                    .child(div().text_color(colors.text_muted).child(id))
+                    .child(
+                        div()
+                            .flex_1()
+                            .truncate()
+                            .text_color(colors.text)
+                            .child(title),
+                    )
+                    .when_some(rig, |this, rig| {
+                        this.child(div().text_xs().text_color(colors.text_muted).child(rig))
+                    })
+                    .on_click(
+                        cx.listener(move |this, _, _, cx| this.toggle_issue(toggle_id.clone(), cx)),
+                    )
+            }))
+    }
+
+    fn render_members(&self, convoy_id: &str, cx: &Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let issues = self
+            .model
+            .read(cx)
+            .convoy(convoy_id)
+            .map(|convoy| convoy.issues.clone())
+            .unwrap_or_default();
+        let running = self.is_running();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(2.0))
+            .when(issues.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_color(colors.text_muted)
+                        .child("This convoy tracks no issues"),
+                )
+            })
+            .children(issues.into_iter().map(|issue| {
+                let issue_id = issue.id.clone();
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .px(px(6.0))
+                    .child(div().text_color(colors.text_muted).child(issue.id.clone()))
+                    .child(
+                        div()
+                            .flex_1()
+                            .truncate()
+                            .text_color(colors.text)
+                            .child(issue.title.clone().unwrap_or_else(|| "Untitled".into())),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(colors.text_muted)
+                            .child(issue.status.clone()),
+                    )
+                    .child(
+                        Button::new(SharedString::from(format!("remove-{}", issue.id)), "Remove")
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .disabled(running)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.remove_issue(issue_id.clone(), cx)
+                            })),
+                    )
+            }))
+    }
+
+    fn render_flag(&self, human: bool, cx: &Context<Self>) -> impl IntoElement {
+        let running = self.is_running();
+        let flag_button = |id: &'static str, label: &'static str, value: bool| {
+            Button::new(id, label)
+                .style(if human == value {
+                    ButtonStyle::Filled
+                } else {
+                    ButtonStyle::Subtle
+                })
+                .label_size(LabelSize::Small)
+                .disabled(running)
+                .on_click(cx.listener(move |this, _, _, cx| this.set_human(value, cx)))
+        };
+
+        div()
+            .flex()
+            .items_center()
+            .gap(px(4.0))
+            .child(
+                div()
+                    .text_color(cx.theme().colors().text_muted)
+                    .child("Managed by"),
+            )
+            .child(flag_button("convoy-auto", "Mayor (auto)", false))
+            .child(flag_button("convoy-human", "Human", true))
+    }
+
+    fn render_status(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let status = self.action_status.as_ref()?;
+        let (text, color) = match status {
+            ActionStatus::Running(text) => (text, cx.theme().colors().text_muted),
+            ActionStatus::Succeeded(text) => (text, cx.theme().status().success),
+            ActionStatus::Failed(text) => (text, cx.theme().status().error),
+        };
+        Some(div().text_color(color).child(text.clone()))
+    }
+}
+
+impl Focusable for ConvoyEditor {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TownItem for ConvoyEditor {
+    type Event = TownItemEvent;
+
+    fn tab_content_text(&self, _cx: &App) -> SharedString {
+        match &self.convoy_id {
+            Some(id) => format!("Edit Convoy {}", id).into(),
+            None => "New Convoy".into(),
+        }
+    }
+
+    fn to_town_item_events(event: &Self::Event, mut f: impl FnMut(TownItemEvent)) {
+        f(*event);
+    }
+}
+
+impl Render for ConvoyEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let running = self.is_running();
+        let picked = self.selected.len();
+
+        let container = div()
+            .id("convoy-editor")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .gap(px(8.0))
+            .p(px(16.0))
+            .overflow_y_scroll()
+            .text_sm();
+
+        let Some(convoy_id) = self.convoy_id.clone() else {
+            let name = self
+                .name
+                .get_or_insert_with(|| {
+                    cx.new(|cx| InputField::new(window, cx, "Convoy name").label("Name"))
+                })
+                .clone();
+            return container
+                .child(div().text_lg().text_color(colors.text).child("New Convoy"))
+                .child(name.clone())
+                .child(self.render_flag(self.human, cx))
+                .child(
+                    div()
+                        .pt(px(4.0))
+                        .text_color(colors.text_muted)
+                        .child("Issues"),
+                )
+                .child(self.render_candidates(cx))
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(8.0))
+                        .child(
+                            Button::new("create-convoy", "Create Convoy")
+                                .style(ButtonStyle::Filled)
+                                .label_size(LabelSize::Small)
+                                .disabled(running)
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    let name = name.read(cx).text(cx);
+                                    this.create(name, cx);
+                                })),
+                        )
+                        .child(
+                            div()
+                                .text_color(colors.text_muted)
+                                .child(format!("{} issues picked", picked)),
+                        ),
+                )
+                .children(self.render_status(cx));
+        };
+
+        let Some((title, owner, human)) = self.model.read(cx).convoy(&convoy_id).map(|convoy| {
+            (
+                convoy.display_name().to_string(),
+                convoy.owner.clone(),
+                convoy.human,
+            )
+        }) else {
+            return container
+                .text_color(colors.text_muted)
+                .child(format!("Convoy {} no longer exists", convoy_id))
+                .children(self.render_status(cx));
+        };
+
+        container
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .child(div().text_lg().text_color(colors.text).child(title))
+                    .child(div().text_color(colors.text_muted).child(convoy_id.clone()))
+                    .when_some(owner, |this, owner| {
+                        this.child(
+                            div()
+                                .text_color(colors.text_muted)
+                                .child(format!("owned by {}", owner)),
+                        )
+                    })
+                    .child(
+                        div().ml_auto().child(
+                            Button::new("refresh-convoy", "Refresh")
+                                .style(ButtonStyle::Subtle)
+                                .label_size(LabelSize::Small)
+                                .disabled(running)
+                                .on_click(cx.listener(|this, _, _, cx| this.refresh(cx))),
+                        ),
+                    ),
+            )
+            .child(self.render_flag(human, cx))
+            .children(self.render_status(cx))
+            .child(
+                div()
+                    .pt(px(4.0))
+                    .text_color(colors.text_muted)
+                    .child("Issues"),
+            )
+            .child(self.render_members(&convoy_id, cx))
+            .child(
+                div()
+                    .pt(px(4.0))
+                    .text_color(colors.text_muted)
+                    .child("Add Issues"),
+            )
+            .child(self.render_candidates(cx))
+            .child(
+                div().child(
+                    Button::new("add-convoy-issues", format!("Add {} Issues", picked))
+                        .style(ButtonStyle::Filled)
+                        .label_size(LabelSize::Small)
+                        .disabled(running || picked == 0)
+                        .on_click(cx.listener(|this, _, _, cx| this.add_selected(cx))),
+                ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_args() {
+        let args = |command: ConvoyCommand| command.args().join(" ");
+        assert_eq!(
+            args(ConvoyCommand::Create {
+                name: "Bug Fixes".into(),
+                issues: vec!["bug-101".into(), "bug-102".into()],
+                human: true,
+            }),
+            "convoy create Bug Fixes bug-101 bug-102 --human"
+        );
+        assert_eq!(
+            args(ConvoyCommand::AddIssues {
+                convoy: "hq-cv-1".into(),
+                issues: vec!["fe-3".into()],
+            }),
+            "convoy add-issue fe-3 --convoy hq-cv-1"
+        );
+        assert_eq!(
+            args(ConvoyCommand::RemoveIssue {
+                convoy: "hq-cv-1".into(),
+                issue: "fe-1".into(),
+            }),
+            "convoy remove-issue fe-1 --convoy hq-cv-1"
+        );
+        assert_eq!(
+            args(ConvoyCommand::SetHuman {
+                convoy: "hq-cv-1".into(),
+                human: false,
+            }),
+            "convoy update hq-cv-1 --auto"
+        );
+        assert_eq!(
+            args(ConvoyCommand::Refresh {
+                convoy: "hq-cv-1".into(),
+            }),
+            "convoy refresh hq-cv-1"
+        );
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    fn test_editor_adds_picked_issues(cx: &mut gpui::TestAppContext) {
+        use crate::dashboard_buffer::{ConvoyInfo, DashboardData};
+        use crate::fake_gt::FakeGt;
+        use crate::town_model::{ConvoyIssue, TownStructure};
+        use collections::HashMap;
+        use gpui::AppContext as _;
+
+        let gt = FakeGt::new()
+            .respond("convoy add-issue fe-2 --convoy hq-cv-1", "")
+            .fail("convoy refresh hq-cv-1", 1, "convoy hq-cv-1 is locked");
+        let model = cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(
+                TownStructure {
+                    convoy_issues: HashMap::from_iter([(
+                        "hq-cv-1".to_string(),
+                        vec![ConvoyIssue {
+                            id: "fe-1".into(),
+                            status: "open".into(),
+                            ..Default::default()
+                        }],
+                    )]),
+                    ..Default::default()
+                },
+                cx,
+            );
+            model.apply_dashboard_data(
+                DashboardData {
+                    convoys: vec![ConvoyInfo {
+                        id: "hq-cv-1".into(),
+                        name: "Auth rewrite".into(),
+                        issues: vec!["fe-1".into()],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            );
+            model
+        });
+        let editor = cx.new(|cx| ConvoyEditor::new(Some("hq-cv-1".into()), model, gt.cli(), cx));
+        let changes = std::rc::Rc::new(std::cell::Cell::new(0));
+        cx.update(|cx| {
+            let changes = changes.clone();
+            cx.subscribe(&editor, move |_, _: &ConvoyEditorEvent, _| {
+                changes.set(changes.get() + 1)
+            })
+            .detach();
+        });
+
+        editor.update(cx, |editor, cx| {
+            editor.toggle_issue("fe-2".into(), cx);
+            editor.add_selected(cx);
+        });
+        cx.run_until_parked();
+        editor.read_with(cx, |editor, _| {
+            assert_eq!(
+                editor.action_status(),
+                Some(&ActionStatus::Succeeded("Added fe-2 to hq-cv-1".into()))
+            );
+            assert!(editor.selected().is_empty());
+        });
+
+        editor.update(cx, |editor, cx| editor.refresh(cx));
+        cx.run_until_parked();
+        editor.read_with(cx, |editor, _| {
+            assert_eq!(
+                editor.action_status(),
+                Some(&ActionStatus::Failed(
+                    "`gt convoy refresh hq-cv-1` exited with status 1: convoy hq-cv-1 is locked"
+                        .into()
+                ))
+            );
+        });
+        assert_eq!(changes.get(), 1);
+    }
+}
@@ -7,12 +7,14 @@ use std::sync::Arc;
 use crate::dashboard_buffer::ConvoyInfo;
 
 type ToggleHandler = Arc<dyn Fn(&ClickEvent, &mut gpui::Window, &mut gpui::App) + 'static>;
+type SelectHandler = Arc<dyn Fn(&str, &mut gpui::Window, &mut gpui::App) + 'static>;
 
 pub struct ConvoySection {
     convoys: Vec<ConvoyInfo>,
     palette: ConvoySectionPalette,
     expanded: bool,
     on_toggle: Option<ToggleHandler>,
+    on_select: Option<SelectHandler>,
 }
 
 #[derive(Clone, Copy)]
@@ -32,6 +34,7 @@ impl ConvoySection {
             palette,
             expanded: true,
             on_toggle: None,
+            on_select: None,
         }
     }
 
@@ -47,6 +50,15 @@ impl ConvoySection {
         self.on_toggle = Some(Arc::new(on_toggle));
         self
     }
+
+    /// Called with the convoy id when a convoy row is clicked
+    pub fn on_select(
+        mut self,
+        on_select: impl Fn(&str, &mut gpui::Window, &mut gpui::App) + 'static,
+    ) -> Self {
+        self.on_select = Some(Arc::new(on_select));
+        self
+    }
 }
 
 impl IntoElement for ConvoySection {
@@ -67,7 +79,10 @@ impl IntoElement for ConvoySection {
         } else {
             self.convoys
                 .iter()
-                .map(|convoy| ConvoyRow::new(convoy.clone(), palette).into_any_element())
+                .map(|convoy| {
+                    ConvoyRow::new(convoy.clone(), palette, self.on_select.clone())
+                        .into_any_element()
+                })
                 .collect()
         };
 
@@ -110,16 +125,25 @@ impl IntoElement for ConvoySection {
 struct ConvoyRow {
     convoy: ConvoyInfo,
     palette: ConvoySectionPalette,
+    on_select: Option<SelectHandler>,
 }
 
 impl ConvoyRow {
-    fn new(convoy: ConvoyInfo, palette: ConvoySectionPalette) -> Self {
-        Self { convoy, palette }
+    fn new(
+        convoy: ConvoyInfo,
+        palette: ConvoySectionPalette,
+        on_select: Option<SelectHandler>,
+    ) -> Self {
+        Self {
+            convoy,
+            palette,
+            on_select,
+        }
     }
 }
 
 impl IntoElement for ConvoyRow {
-    type Element = gpui::Div;
+    type Element = gpui::Stateful<gpui::Div>;
 
     fn into_element(self) -> Self::Element {
         let palette = self.palette;
@@ -129,7 +153,18 @@ impl IntoElement for ConvoyRow {
         let bar_width = 200.0;
         let fill_width = bar_width * convoy.progress;
 
-        div()
+        let mut details = vec![format!(
+            "{} issue{}",
+            convoy.issues.len(),
+            if convoy.issues.len() == 1 { "" } else { "s" }
+        )];
+        details.extend(convoy.owner.clone());
+        details.push(if convoy.human { "human" } else { "auto" }.to_string());
+
+        let row = div()
+            .id(gpui::ElementId::Name(
+                format!("convoy-row-{}", convoy.id).into(),
+            ))
             .flex()
             .items_center()
             .gap(px(12.0))
@@ -138,7 +173,8 @@ impl IntoElement for ConvoyRow {
                 div()
                     .text_color(palette.text)
                     .w(px(120.0))
-                    .child(convoy.id.clone()),
+                    .truncate()
+                    .child(convoy.display_name().to_string()),
             )
             .child(
                 div()
@@ -161,6 +197,20 @@ impl IntoElement for ConvoyRow {
                     .w(px(40.0))
                     .child(format!("{}%", progress_percent)),
             )
+            .child(
+                div()
+                    .text_color(palette.text_muted)
+                    .text_sm()
+                    .child(details.join(" · ")),
+            );
+
+        if let Some(on_select) = self.on_select {
+            let id = convoy.id;
+            row.cursor_pointer()
+                .on_click(move |_, window, cx| on_select(&id, window, cx))
+        } else {
+            row
+        }
     }
 }
 
@@ -187,10 +237,12 @@ mod tests {
             ConvoyInfo {
                 id: "refactor-auth".to_string(),
                 progress: 0.65,
+                ..Default::default()
             },
             ConvoyInfo {
                 id: "migrate-db".to_string(),
                 progress: 0.30,
+                ..Default::default()
             },
         ];
 
@@ -211,14 +263,17 @@ mod tests {
             ConvoyInfo {
                 id: "full-progress".to_string(),
                 progress: 1.0,
+                ..Default::default()
             },
             ConvoyInfo {
                 id: "zero-progress".to_string(),
                 progress: 0.0,
+                ..Default::default()
             },
             ConvoyInfo {
                 id: "half-progress".to_string(),
                 progress: 0.5,
+                ..Default::default()
             },
         ];
 
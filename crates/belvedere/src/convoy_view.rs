@@ -0,0 +1,599 @@
+use collections::{HashMap, HashSet};
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString, Subscription,
+    Window, div, prelude::*, px,
+};
+use ui::{ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, LabelSize};
+
+use crate::hook_conflicts::{ConflictKind, HookConflict};
+use crate::kanban_board::DraggedIssue;
+use crate::town_item::{TownItem, TownItemEvent};
+use crate::town_model::{ConvoyIssue, TownModel, TownModelEvent};
+
+/// One member issue of a convoy, resolved against the rest of the town
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConvoyIssueRow {
+    pub issue: ConvoyIssue,
+    /// Id of the assignee's hook
+    pub hook: Option<String>,
+    pub on_critical_path: bool,
+    /// Whether the issue is on the critical path and holds up later issues on it
+    pub blocking: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimelineEventKind {
+    Slung,
+    Completed,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimelineEvent {
+    /// RFC 3339 timestamp
+    pub at: String,
+    pub issue: String,
+    pub kind: TimelineEventKind,
+    pub assignee: Option<String>,
+}
+
+/// Events emitted by a `ConvoyView` besides `TownItemEvent`s
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConvoyViewEvent {
+    /// Open the kanban board of this convoy's issues
+    OpenBoard(String),
+    /// Open the editor for this convoy's issues and flag
+    Edit(String),
+}
+
+/// Center pane item showing a convoy's member issues, dependencies and history
+pub struct ConvoyView {
+    convoy_id: String,
+    model: Entity<TownModel>,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<TownItemEvent> for ConvoyView {}
+impl EventEmitter<ConvoyViewEvent> for ConvoyView {}
+
+impl ConvoyView {
+    pub fn new(convoy_id: String, model: Entity<TownModel>, cx: &mut Context<Self>) -> Self {
+        let subscription = cx.subscribe(&model, |_, _, event: &TownModelEvent, cx| {
+            if matches!(
+                event,
+                TownModelEvent::ConvoysChanged
+                    | TownModelEvent::HooksChanged
+                    | TownModelEvent::AgentsChanged
+                    | TownModelEvent::ConflictsChanged
+            ) {
+                cx.notify();
+            }
+        });
+
+        Self {
+            convoy_id,
+            model,
+            focus_handle: cx.focus_handle(),
+            _subscription: subscription,
+        }
+    }
+
+    pub fn convoy_id(&self) -> &str {
+        &self.convoy_id
+    }
+
+    /// The convoy's issues in their tracked order
+    pub fn rows(&self, cx: &App) -> Vec<ConvoyIssueRow> {
+        let model = self.model.read(cx);
+        let Some(convoy) = model.convoy(&self.convoy_id) else {
+            return Vec::new();
+        };
+
+        let path = critical_path(&convoy.issues);
+        let blockers: HashSet<&str> = path
+            .iter()
+            .take(path.len().saturating_sub(1))
+            .map(String::as_str)
+            .collect();
+
+        convoy
+            .issues
+            .iter()
+            .map(|issue| ConvoyIssueRow {
+                issue: issue.clone(),
+                hook: issue
+                    .assignee
+                    .as_deref()
+                    .and_then(|assignee| model.hook_for_agent(assignee))
+                    .map(|hook| hook.id()),
+                on_critical_path: path.contains(&issue.id),
+                blocking: blockers.contains(issue.id.as_str()),
+            })
+            .collect()
+    }
+
+    /// Conflicts between the hooks of this convoy's assignees and any other hook
+    pub fn conflicts(&self, cx: &App) -> Vec<HookConflict> {
+        let hooks: HashSet<String> = self
+            .rows(cx)
+            .into_iter()
+            .filter_map(|row| row.hook)
+            .collect();
+        self.model
+            .read(cx)
+            .conflicts()
+            .for_hooks(&hooks)
+            .cloned()
+            .collect()
+    }
+
+    pub fn timeline(&self, cx: &App) -> Vec<TimelineEvent> {
+        self.model
+            .read(cx)
+            .convoy(&self.convoy_id)
+            .map(|convoy| timeline(&convoy.issues))
+            .unwrap_or_default()
+    }
+
+    fn render_issue(&self, row: &ConvoyIssueRow, cx: &App) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+        let issue = &row.issue;
+
+        let (icon, icon_color) = match issue.status.as_str() {
+            "closed" => ("✓", status.success),
+            "in_progress" | "hooked" => ("●", status.info),
+            "blocked" => ("✗", status.error),
+            _ => ("○", colors.text_muted),
+        };
+
+        let detail = |text: String| div().text_xs().text_color(colors.text_muted).child(text);
+        let dragged = DraggedIssue {
+            id: issue.id.clone(),
+            title: issue.title.clone().unwrap_or_default(),
+        };
+
+        div()
+            .id(SharedString::from(format!("convoy-issue-{}", issue.id)))
+            .flex()
+            .flex_col()
+            .gap(px(2.0))
+            .p(px(6.0))
+            .cursor_grab()
+            .on_drag(dragged, |dragged, _, _, cx| cx.new(|_| dragged.clone()))
+            .rounded(px(4.0))
+            .border_1()
+            .border_color(if row.blocking {
+                status.warning
+            } else {
+                colors.border_variant
+            })
+            .when(row.blocking, |this| this.bg(status.warning_background))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(6.0))
+                    .child(div().text_color(icon_color).child(icon))
+                    .child(div().text_color(colors.text).child(issue.id.clone()))
+                    .when_some(issue.title.clone(), |this, title| {
+                        this.child(div().text_color(colors.text).child(title))
+                    })
+                    .child(
+                        div()
+                            .ml_auto()
+                            .text_xs()
+                            .text_color(colors.text_muted)
+                            .child(issue.status.clone()),
+                    )
+                    .when(row.blocking, |this| {
+                        this.child(div().text_xs().text_color(status.warning).child("blocking"))
+                    }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap(px(12.0))
+                    .child(detail(format!(
+                        "Assignee: {}",
+                        issue.assignee.as_deref().unwrap_or("unassigned")
+                    )))
+                    .child(detail(format!(
+                        "Hook: {}",
+                        row.hook.as_deref().unwrap_or("none")
+                    )))
+                    .when(!issue.depends_on.is_empty(), |this| {
+                        this.child(detail(format!(
+                            "Depends on: {}",
+                            issue.depends_on.join(", ")
+                        )))
+                    }),
+            )
+    }
+}
+
+/// The longest chain of open issues linked by dependencies within the convoy.
+///
+/// Listed from the issue to finish first to the issue that finishes last. Issues
+/// outside the convoy and closed issues don't count towards the chain.
+pub fn critical_path(issues: &[ConvoyIssue]) -> Vec<String> {
+    let open: HashMap<&str, &ConvoyIssue> = issues
+        .iter()
+        .filter(|issue| !issue.is_closed())
+        .map(|issue| (issue.id.as_str(), issue))
+        .collect();
+
+    fn chain<'a>(
+        id: &'a str,
+        open: &HashMap<&'a str, &'a ConvoyIssue>,
+        memo: &mut HashMap<&'a str, Vec<&'a str>>,
+        visiting: &mut HashSet<&'a str>,
+    ) -> Vec<&'a str> {
+        if let Some(chain) = memo.get(id) {
+            return chain.clone();
+        }
+        if !visiting.insert(id) {
+            // Dependency cycle; stop here rather than recursing forever
+            return Vec::new();
+        }
+
+        let mut longest: Vec<&'a str> = Vec::new();
+        for dependency in &open[&id].depends_on {
+            if let Some((&dependency, _)) = open.get_key_value(dependency.as_str()) {
+                let candidate = chain(dependency, open, memo, visiting);
+                if candidate.len() > longest.len() {
+                    longest = candidate;
+                }
+            }
+        }
+        longest.push(id);
+
+        visiting.remove(id);
+        memo.insert(id, longest.clone());
+        longest
+    }
+
+    let mut memo = HashMap::default();
+    let mut visiting = HashSet::default();
+    let mut longest: Vec<&str> = Vec::new();
+    for issue in issues.iter().filter(|issue| !issue.is_closed()) {
+        let candidate = chain(issue.id.as_str(), &open, &mut memo, &mut visiting);
+        if candidate.len() > longest.len() {
+            longest = candidate;
+        }
+    }
+
+    longest.into_iter().map(str::to_string).collect()
+}
+
+/// When each issue was slung and completed, oldest first
+pub fn timeline(issues: &[ConvoyIssue]) -> Vec<TimelineEvent> {
+    let mut events = Vec::new();
+    for issue in issues {
+        if let Some(at) = &issue.slung_at {
+            events.push(TimelineEvent {
+                at: at.clone(),
+                issue: issue.id.clone(),
+                kind: TimelineEventKind::Slung,
+                assignee: issue.assignee.clone(),
+            });
+        }
+        if let Some(at) = &issue.completed_at {
+            events.push(TimelineEvent {
+                at: at.clone(),
+                issue: issue.id.clone(),
+                kind: TimelineEventKind::Completed,
+                assignee: issue.assignee.clone(),
+            });
+        }
+    }
+    events.sort_by(|a, b| a.at.cmp(&b.at));
+    events
+}
+
+/// Shortens `2026-01-05T09:00:00Z` to `2026-01-05 09:00`
+fn format_timestamp(timestamp: &str) -> String {
+    timestamp
+        .get(..16)
+        .unwrap_or(timestamp)
+        .replacen('T', " ", 1)
+}
+
+impl Focusable for ConvoyView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TownItem for ConvoyView {
+    type Event = TownItemEvent;
+
+    fn tab_content_text(&self, _cx: &App) -> SharedString {
+        format!("Convoy {}", self.convoy_id).into()
+    }
+
+    fn to_town_item_events(event: &Self::Event, mut f: impl FnMut(TownItemEvent)) {
+        f(*event);
+    }
+}
+
+impl Render for ConvoyView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let container = div()
+            .id("convoy-view")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .gap(px(8.0))
+            .p(px(16.0))
+            .overflow_y_scroll()
+            .text_sm();
+
+        let Some((progress, critical_path)) = self
+            .model
+            .read(cx)
+            .convoy(&self.convoy_id)
+            .map(|convoy| (convoy.progress, critical_path(&convoy.issues)))
+        else {
+            return container
+                .text_color(colors.text_muted)
+                .child(format!("Convoy {} no longer exists", self.convoy_id));
+        };
+
+        let rows = self.rows(cx);
+        let timeline = self.timeline(cx);
+        let conflicts = self.conflicts(cx);
+
+        container
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .child(
+                        div()
+                            .text_lg()
+                            .text_color(colors.text)
+                            .child(format!("Convoy {}", self.convoy_id)),
+                    )
+                    .child(
+                        div()
+                            .text_color(colors.text_muted)
+                            .child(format!("{}% complete", (progress * 100.0).round())),
+                    )
+                    .child(
+                        div()
+                            .ml_auto()
+                            .flex()
+                            .gap(px(4.0))
+                            .child(
+                                Button::new("convoy-edit", "Edit")
+                                    .style(ButtonStyle::Subtle)
+                                    .label_size(LabelSize::Small)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        cx.emit(ConvoyViewEvent::Edit(this.convoy_id.clone()));
+                                    })),
+                            )
+                            .child(
+                                Button::new("convoy-open-board", "Open Board")
+                                    .style(ButtonStyle::Subtle)
+                                    .label_size(LabelSize::Small)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        cx.emit(ConvoyViewEvent::OpenBoard(this.convoy_id.clone()));
+                                    })),
+                            ),
+                    ),
+            )
+            .when(critical_path.len() > 1, |this| {
+                this.child(
+                    div()
+                        .text_color(cx.theme().status().warning)
+                        .child(format!("Critical path: {}", critical_path.join(" → "))),
+                )
+            })
+            .children(conflicts.into_iter().map(|conflict| {
+                div()
+                    .text_color(match conflict.kind {
+                        ConflictKind::SameFile => cx.theme().status().warning,
+                        ConflictKind::OverlappingHunks => cx.theme().status().error,
+                    })
+                    .child(format!("⚠ Conflict: {}", conflict.description()))
+            }))
+            .child(
+                div()
+                    .pt(px(4.0))
+                    .text_color(colors.text_muted)
+                    .child("Issues"),
+            )
+            .when(rows.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_color(colors.text_muted)
+                        .child("This convoy tracks no issues"),
+                )
+            })
+            .children(rows.iter().map(|row| self.render_issue(row, cx)))
+            .child(
+                div()
+                    .pt(px(4.0))
+                    .text_color(colors.text_muted)
+                    .child("Timeline"),
+            )
+            .when(timeline.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_color(colors.text_muted)
+                        .child("Nothing has been slung yet"),
+                )
+            })
+            .children(timeline.into_iter().map(|event| {
+                let verb = match event.kind {
+                    TimelineEventKind::Slung => "slung",
+                    TimelineEventKind::Completed => "completed",
+                };
+                let assignee = event
+                    .assignee
+                    .map(|assignee| format!(" → {}", assignee))
+                    .unwrap_or_default();
+                div()
+                    .flex()
+                    .gap(px(8.0))
+                    .child(
+                        div()
+                            .text_color(colors.text_muted)
+                            .child(format_timestamp(&event.at)),
+                    )
+                    .child(
+                        div()
+                            .text_color(colors.text)
+                            .child(format!("{} {}{}", event.issue, verb, assignee)),
+                    )
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_discovery::AgentDirectory;
+    use crate::dashboard_buffer::{ConvoyInfo, DashboardData};
+    use crate::hook_conflicts::ConflictReport;
+    use crate::town_model::{Hook, TownStructure};
+    use gpui::{AppContext as _, TestAppContext};
+
+    fn issue(id: &str, status: &str, depends_on: &[&str]) -> ConvoyIssue {
+        ConvoyIssue {
+            id: id.into(),
+            status: status.into(),
+            depends_on: depends_on.iter().map(|id| id.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_critical_path_follows_longest_open_chain() {
+        let issues = vec![
+            issue("a", "closed", &[]),
+            issue("b", "open", &["a"]),
+            issue("c", "in_progress", &["b"]),
+            issue("d", "open", &["c", "x"]),
+            issue("e", "open", &["b"]),
+        ];
+
+        assert_eq!(critical_path(&issues), vec!["b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_critical_path_survives_cycles() {
+        let issues = vec![issue("a", "open", &["b"]), issue("b", "open", &["a"])];
+
+        assert_eq!(critical_path(&issues).len(), 2);
+    }
+
+    #[test]
+    fn test_timeline_is_sorted() {
+        let issues = vec![
+            ConvoyIssue {
+                slung_at: Some("2026-01-05T12:00:00Z".into()),
+                ..issue("b", "in_progress", &[])
+            },
+            ConvoyIssue {
+                slung_at: Some("2026-01-05T09:00:00Z".into()),
+                completed_at: Some("2026-01-05T11:30:00Z".into()),
+                ..issue("a", "closed", &[])
+            },
+        ];
+
+        let events: Vec<(String, TimelineEventKind)> = timeline(&issues)
+            .into_iter()
+            .map(|event| (event.issue, event.kind))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                ("a".to_string(), TimelineEventKind::Slung),
+                ("a".to_string(), TimelineEventKind::Completed),
+                ("b".to_string(), TimelineEventKind::Slung),
+            ]
+        );
+        assert_eq!(format_timestamp("2026-01-05T09:00:00Z"), "2026-01-05 09:00");
+    }
+
+    #[gpui::test]
+    fn test_rows_resolve_hooks_and_blockers(cx: &mut TestAppContext) {
+        let structure = TownStructure {
+            agents: vec![
+                AgentDirectory::from_rig_member("/gt/frontend/polecats/toast".into()).unwrap(),
+            ],
+            hooks: vec![Hook {
+                name: "toast".into(),
+                rig: "frontend".into(),
+                path: "/gt/frontend/hooks/toast".into(),
+                agent: None,
+            }],
+            convoy_issues: HashMap::from_iter([(
+                "hq-cv-1".to_string(),
+                vec![
+                    ConvoyIssue {
+                        assignee: Some("frontend/polecats/toast".into()),
+                        ..issue("fe-1", "in_progress", &[])
+                    },
+                    issue("fe-2", "open", &["fe-1"]),
+                    issue("fe-3", "open", &[]),
+                ],
+            )]),
+            ..Default::default()
+        };
+        let data = DashboardData {
+            convoys: vec![ConvoyInfo {
+                id: "hq-cv-1".into(),
+                progress: 0.0,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let model = cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(structure, cx);
+            model.apply_dashboard_data(data, cx);
+            model
+        });
+        let conflict = |hooks: (&str, &str)| HookConflict {
+            path: "src/main.rs".into(),
+            hooks: (hooks.0.into(), hooks.1.into()),
+            owners: (hooks.0.into(), hooks.1.into()),
+            kind: ConflictKind::SameFile,
+            rows: Vec::new(),
+        };
+        model.update(cx, |model, cx| {
+            model.set_conflicts(
+                ConflictReport {
+                    conflicts: vec![
+                        conflict(("frontend/nux", "frontend/slit")),
+                        conflict(("frontend/nux", "frontend/toast")),
+                    ],
+                    ..Default::default()
+                },
+                cx,
+            )
+        });
+        let view = cx.new(|cx| ConvoyView::new("hq-cv-1".into(), model, cx));
+
+        view.read_with(cx, |view, cx| {
+            let rows = view.rows(cx);
+            assert_eq!(rows.len(), 3);
+            assert_eq!(rows[0].hook.as_deref(), Some("frontend/toast"));
+            assert!(rows[0].blocking);
+            assert!(rows[1].on_critical_path);
+            assert!(!rows[1].blocking);
+            assert!(!rows[2].on_critical_path);
+
+            assert_eq!(
+                view.conflicts(cx),
+                vec![conflict(("frontend/nux", "frontend/toast"))]
+            );
+        });
+    }
+}
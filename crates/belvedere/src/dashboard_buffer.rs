@@ -1,15 +1,33 @@
 #![allow(dead_code)]
 
+use collections::{HashMap, HashSet};
+use fs::Fs;
+use futures::StreamExt as _;
 use gpui::{
-    AnyElement, App, Context, EventEmitter, FocusHandle, Focusable, Hsla, IntoElement,
-    ParentElement, Render, Styled, Window, div, px, rgb,
+    AnyElement, App, AppContext as _, Context, EventEmitter, FocusHandle, Focusable, Hsla,
+    IntoElement, ParentElement, Render, Styled, Task, Window, div, prelude::FluentBuilder as _, px,
+    rgb,
 };
+use serde::Serialize;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::agent_section::{AgentSection, AgentSectionPalette};
+use crate::conflict_section::{ConflictSection, ConflictSectionPalette};
 use crate::convoy_section::{ConvoySection, ConvoySectionPalette};
+use crate::hook_conflicts::ConflictReport;
 use crate::rig_section::{RigSection, RigSectionPalette};
 
+/// Default interval between background refreshes
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long to wait for file changes to settle before refreshing
+pub const REFRESH_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Latency passed to the file watcher
+const WATCH_LATENCY: Duration = Duration::from_millis(100);
+
 /// Dashboard color palette matching Zed's One Dark theme.
 /// Values from: assets/themes/one/one.json
 struct DashboardPalette {
@@ -70,6 +88,18 @@ impl DashboardPalette {
         }
     }
 
+    fn to_conflict_section_palette(&self) -> ConflictSectionPalette {
+        ConflictSectionPalette {
+            panel_bg: self.panel_bg,
+            border_variant: self.border_variant,
+            text: self.text,
+            text_muted: self.text_muted,
+            accent_warning: self.accent_warning,
+            accent_error: self.accent_error,
+            element_bg: self.element_bg,
+        }
+    }
+
     fn to_rig_section_palette(&self) -> RigSectionPalette {
         RigSectionPalette {
             panel_bg: self.panel_bg,
@@ -94,17 +124,110 @@ pub enum DashboardEvent {
     AgentRemoved(String),
     /// An agent's status changed
     AgentStatusChanged { name: String, status: AgentStatus },
+    /// A convoy was added
+    ConvoyAdded(String),
+    /// A convoy was removed
+    ConvoyRemoved(String),
+    /// A convoy's progress changed
+    ConvoyProgressChanged { id: String, progress: f32 },
+    /// A rig was added
+    RigAdded(String),
+    /// A rig was removed
+    RigRemoved(String),
+    /// A convoy row was clicked and the convoy should be opened
+    OpenConvoy(String),
 }
 
 /// Dashboard data returned by any data source
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct DashboardData {
     pub agents: Vec<AgentInfo>,
     pub convoys: Vec<ConvoyInfo>,
     pub rigs: Vec<RigInfo>,
 }
 
-#[derive(Clone, Debug)]
+impl DashboardData {
+    /// Returns the events describing how `newer` differs from this snapshot.
+    ///
+    /// Additions and changes are reported in `newer`'s order, removals in this
+    /// snapshot's order.
+    pub fn diff(&self, newer: &DashboardData) -> Vec<DashboardEvent> {
+        let mut events = Vec::new();
+
+        let old_agents: HashMap<&str, &AgentInfo> = self
+            .agents
+            .iter()
+            .map(|agent| (agent.name.as_str(), agent))
+            .collect();
+        let new_agents: HashSet<&str> = newer
+            .agents
+            .iter()
+            .map(|agent| agent.name.as_str())
+            .collect();
+        for agent in &newer.agents {
+            match old_agents.get(agent.name.as_str()) {
+                None => events.push(DashboardEvent::AgentAdded(agent.name.clone())),
+                Some(old) if old.status != agent.status => {
+                    events.push(DashboardEvent::AgentStatusChanged {
+                        name: agent.name.clone(),
+                        status: agent.status.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for agent in &self.agents {
+            if !new_agents.contains(agent.name.as_str()) {
+                events.push(DashboardEvent::AgentRemoved(agent.name.clone()));
+            }
+        }
+
+        let old_convoys: HashMap<&str, &ConvoyInfo> = self
+            .convoys
+            .iter()
+            .map(|convoy| (convoy.id.as_str(), convoy))
+            .collect();
+        let new_convoys: HashSet<&str> = newer
+            .convoys
+            .iter()
+            .map(|convoy| convoy.id.as_str())
+            .collect();
+        for convoy in &newer.convoys {
+            match old_convoys.get(convoy.id.as_str()) {
+                None => events.push(DashboardEvent::ConvoyAdded(convoy.id.clone())),
+                Some(old) if (old.progress - convoy.progress).abs() > f32::EPSILON => {
+                    events.push(DashboardEvent::ConvoyProgressChanged {
+                        id: convoy.id.clone(),
+                        progress: convoy.progress,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for convoy in &self.convoys {
+            if !new_convoys.contains(convoy.id.as_str()) {
+                events.push(DashboardEvent::ConvoyRemoved(convoy.id.clone()));
+            }
+        }
+
+        let old_rigs: HashSet<&str> = self.rigs.iter().map(|rig| rig.name.as_str()).collect();
+        let new_rigs: HashSet<&str> = newer.rigs.iter().map(|rig| rig.name.as_str()).collect();
+        for rig in &newer.rigs {
+            if !old_rigs.contains(rig.name.as_str()) {
+                events.push(DashboardEvent::RigAdded(rig.name.clone()));
+            }
+        }
+        for rig in &self.rigs {
+            if !new_rigs.contains(rig.name.as_str()) {
+                events.push(DashboardEvent::RigRemoved(rig.name.clone()));
+            }
+        }
+
+        events
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
 pub struct AgentInfo {
     pub name: String,
     pub status: AgentStatus,
@@ -112,26 +235,46 @@ pub struct AgentInfo {
     pub context_fill: Option<f32>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 pub struct TokenUsage {
     pub input_tokens: u64,
     pub output_tokens: u64,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AgentStatus {
     Active,
     Idle,
     Error(String),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct ConvoyInfo {
     pub id: String,
+    /// The name given to `gt convoy create`
+    pub name: String,
     pub progress: f32,
+    /// Ids of the issues the convoy tracks
+    pub issues: Vec<String>,
+    /// Who created the convoy, e.g. `mayor` or a human's name
+    pub owner: Option<String>,
+    /// Whether a human manages the convoy instead of the Mayor
+    pub human: bool,
 }
 
-#[derive(Clone, Debug)]
+impl ConvoyInfo {
+    pub fn display_name(&self) -> &str {
+        convoy_display_name(&self.id, &self.name)
+    }
+}
+
+/// A convoy's name, or its id when it has none
+pub(crate) fn convoy_display_name<'a>(id: &'a str, name: &'a str) -> &'a str {
+    if name.is_empty() { id } else { name }
+}
+
+#[derive(Clone, Debug, Serialize)]
 pub struct RigInfo {
     pub name: String,
     pub path: String,
@@ -139,8 +282,18 @@ pub struct RigInfo {
 
 /// Trait for fetching dashboard data - implementations can be direct, mock, or HTTP
 pub trait DashboardDataSource: Send + Sync {
+    /// Fails with `NotAvailable` when the town can't be reached at all
     fn fetch(&self) -> Result<DashboardData, DashboardError>;
+    /// A quick probe for callers that don't need the data
     fn is_available(&self) -> bool;
+
+    /// Directories whose changes should trigger a refresh
+    ///
+    /// Called on a background thread after each fetch, so the set can grow as the
+    /// source discovers new rigs and agents.
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -160,25 +313,53 @@ impl std::fmt::Display for DashboardError {
     }
 }
 
+/// ANSI escape codes used by `DashboardFormatter::format_ansi`
+pub(crate) mod ansi {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const DIM: &str = "\x1b[2m";
+    pub const RED: &str = "\x1b[31m";
+    pub const GREEN: &str = "\x1b[32m";
+}
+
 /// Formats dashboard data for display
 pub struct DashboardFormatter;
 
 impl DashboardFormatter {
     pub fn format(data: &DashboardData) -> String {
+        Self::format_styled(data, false)
+    }
+
+    /// Formats like `format`, with ANSI colors for terminals. Status icons use the
+    /// dashboard's success, muted and error colors.
+    pub fn format_ansi(data: &DashboardData) -> String {
+        Self::format_styled(data, true)
+    }
+
+    fn format_styled(data: &DashboardData, color: bool) -> String {
+        let paint = |style: &str, text: &str| {
+            if color {
+                format!("{}{}{}", style, text, ansi::RESET)
+            } else {
+                text.to_string()
+            }
+        };
         let mut output = String::new();
 
-        output.push_str("═══ Gastown Dashboard ═══\n\n");
+        output.push_str(&paint(ansi::BOLD, "═══ Gastown Dashboard ═══"));
+        output.push_str("\n\n");
 
         // Agents section
-        output.push_str("▸ Agents\n");
+        output.push_str(&paint(ansi::BOLD, "▸ Agents"));
+        output.push('\n');
         if data.agents.is_empty() {
             output.push_str("  No agents running\n");
         } else {
             for agent in &data.agents {
                 let status_icon = match &agent.status {
-                    AgentStatus::Active => "●",
-                    AgentStatus::Idle => "○",
-                    AgentStatus::Error(_) => "✗",
+                    AgentStatus::Active => paint(ansi::GREEN, "●"),
+                    AgentStatus::Idle => paint(ansi::DIM, "○"),
+                    AgentStatus::Error(_) => paint(ansi::RED, "✗"),
                 };
                 let mut line = format!("  {} {}", status_icon, agent.name);
 
@@ -198,7 +379,8 @@ impl DashboardFormatter {
         output.push('\n');
 
         // Convoys section
-        output.push_str("▸ Convoys\n");
+        output.push_str(&paint(ansi::BOLD, "▸ Convoys"));
+        output.push('\n');
         if data.convoys.is_empty() {
             output.push_str("  No active convoys\n");
         } else {
@@ -215,7 +397,8 @@ impl DashboardFormatter {
         output.push('\n');
 
         // Rigs section
-        output.push_str("▸ Rigs\n");
+        output.push_str(&paint(ansi::BOLD, "▸ Rigs"));
+        output.push('\n');
         if data.rigs.is_empty() {
             output.push_str("  No rigs configured\n");
         } else {
@@ -250,16 +433,40 @@ impl DashboardFormatter {
 
 /// View for displaying dashboard data.
 /// This is a pure GPUI view - not a workspace Item.
+///
+/// Data is fetched on the background executor. Refreshes can be triggered explicitly,
+/// by polling (`set_refresh_interval`) or by file changes (`watch`); all background
+/// work is owned by the view and cancelled when it is dropped.
 pub struct DashboardView {
     focus_handle: FocusHandle,
     data: Option<DashboardData>,
     error: Option<DashboardError>,
     data_source: Arc<dyn DashboardDataSource>,
-    last_update: Option<std::time::Instant>,
+    last_update: Option<Instant>,
+    /// Set when a refresh failed while older data is still displayed
+    stale_since: Option<Instant>,
     connection_status: ConnectionStatus,
+    /// Hooks changing the same files, as last analyzed by the town
+    conflicts: ConflictReport,
     agents_expanded: bool,
+    conflicts_expanded: bool,
     convoys_expanded: bool,
     rigs_expanded: bool,
+    refreshing: bool,
+    refresh_queued: bool,
+    fs: Option<Arc<dyn Fs>>,
+    watched_paths: Vec<PathBuf>,
+    refresh_task: Option<Task<()>>,
+    debounce_task: Option<Task<()>>,
+    poll_task: Option<Task<()>>,
+    watch_task: Option<Task<()>>,
+}
+
+/// Result of a background fetch
+struct FetchOutcome {
+    available: bool,
+    result: Result<DashboardData, DashboardError>,
+    watch_paths: Vec<PathBuf>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -270,19 +477,30 @@ pub enum ConnectionStatus {
 }
 
 impl DashboardView {
-    pub fn new(data_source: Arc<dyn DashboardDataSource>, cx: &mut App) -> Self {
+    pub fn new(data_source: Arc<dyn DashboardDataSource>, cx: &mut Context<Self>) -> Self {
         let mut view = Self {
             focus_handle: cx.focus_handle(),
             data: None,
             error: None,
             data_source,
             last_update: None,
+            stale_since: None,
             connection_status: ConnectionStatus::Unknown,
+            conflicts: ConflictReport::default(),
             agents_expanded: true,
+            conflicts_expanded: true,
             convoys_expanded: true,
             rigs_expanded: true,
+            refreshing: false,
+            refresh_queued: false,
+            fs: None,
+            watched_paths: Vec::new(),
+            refresh_task: None,
+            debounce_task: None,
+            poll_task: None,
+            watch_task: None,
         };
-        view.refresh_sync();
+        view.refresh(cx);
         view
     }
 
@@ -296,6 +514,28 @@ impl DashboardView {
         cx.notify();
     }
 
+    /// Asks the town to open the convoy with the given id
+    pub fn open_convoy(&mut self, id: &str, cx: &mut Context<Self>) {
+        cx.emit(DashboardEvent::OpenConvoy(id.to_string()));
+    }
+
+    pub fn toggle_conflicts_section(&mut self, cx: &mut Context<Self>) {
+        self.conflicts_expanded = !self.conflicts_expanded;
+        cx.notify();
+    }
+
+    pub fn conflicts(&self) -> &ConflictReport {
+        &self.conflicts
+    }
+
+    /// Replaces the hook conflicts shown as warnings above the agents
+    pub fn set_conflicts(&mut self, conflicts: ConflictReport, cx: &mut Context<Self>) {
+        if self.conflicts != conflicts {
+            self.conflicts = conflicts;
+            cx.notify();
+        }
+    }
+
     pub fn toggle_rigs_section(&mut self, cx: &mut Context<Self>) {
         self.rigs_expanded = !self.rigs_expanded;
         cx.notify();
@@ -309,6 +549,16 @@ impl DashboardView {
         }
     }
 
+    /// The most recently fetched data, kept while later refreshes fail
+    pub fn data(&self) -> Option<&DashboardData> {
+        self.data.as_ref()
+    }
+
+    /// The error of the last refresh, if it failed
+    pub fn error(&self) -> Option<&DashboardError> {
+        self.error.as_ref()
+    }
+
     pub fn is_read_only(&self) -> bool {
         true
     }
@@ -317,34 +567,188 @@ impl DashboardView {
         &self.connection_status
     }
 
-    pub fn last_update(&self) -> Option<std::time::Instant> {
+    pub fn last_update(&self) -> Option<Instant> {
         self.last_update
     }
 
+    pub fn is_refreshing(&self) -> bool {
+        self.refreshing
+    }
+
+    /// When the last successful update was, if the displayed data is out of date
+    pub fn stale_since(&self) -> Option<Instant> {
+        self.stale_since
+    }
+
+    pub fn watched_paths(&self) -> &[PathBuf] {
+        &self.watched_paths
+    }
+
+    /// Starts a background refresh, superseding any refresh already in flight
     pub fn refresh(&mut self, cx: &mut Context<Self>) {
-        self.refresh_sync();
-        cx.emit(DashboardEvent::DataRefreshed);
+        let data_source = self.data_source.clone();
+        let fetch = cx.background_spawn(async move {
+            let result = data_source.fetch();
+            // Sources report an unreachable town from `fetch`, which saves asking twice
+            let available = !matches!(result, Err(DashboardError::NotAvailable));
+            let watch_paths = data_source.watch_paths();
+            FetchOutcome {
+                available,
+                result,
+                watch_paths,
+            }
+        });
+
+        self.refreshing = true;
+        self.refresh_queued = false;
+        self.refresh_task = Some(cx.spawn(async move |this, cx| {
+            let outcome = fetch.await;
+            this.update(cx, |this, cx| this.apply_fetch_outcome(outcome, cx))
+                .ok();
+        }));
         cx.notify();
     }
 
-    fn refresh_sync(&mut self) {
-        self.connection_status = if self.data_source.is_available() {
+    /// Refreshes every `interval` until cleared with `None` or the view is dropped
+    pub fn set_refresh_interval(&mut self, interval: Option<Duration>, cx: &mut Context<Self>) {
+        self.poll_task = interval.map(|interval| {
+            cx.spawn(async move |this, cx| {
+                loop {
+                    cx.background_executor().timer(interval).await;
+                    if this
+                        .update(cx, |this, cx| this.request_refresh(cx))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            })
+        });
+    }
+
+    /// Refreshes when anything under the data source's watch paths changes
+    pub fn watch(&mut self, fs: Arc<dyn Fs>, cx: &mut Context<Self>) {
+        self.fs = Some(fs);
+        self.restart_watcher(cx);
+    }
+
+    /// Refreshes after `REFRESH_DEBOUNCE`, coalescing bursts of change notifications
+    fn schedule_refresh(&mut self, cx: &mut Context<Self>) {
+        self.debounce_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            this.update(cx, |this, cx| this.request_refresh(cx)).ok();
+        }));
+    }
+
+    /// Refreshes now, or right after the refresh that is already in flight
+    fn request_refresh(&mut self, cx: &mut Context<Self>) {
+        if self.refreshing {
+            self.refresh_queued = true;
+        } else {
+            self.refresh(cx);
+        }
+    }
+
+    fn apply_fetch_outcome(&mut self, outcome: FetchOutcome, cx: &mut Context<Self>) {
+        self.refreshing = false;
+        let connection_status = if outcome.available {
             ConnectionStatus::Connected
         } else {
             ConnectionStatus::Disconnected
         };
+        if connection_status != self.connection_status {
+            self.connection_status = connection_status.clone();
+            cx.emit(DashboardEvent::ConnectionChanged(connection_status));
+        }
 
-        match self.data_source.fetch() {
+        match outcome.result {
             Ok(data) => {
+                let changes = self
+                    .data
+                    .as_ref()
+                    .unwrap_or(&DashboardData::default())
+                    .diff(&data);
+                for event in changes {
+                    cx.emit(event);
+                }
                 self.data = Some(data);
                 self.error = None;
-                self.last_update = Some(std::time::Instant::now());
+                self.last_update = Some(Instant::now());
+                self.stale_since = None;
             }
             Err(err) => {
-                self.data = None;
+                if self.data.is_some() {
+                    self.stale_since = self.stale_since.or(self.last_update);
+                }
                 self.error = Some(err);
             }
         }
+
+        if outcome.watch_paths != self.watched_paths {
+            self.watched_paths = outcome.watch_paths;
+            self.restart_watcher(cx);
+        }
+
+        cx.emit(DashboardEvent::DataRefreshed);
+        cx.notify();
+
+        if self.refresh_queued {
+            self.refresh(cx);
+        }
+    }
+
+    fn restart_watcher(&mut self, cx: &mut Context<Self>) {
+        let Some(fs) = self.fs.clone() else {
+            return;
+        };
+        if self.watched_paths.is_empty() {
+            self.watch_task = None;
+            return;
+        }
+
+        let paths = self.watched_paths.clone();
+        self.watch_task = Some(cx.spawn(async move |this, cx| {
+            watch_for_changes(fs, paths, || {
+                this.update(cx, |this, cx| this.schedule_refresh(cx))
+            })
+            .await;
+        }));
+    }
+}
+
+/// Calls `on_change` for every batch of changes under `paths`, until it fails
+/// because whoever was watching is gone
+pub(crate) async fn watch_for_changes<E>(
+    fs: Arc<dyn Fs>,
+    paths: Vec<PathBuf>,
+    mut on_change: impl FnMut() -> Result<(), E>,
+) {
+    let mut streams = Vec::with_capacity(paths.len());
+    let mut watchers = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let (events, watcher) = fs.watch(path, WATCH_LATENCY).await;
+        streams.push(events);
+        watchers.push(watcher);
+    }
+
+    let mut events = futures::stream::select_all(streams);
+    while events.next().await.is_some() {
+        if on_change().is_err() {
+            break;
+        }
+    }
+    drop(watchers);
+}
+
+/// Formats a duration coarsely, e.g. "3s", "5m", "2h"
+pub(crate) fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 60 * 60 {
+        format!("{}m", seconds / 60)
+    } else {
+        format!("{}h", seconds / (60 * 60))
     }
 }
 
@@ -392,6 +796,9 @@ impl DashboardView {
             .p(px(16.0))
             .gap(px(16.0))
             .child(self.render_header(palette))
+            .when(!self.conflicts.is_empty(), |this| {
+                this.child(self.render_conflicts_section(palette, cx))
+            })
             .child(self.render_agents_section(&data.agents, palette, cx))
             .child(self.render_convoys_section(&data.convoys, palette, cx))
             .child(self.render_rigs_section(&data.rigs, palette, cx))
@@ -448,7 +855,34 @@ impl DashboardView {
                     .text_color(palette.text)
                     .child("Gastown Dashboard"),
             )
-            .child(self.render_connection_status(palette))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(12.0))
+                    .child(self.render_freshness(palette))
+                    .child(self.render_connection_status(palette)),
+            )
+    }
+
+    fn render_freshness(&self, palette: &DashboardPalette) -> impl IntoElement {
+        let (color, label) = if self.refreshing {
+            (palette.accent_info, "↻ Refreshing…".to_string())
+        } else if let Some(stale_since) = self.stale_since {
+            (
+                palette.accent_warning,
+                format!("Stale since {} ago", format_elapsed(stale_since.elapsed())),
+            )
+        } else if let Some(last_update) = self.last_update {
+            (
+                palette.text_muted,
+                format!("Updated {} ago", format_elapsed(last_update.elapsed())),
+            )
+        } else {
+            (palette.text_muted, String::new())
+        };
+
+        div().text_sm().text_color(color).child(label)
     }
 
     fn render_connection_status(&self, palette: &DashboardPalette) -> impl IntoElement {
@@ -474,6 +908,18 @@ impl DashboardView {
             }))
     }
 
+    fn render_conflicts_section(
+        &self,
+        palette: &DashboardPalette,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        ConflictSection::new(&self.conflicts, palette.to_conflict_section_palette())
+            .expanded(self.conflicts_expanded)
+            .on_toggle(cx.listener(|this, _event, _window, cx| {
+                this.toggle_conflicts_section(cx);
+            }))
+    }
+
     fn render_convoys_section(
         &self,
         convoys: &[ConvoyInfo],
@@ -485,6 +931,12 @@ impl DashboardView {
             .on_toggle(cx.listener(|this, _event, _window, cx| {
                 this.toggle_convoys_section(cx);
             }))
+            .on_select({
+                let this = cx.entity().downgrade();
+                move |id, _window, cx| {
+                    this.update(cx, |this, cx| this.open_convoy(id, cx)).ok();
+                }
+            })
     }
 
     fn render_rigs_section(
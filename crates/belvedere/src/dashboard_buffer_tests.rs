@@ -1,10 +1,14 @@
+use fs::{FakeFs, PathEventKind};
 use gpui::{AppContext as _, TestAppContext, VisualTestContext};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::dashboard_buffer::{
     AgentInfo, AgentStatus, ConnectionStatus, ConvoyInfo, DashboardData, DashboardDataSource,
-    DashboardError, DashboardEvent, DashboardFormatter, DashboardView, RigInfo, TokenUsage,
+    DashboardError, DashboardEvent, DashboardFormatter, DashboardView, REFRESH_DEBOUNCE, RigInfo,
+    TokenUsage,
 };
 
 /// Mock data source for testing
@@ -33,6 +37,9 @@ impl MockDataSource {
 pub struct ReactiveMockDataSource {
     data: Mutex<Option<DashboardData>>,
     available: Mutex<bool>,
+    watch_paths: Mutex<Vec<PathBuf>>,
+    fetch_count: AtomicUsize,
+    availability_checks: AtomicUsize,
 }
 
 impl ReactiveMockDataSource {
@@ -40,9 +47,24 @@ impl ReactiveMockDataSource {
         Self {
             data: Mutex::new(Some(data)),
             available: Mutex::new(true),
+            watch_paths: Mutex::new(Vec::new()),
+            fetch_count: AtomicUsize::new(0),
+            availability_checks: AtomicUsize::new(0),
         }
     }
 
+    pub fn set_watch_paths(&self, paths: Vec<PathBuf>) {
+        *self.watch_paths.lock().unwrap() = paths;
+    }
+
+    pub fn fetch_count(&self) -> usize {
+        self.fetch_count.load(Ordering::SeqCst)
+    }
+
+    pub fn availability_checks(&self) -> usize {
+        self.availability_checks.load(Ordering::SeqCst)
+    }
+
     pub fn update_data(&self, data: DashboardData) {
         *self.data.lock().unwrap() = Some(data);
     }
@@ -58,6 +80,7 @@ impl ReactiveMockDataSource {
 
 impl DashboardDataSource for ReactiveMockDataSource {
     fn fetch(&self) -> Result<DashboardData, DashboardError> {
+        self.fetch_count.fetch_add(1, Ordering::SeqCst);
         if !*self.available.lock().unwrap() {
             return Err(DashboardError::NotAvailable);
         }
@@ -69,8 +92,13 @@ impl DashboardDataSource for ReactiveMockDataSource {
     }
 
     fn is_available(&self) -> bool {
+        self.availability_checks.fetch_add(1, Ordering::SeqCst);
         *self.available.lock().unwrap()
     }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        self.watch_paths.lock().unwrap().clone()
+    }
 }
 
 impl DashboardDataSource for MockDataSource {
@@ -107,6 +135,7 @@ fn sample_dashboard_data() -> DashboardData {
         convoys: vec![ConvoyInfo {
             id: "convoy-1".into(),
             progress: 0.5,
+            ..Default::default()
         }],
         rigs: vec![RigInfo {
             name: "main".into(),
@@ -119,6 +148,7 @@ fn sample_dashboard_data() -> DashboardData {
 async fn test_dashboard_displays_content(cx: &mut TestAppContext) {
     let data_source = Arc::new(MockDataSource::available_with(sample_dashboard_data()));
     let view = cx.new(|cx| DashboardView::new(data_source, cx));
+    cx.run_until_parked();
 
     view.update(cx, |view: &mut DashboardView, _cx| {
         let content = view.content();
@@ -133,6 +163,7 @@ async fn test_dashboard_displays_content(cx: &mut TestAppContext) {
 async fn test_dashboard_is_read_only(cx: &mut TestAppContext) {
     let data_source = Arc::new(MockDataSource::available_with(DashboardData::default()));
     let view = cx.new(|cx| DashboardView::new(data_source, cx));
+    cx.run_until_parked();
 
     view.update(cx, |view: &mut DashboardView, _cx| {
         assert!(view.is_read_only(), "Dashboard should be read-only");
@@ -143,24 +174,25 @@ async fn test_dashboard_is_read_only(cx: &mut TestAppContext) {
 async fn test_dashboard_refresh_updates_timestamp(cx: &mut TestAppContext) {
     let data_source = Arc::new(MockDataSource::available_with(DashboardData::default()));
     let view = cx.new(|cx| DashboardView::new(data_source, cx));
+    cx.run_until_parked();
 
-    view.update(cx, |view: &mut DashboardView, cx| {
-        let first_update = view.last_update();
-        assert!(first_update.is_some());
+    let first_update = view.read_with(cx, |view, _cx| view.last_update());
+    assert!(first_update.is_some());
 
-        std::thread::sleep(std::time::Duration::from_millis(10));
-        view.refresh(cx);
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    view.update(cx, |view: &mut DashboardView, cx| view.refresh(cx));
+    cx.run_until_parked();
 
-        let second_update = view.last_update();
-        assert!(second_update.is_some());
-        assert!(second_update.unwrap() > first_update.unwrap());
-    });
+    let second_update = view.read_with(cx, |view, _cx| view.last_update());
+    assert!(second_update.is_some());
+    assert!(second_update.unwrap() > first_update.unwrap());
 }
 
 #[gpui::test]
 async fn test_dashboard_shows_error_when_unavailable(cx: &mut TestAppContext) {
     let data_source = Arc::new(MockDataSource::unavailable());
     let view = cx.new(|cx| DashboardView::new(data_source, cx));
+    cx.run_until_parked();
 
     view.update(cx, |view: &mut DashboardView, _cx| {
         assert_eq!(view.connection_status(), &ConnectionStatus::Disconnected);
@@ -173,6 +205,7 @@ async fn test_dashboard_shows_error_when_unavailable(cx: &mut TestAppContext) {
 async fn test_dashboard_shows_connected_status(cx: &mut TestAppContext) {
     let data_source = Arc::new(MockDataSource::available_with(DashboardData::default()));
     let view = cx.new(|cx| DashboardView::new(data_source, cx));
+    cx.run_until_parked();
 
     view.update(cx, |view: &mut DashboardView, _cx| {
         assert_eq!(view.connection_status(), &ConnectionStatus::Connected);
@@ -230,10 +263,12 @@ async fn test_dashboard_formatter_shows_convoy_progress(_cx: &mut TestAppContext
             ConvoyInfo {
                 id: "convoy-half".into(),
                 progress: 0.5,
+                ..Default::default()
             },
             ConvoyInfo {
                 id: "convoy-done".into(),
                 progress: 1.0,
+                ..Default::default()
             },
         ],
         ..Default::default()
@@ -330,6 +365,7 @@ async fn test_gpui_render_produces_element(cx: &mut TestAppContext) {
     });
 
     let mut cx = VisualTestContext::from_window(window.into(), cx);
+    cx.run_until_parked();
 
     window
         .root(&mut cx)
@@ -378,6 +414,7 @@ async fn test_gpui_view_is_focusable(cx: &mut TestAppContext) {
 async fn test_refresh_emits_data_refreshed_event(cx: &mut TestAppContext) {
     let data_source = Arc::new(MockDataSource::available_with(sample_dashboard_data()));
     let view = cx.new(|cx| DashboardView::new(data_source, cx));
+    cx.run_until_parked();
 
     let events: Arc<Mutex<Vec<DashboardEvent>>> = Arc::new(Mutex::new(Vec::new()));
     let events_clone = events.clone();
@@ -392,6 +429,7 @@ async fn test_refresh_emits_data_refreshed_event(cx: &mut TestAppContext) {
     view.update(cx, |view, cx| {
         view.refresh(cx);
     });
+    cx.run_until_parked();
 
     let captured_events = events.lock().unwrap();
     assert_eq!(captured_events.len(), 1, "Should emit exactly one event");
@@ -415,6 +453,7 @@ async fn test_reactive_data_updates_content(cx: &mut TestAppContext) {
 
     let data_source = Arc::new(ReactiveMockDataSource::new(initial_data));
     let view = cx.new(|cx| DashboardView::new(data_source.clone(), cx));
+    cx.run_until_parked();
 
     view.update(cx, |view, _cx| {
         assert!(view.content().contains("initial-agent"));
@@ -435,6 +474,7 @@ async fn test_reactive_data_updates_content(cx: &mut TestAppContext) {
     view.update(cx, |view, cx| {
         view.refresh(cx);
     });
+    cx.run_until_parked();
 
     view.update(cx, |view, _cx| {
         assert!(
@@ -453,6 +493,7 @@ async fn test_connection_status_transitions(cx: &mut TestAppContext) {
     let initial_data = sample_dashboard_data();
     let data_source = Arc::new(ReactiveMockDataSource::new(initial_data));
     let view = cx.new(|cx| DashboardView::new(data_source.clone(), cx));
+    cx.run_until_parked();
 
     view.update(cx, |view, _cx| {
         assert_eq!(
@@ -466,6 +507,7 @@ async fn test_connection_status_transitions(cx: &mut TestAppContext) {
     view.update(cx, |view, cx| {
         view.refresh(cx);
     });
+    cx.run_until_parked();
 
     view.update(cx, |view, _cx| {
         assert_eq!(
@@ -474,11 +516,14 @@ async fn test_connection_status_transitions(cx: &mut TestAppContext) {
             "Should transition to disconnected"
         );
     });
+    // Availability comes from the fetch itself
+    assert_eq!(data_source.availability_checks(), 0);
 
     data_source.set_available();
     view.update(cx, |view, cx| {
         view.refresh(cx);
     });
+    cx.run_until_parked();
 
     view.update(cx, |view, _cx| {
         assert_eq!(
@@ -489,6 +534,341 @@ async fn test_connection_status_transitions(cx: &mut TestAppContext) {
     });
 }
 
+// ============================================================================
+// Granular Event Tests
+// ============================================================================
+
+fn agent(name: &str, status: AgentStatus) -> AgentInfo {
+    AgentInfo {
+        name: name.into(),
+        status,
+        token_usage: None,
+        context_fill: None,
+    }
+}
+
+fn record_events(
+    view: &gpui::Entity<DashboardView>,
+    cx: &mut TestAppContext,
+) -> Arc<Mutex<Vec<DashboardEvent>>> {
+    let events: Arc<Mutex<Vec<DashboardEvent>>> = Arc::new(Mutex::new(Vec::new()));
+    let events_clone = events.clone();
+    cx.update(|cx| {
+        cx.subscribe(view, move |_, event: &DashboardEvent, _cx| {
+            if !matches!(event, DashboardEvent::DataRefreshed) {
+                events_clone.lock().unwrap().push(event.clone());
+            }
+        })
+        .detach();
+    });
+    events
+}
+
+fn refresh_with(
+    view: &gpui::Entity<DashboardView>,
+    data_source: &ReactiveMockDataSource,
+    data: DashboardData,
+    cx: &mut TestAppContext,
+) {
+    data_source.update_data(data);
+    view.update(cx, |view, cx| view.refresh(cx));
+    cx.run_until_parked();
+}
+
+#[gpui::test]
+async fn test_agent_add_remove_and_status_events(cx: &mut TestAppContext) {
+    let data_source = Arc::new(ReactiveMockDataSource::new(DashboardData {
+        agents: vec![agent("toast", AgentStatus::Idle)],
+        ..Default::default()
+    }));
+    let view = cx.new(|cx| DashboardView::new(data_source.clone(), cx));
+    cx.run_until_parked();
+    let events = record_events(&view, cx);
+
+    refresh_with(
+        &view,
+        &data_source,
+        DashboardData {
+            agents: vec![
+                agent("toast", AgentStatus::Active),
+                agent("nux", AgentStatus::Idle),
+            ],
+            ..Default::default()
+        },
+        cx,
+    );
+    refresh_with(
+        &view,
+        &data_source,
+        DashboardData {
+            agents: vec![agent("nux", AgentStatus::Error("crashed".into()))],
+            ..Default::default()
+        },
+        cx,
+    );
+    refresh_with(
+        &view,
+        &data_source,
+        DashboardData {
+            agents: vec![agent("nux", AgentStatus::Error("crashed".into()))],
+            ..Default::default()
+        },
+        cx,
+    );
+
+    let events = events.lock().unwrap();
+    let descriptions: Vec<String> = events.iter().map(|event| format!("{:?}", event)).collect();
+    assert_eq!(
+        descriptions,
+        vec![
+            r#"AgentStatusChanged { name: "toast", status: Active }"#,
+            r#"AgentAdded("nux")"#,
+            r#"AgentStatusChanged { name: "nux", status: Error("crashed") }"#,
+            r#"AgentRemoved("toast")"#,
+        ],
+        "an unchanged snapshot should emit nothing"
+    );
+}
+
+#[gpui::test]
+async fn test_convoy_and_rig_events(cx: &mut TestAppContext) {
+    let data_source = Arc::new(ReactiveMockDataSource::new(sample_dashboard_data()));
+    let view = cx.new(|cx| DashboardView::new(data_source.clone(), cx));
+    cx.run_until_parked();
+    let events = record_events(&view, cx);
+
+    let mut data = sample_dashboard_data();
+    data.convoys = vec![
+        ConvoyInfo {
+            id: "convoy-1".into(),
+            progress: 0.75,
+            ..Default::default()
+        },
+        ConvoyInfo {
+            id: "convoy-2".into(),
+            progress: 0.0,
+            ..Default::default()
+        },
+    ];
+    data.rigs = vec![RigInfo {
+        name: "docs".into(),
+        path: "/docs".into(),
+    }];
+    refresh_with(&view, &data_source, data, cx);
+
+    refresh_with(
+        &view,
+        &data_source,
+        DashboardData {
+            convoys: vec![],
+            ..sample_dashboard_data()
+        },
+        cx,
+    );
+
+    let events = events.lock().unwrap();
+    let descriptions: Vec<String> = events.iter().map(|event| format!("{:?}", event)).collect();
+    assert_eq!(
+        descriptions,
+        vec![
+            r#"ConvoyProgressChanged { id: "convoy-1", progress: 0.75 }"#,
+            r#"ConvoyAdded("convoy-2")"#,
+            r#"RigAdded("docs")"#,
+            r#"RigRemoved("main")"#,
+            r#"ConvoyRemoved("convoy-1")"#,
+            r#"ConvoyRemoved("convoy-2")"#,
+            r#"RigAdded("main")"#,
+            r#"RigRemoved("docs")"#,
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_connection_changed_events(cx: &mut TestAppContext) {
+    let data_source = Arc::new(ReactiveMockDataSource::new(sample_dashboard_data()));
+    let view = cx.new(|cx| DashboardView::new(data_source.clone(), cx));
+    cx.run_until_parked();
+    let events = record_events(&view, cx);
+
+    data_source.set_unavailable();
+    view.update(cx, |view, cx| view.refresh(cx));
+    cx.run_until_parked();
+    view.update(cx, |view, cx| view.refresh(cx));
+    cx.run_until_parked();
+    data_source.set_available();
+    view.update(cx, |view, cx| view.refresh(cx));
+    cx.run_until_parked();
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 2, "only transitions should be reported");
+    assert!(matches!(
+        events[0],
+        DashboardEvent::ConnectionChanged(ConnectionStatus::Disconnected)
+    ));
+    assert!(matches!(
+        events[1],
+        DashboardEvent::ConnectionChanged(ConnectionStatus::Connected)
+    ));
+}
+
+#[gpui::test]
+async fn test_diff_from_empty_reports_everything_added(_cx: &mut TestAppContext) {
+    let events = DashboardData::default().diff(&sample_dashboard_data());
+
+    let descriptions: Vec<String> = events.iter().map(|event| format!("{:?}", event)).collect();
+    assert_eq!(
+        descriptions,
+        vec![
+            r#"AgentAdded("agent-1")"#,
+            r#"AgentAdded("agent-2")"#,
+            r#"ConvoyAdded("convoy-1")"#,
+            r#"RigAdded("main")"#,
+        ]
+    );
+}
+
+// ============================================================================
+// Background Refresh Tests
+// ============================================================================
+
+#[gpui::test]
+async fn test_refresh_runs_in_background(cx: &mut TestAppContext) {
+    let data_source = Arc::new(ReactiveMockDataSource::new(sample_dashboard_data()));
+    let view = cx.new(|cx| DashboardView::new(data_source.clone(), cx));
+
+    view.update(cx, |view, _cx| {
+        assert!(view.is_refreshing());
+        assert_eq!(view.content(), "Loading...");
+        assert_eq!(
+            data_source.fetch_count(),
+            0,
+            "fetch must not block creation"
+        );
+    });
+
+    cx.run_until_parked();
+
+    view.update(cx, |view, _cx| {
+        assert!(!view.is_refreshing());
+        assert!(view.content().contains("agent-1"));
+        assert_eq!(data_source.fetch_count(), 1);
+    });
+}
+
+#[gpui::test]
+async fn test_polling_refreshes_on_interval(cx: &mut TestAppContext) {
+    let data_source = Arc::new(ReactiveMockDataSource::new(sample_dashboard_data()));
+    let view = cx.new(|cx| {
+        let mut view = DashboardView::new(data_source.clone(), cx);
+        view.set_refresh_interval(Some(Duration::from_secs(5)), cx);
+        view
+    });
+    cx.run_until_parked();
+    assert_eq!(data_source.fetch_count(), 1);
+
+    cx.executor().advance_clock(Duration::from_secs(5));
+    cx.run_until_parked();
+    assert_eq!(data_source.fetch_count(), 2);
+
+    cx.executor().advance_clock(Duration::from_secs(10));
+    cx.run_until_parked();
+    assert_eq!(data_source.fetch_count(), 4);
+
+    view.update(cx, |view, cx| view.set_refresh_interval(None, cx));
+    cx.executor().advance_clock(Duration::from_secs(30));
+    cx.run_until_parked();
+    assert_eq!(data_source.fetch_count(), 4, "polling should stop");
+}
+
+#[gpui::test]
+async fn test_dropping_view_cancels_polling(cx: &mut TestAppContext) {
+    let data_source = Arc::new(ReactiveMockDataSource::new(sample_dashboard_data()));
+    let view = cx.new(|cx| {
+        let mut view = DashboardView::new(data_source.clone(), cx);
+        view.set_refresh_interval(Some(Duration::from_secs(1)), cx);
+        view
+    });
+    cx.run_until_parked();
+    assert_eq!(data_source.fetch_count(), 1);
+
+    cx.update(|_| drop(view));
+    cx.executor().advance_clock(Duration::from_secs(10));
+    cx.run_until_parked();
+
+    assert_eq!(data_source.fetch_count(), 1);
+}
+
+#[gpui::test]
+async fn test_file_changes_trigger_debounced_refresh(cx: &mut TestAppContext) {
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/gt", serde_json::json!({ "frontend": { "crew": {} } }))
+        .await;
+
+    let data_source = Arc::new(ReactiveMockDataSource::new(sample_dashboard_data()));
+    data_source.set_watch_paths(vec![PathBuf::from("/gt/frontend")]);
+
+    let view = cx.new(|cx| {
+        let mut view = DashboardView::new(data_source.clone(), cx);
+        view.watch(fs.clone(), cx);
+        view
+    });
+    cx.run_until_parked();
+    assert_eq!(data_source.fetch_count(), 1);
+    view.read_with(cx, |view, _cx| {
+        assert_eq!(view.watched_paths(), &[PathBuf::from("/gt/frontend")]);
+    });
+
+    for _ in 0..5 {
+        fs.emit_fs_event("/gt/frontend/crew/alice", Some(PathEventKind::Created));
+        cx.executor().advance_clock(REFRESH_DEBOUNCE / 5);
+        cx.run_until_parked();
+    }
+    assert_eq!(
+        data_source.fetch_count(),
+        1,
+        "refresh should wait for changes to settle"
+    );
+
+    cx.executor().advance_clock(REFRESH_DEBOUNCE);
+    cx.run_until_parked();
+    assert_eq!(data_source.fetch_count(), 2, "a burst should refresh once");
+
+    fs.emit_fs_event("/gt/other/file", Some(PathEventKind::Changed));
+    cx.executor().advance_clock(REFRESH_DEBOUNCE * 2);
+    cx.run_until_parked();
+    assert_eq!(data_source.fetch_count(), 2, "unwatched paths are ignored");
+}
+
+#[gpui::test]
+async fn test_failed_refresh_keeps_stale_data(cx: &mut TestAppContext) {
+    let data_source = Arc::new(ReactiveMockDataSource::new(sample_dashboard_data()));
+    let view = cx.new(|cx| DashboardView::new(data_source.clone(), cx));
+    cx.run_until_parked();
+
+    let last_update = view.read_with(cx, |view, _cx| {
+        assert_eq!(view.stale_since(), None);
+        view.last_update()
+    });
+
+    data_source.set_unavailable();
+    view.update(cx, |view, cx| view.refresh(cx));
+    cx.run_until_parked();
+
+    view.read_with(cx, |view, _cx| {
+        assert_eq!(view.stale_since(), last_update);
+        assert!(
+            view.content().contains("agent-1"),
+            "last known data should stay visible"
+        );
+    });
+
+    data_source.set_available();
+    view.update(cx, |view, cx| view.refresh(cx));
+    cx.run_until_parked();
+
+    view.read_with(cx, |view, _cx| assert_eq!(view.stale_since(), None));
+}
+
 // ============================================================================
 // Performance Tests
 // ============================================================================
@@ -534,6 +914,7 @@ async fn test_gpui_format_performance_large_dataset(_cx: &mut TestAppContext) {
             .map(|i| ConvoyInfo {
                 id: format!("convoy-{}", i),
                 progress: (i as f32) / 50.0,
+                ..Default::default()
             })
             .collect(),
         rigs: (0..20)
@@ -561,12 +942,14 @@ async fn test_gpui_format_performance_large_dataset(_cx: &mut TestAppContext) {
 async fn test_gpui_refresh_performance(cx: &mut TestAppContext) {
     let data_source = Arc::new(MockDataSource::available_with(sample_dashboard_data()));
     let view = cx.new(|cx| DashboardView::new(data_source, cx));
+    cx.run_until_parked();
 
     let start = Instant::now();
     for _ in 0..100 {
         view.update(cx, |view, cx| {
             view.refresh(cx);
         });
+        cx.run_until_parked();
     }
     let elapsed = start.elapsed();
 
@@ -654,14 +1037,17 @@ async fn test_progress_bar_rendering(_cx: &mut TestAppContext) {
             ConvoyInfo {
                 id: "empty".into(),
                 progress: 0.0,
+                ..Default::default()
             },
             ConvoyInfo {
                 id: "half".into(),
                 progress: 0.5,
+                ..Default::default()
             },
             ConvoyInfo {
                 id: "full".into(),
                 progress: 1.0,
+                ..Default::default()
             },
         ],
         ..Default::default()
@@ -745,6 +1131,7 @@ async fn test_data_binding_agents(cx: &mut TestAppContext) {
 
     let data_source = Arc::new(MockDataSource::available_with(data));
     let view = cx.new(|cx| DashboardView::new(data_source, cx));
+    cx.run_until_parked();
 
     view.update(cx, |view, _cx| {
         let content = view.content();
@@ -764,10 +1151,12 @@ async fn test_data_binding_convoys(cx: &mut TestAppContext) {
             ConvoyInfo {
                 id: "task-001".into(),
                 progress: 0.25,
+                ..Default::default()
             },
             ConvoyInfo {
                 id: "task-002".into(),
                 progress: 0.75,
+                ..Default::default()
             },
         ],
         rigs: vec![],
@@ -775,6 +1164,7 @@ async fn test_data_binding_convoys(cx: &mut TestAppContext) {
 
     let data_source = Arc::new(MockDataSource::available_with(data));
     let view = cx.new(|cx| DashboardView::new(data_source, cx));
+    cx.run_until_parked();
 
     view.update(cx, |view, _cx| {
         let content = view.content();
@@ -805,6 +1195,7 @@ async fn test_data_binding_rigs(cx: &mut TestAppContext) {
 
     let data_source = Arc::new(MockDataSource::available_with(data));
     let view = cx.new(|cx| DashboardView::new(data_source, cx));
+    cx.run_until_parked();
 
     view.update(cx, |view, _cx| {
         let content = view.content();
@@ -832,6 +1223,7 @@ async fn test_data_binding_preserves_special_characters(cx: &mut TestAppContext)
 
     let data_source = Arc::new(MockDataSource::available_with(data));
     let view = cx.new(|cx| DashboardView::new(data_source, cx));
+    cx.run_until_parked();
 
     view.update(cx, |view, _cx| {
         let content = view.content();
@@ -856,6 +1248,7 @@ async fn test_empty_sections_display_placeholder(cx: &mut TestAppContext) {
     let data = DashboardData::default();
     let data_source = Arc::new(MockDataSource::available_with(data));
     let view = cx.new(|cx| DashboardView::new(data_source, cx));
+    cx.run_until_parked();
 
     view.update(cx, |view, _cx| {
         let content = view.content();
@@ -888,6 +1281,7 @@ async fn test_error_display_parse_error(_cx: &mut TestAppContext) {
 async fn test_multiple_rapid_refreshes(cx: &mut TestAppContext) {
     let data_source = Arc::new(ReactiveMockDataSource::new(sample_dashboard_data()));
     let view = cx.new(|cx| DashboardView::new(data_source.clone(), cx));
+    cx.run_until_parked();
 
     for i in 0..50 {
         let new_data = DashboardData {
@@ -905,6 +1299,7 @@ async fn test_multiple_rapid_refreshes(cx: &mut TestAppContext) {
             view.refresh(cx);
         });
     }
+    cx.run_until_parked();
 
     view.update(cx, |view, _cx| {
         assert!(
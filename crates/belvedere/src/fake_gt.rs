@@ -0,0 +1,133 @@
+//! A scriptable stand-in for the `gt` and `bd` commands, used by tests.
+//!
+//! `FakeGt` writes a small shell script into a temporary directory. Each scripted
+//! command line maps to canned stdout, stderr and an exit status, and every
+//! invocation is appended to a log so tests can assert what was run.
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use crate::gt_cli::GtCli;
+
+struct Response {
+    args: String,
+    stdout: String,
+    stderr: String,
+    status: i32,
+}
+
+pub struct FakeGt {
+    dir: TempDir,
+    responses: Vec<Response>,
+}
+
+impl Default for FakeGt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeGt {
+    pub fn new() -> Self {
+        Self {
+            dir: TempDir::new().expect("failed to create fake gt directory"),
+            responses: Vec::new(),
+        }
+    }
+
+    /// Makes `gt <args>` print `stdout` and exit successfully
+    pub fn respond(mut self, args: &str, stdout: &str) -> Self {
+        self.responses.push(Response {
+            args: args.into(),
+            stdout: stdout.into(),
+            stderr: String::new(),
+            status: 0,
+        });
+        self
+    }
+
+    /// Makes `gt <args>` print `stderr` and exit with `status`
+    pub fn fail(mut self, args: &str, status: i32, stderr: &str) -> Self {
+        self.responses.push(Response {
+            args: args.into(),
+            stdout: String::new(),
+            stderr: stderr.into(),
+            status,
+        });
+        self
+    }
+
+    /// Writes the script as `gt` and returns its path
+    pub fn install(&self) -> PathBuf {
+        self.install_as("gt")
+    }
+
+    /// Writes the script under the given command name and returns its path
+    pub fn install_as(&self, name: &str) -> PathBuf {
+        let root = self.dir.path();
+        let mut script = String::from("#!/bin/sh\n");
+        script.push_str(&format!(
+            "printf '%s\\t%s\\n' \"$(pwd)\" \"$*\" >> '{}'\n",
+            self.log_path().display()
+        ));
+        script.push_str("case \"$*\" in\n");
+        script.push_str("  version) echo 'gt fake'; exit 0 ;;\n");
+
+        for (index, response) in self.responses.iter().enumerate() {
+            let stdout_path = root.join(format!("{}.out", index));
+            let stderr_path = root.join(format!("{}.err", index));
+            fs::write(&stdout_path, &response.stdout).unwrap();
+            fs::write(&stderr_path, &response.stderr).unwrap();
+            script.push_str(&format!(
+                "  '{}') cat '{}'; cat '{}' >&2; exit {} ;;\n",
+                response.args.replace('\'', "'\\''"),
+                stdout_path.display(),
+                stderr_path.display(),
+                response.status
+            ));
+        }
+
+        script.push_str(&format!(
+            "  *) echo \"fake {}: unexpected command: $*\" >&2; exit 64 ;;\n",
+            name
+        ));
+        script.push_str("esac\n");
+
+        let script_path = root.join(name);
+        fs::write(&script_path, script).unwrap();
+        fs::set_permissions(&script_path, fs::Permissions::from_mode(0o755)).unwrap();
+        script_path
+    }
+
+    /// Writes the script and returns a `GtCli` that runs it
+    pub fn cli(&self) -> GtCli {
+        GtCli::new(self.install())
+    }
+
+    /// Writes the script as `bd` and returns a runner for it
+    pub fn bd_cli(&self) -> GtCli {
+        GtCli::new(self.install_as("bd"))
+    }
+
+    /// Returns the `(working directory, arguments)` of every invocation so far
+    pub fn calls(&self) -> Vec<(PathBuf, String)> {
+        fs::read_to_string(self.log_path())
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let (cwd, args) = line.split_once('\t')?;
+                Some((PathBuf::from(cwd), args.to_string()))
+            })
+            .collect()
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    fn log_path(&self) -> PathBuf {
+        self.dir.path().join("calls.log")
+    }
+}
@@ -0,0 +1,720 @@
+use collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::beads::{BeadsIssue, DependencyType, IssueStatus};
+
+/// Formula definitions, relative to a `.beads/` directory
+pub const FORMULAS_DIR: &str = "formulas";
+
+/// Suffix of formula files in `FORMULAS_DIR`
+pub const FORMULA_EXTENSION: &str = ".formula.toml";
+
+/// Label marking the root issue of a molecule poured from the named formula
+pub const FORMULA_LABEL_PREFIX: &str = "formula:";
+
+/// Label marking the issue a molecule created for the named formula step
+pub const STEP_LABEL_PREFIX: &str = "step:";
+
+/// Errors produced when loading a formula
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormulaError {
+    /// The formula file could not be read
+    Io { path: PathBuf, message: String },
+    /// The file is not valid formula TOML
+    Parse(String),
+    /// Two steps share an id
+    DuplicateStep(String),
+    /// A step needs a step that doesn't exist
+    UnknownDependency { step: String, needs: String },
+    /// Steps that (transitively) need each other
+    Cycle(Vec<String>),
+    /// A template uses a variable the formula doesn't declare
+    UndeclaredVariable { step: String, variable: String },
+}
+
+impl std::fmt::Display for FormulaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormulaError::Io { path, message } => write!(f, "{}: {}", path.display(), message),
+            FormulaError::Parse(message) => write!(f, "Invalid formula: {}", message),
+            FormulaError::DuplicateStep(step) => write!(f, "Step '{}' is defined twice", step),
+            FormulaError::UnknownDependency { step, needs } => {
+                write!(f, "Step '{}' needs unknown step '{}'", step, needs)
+            }
+            FormulaError::Cycle(steps) => {
+                write!(f, "Steps depend on each other: {}", steps.join(", "))
+            }
+            FormulaError::UndeclaredVariable { step, variable } => {
+                write!(f, "Step '{}' uses undeclared variable '{}'", step, variable)
+            }
+        }
+    }
+}
+
+/// Why a value entered for a formula variable was rejected
+#[derive(Debug, Clone, PartialEq)]
+pub enum VarError {
+    /// A required variable has no value and no default
+    Missing(String),
+    /// The value isn't one of the variable's `enum` choices
+    NotAChoice { variable: String, value: String },
+    /// The value doesn't match the variable's `pattern`
+    PatternMismatch { variable: String, pattern: String },
+    /// The variable's `pattern` is not a valid regular expression
+    InvalidPattern { variable: String, message: String },
+    /// A value was given for a variable the formula doesn't declare
+    Unknown(String),
+}
+
+impl VarError {
+    /// The variable the error is about
+    pub fn variable(&self) -> &str {
+        match self {
+            VarError::Missing(variable) | VarError::Unknown(variable) => variable,
+            VarError::NotAChoice { variable, .. }
+            | VarError::PatternMismatch { variable, .. }
+            | VarError::InvalidPattern { variable, .. } => variable,
+        }
+    }
+}
+
+impl std::fmt::Display for VarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VarError::Missing(variable) => write!(f, "'{}' is required", variable),
+            VarError::NotAChoice { variable, value } => {
+                write!(f, "'{}' is not an allowed value for '{}'", value, variable)
+            }
+            VarError::PatternMismatch { variable, pattern } => {
+                write!(f, "'{}' must match {}", variable, pattern)
+            }
+            VarError::InvalidPattern { variable, message } => {
+                write!(f, "'{}' has an invalid pattern: {}", variable, message)
+            }
+            VarError::Unknown(variable) => write!(f, "'{}' is not a variable", variable),
+        }
+    }
+}
+
+/// A variable that is filled in when a formula is cooked
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(from = "FormulaVarDefinition")]
+pub struct FormulaVar {
+    pub description: String,
+    pub default: Option<String>,
+    pub required: bool,
+    /// Allowed values; any value is allowed when empty
+    pub choices: Vec<String>,
+    /// Regular expression values must match
+    pub pattern: Option<String>,
+}
+
+/// A variable is either a table, or a string giving its default
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FormulaVarDefinition {
+    Default(String),
+    Table {
+        #[serde(default)]
+        description: String,
+        #[serde(default)]
+        default: Option<String>,
+        #[serde(default)]
+        required: bool,
+        #[serde(default, rename = "enum")]
+        choices: Vec<String>,
+        #[serde(default)]
+        pattern: Option<String>,
+    },
+}
+
+impl From<FormulaVarDefinition> for FormulaVar {
+    fn from(definition: FormulaVarDefinition) -> Self {
+        match definition {
+            FormulaVarDefinition::Default(default) => FormulaVar {
+                default: Some(default),
+                ..Default::default()
+            },
+            FormulaVarDefinition::Table {
+                description,
+                default,
+                required,
+                choices,
+                pattern,
+            } => FormulaVar {
+                description,
+                default,
+                required,
+                choices,
+                pattern,
+            },
+        }
+    }
+}
+
+/// A unit of work in a formula, becoming one issue when poured
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct FormulaStep {
+    pub id: String,
+    /// Issue title template
+    #[serde(default)]
+    pub title: String,
+    /// Issue description template
+    #[serde(default)]
+    pub description: String,
+    /// Ids of the steps that must finish before this one
+    #[serde(default)]
+    pub needs: Vec<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+/// A `<name>.formula.toml` workflow template, as cooked by `bd cook`
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct Formula {
+    #[serde(rename = "formula")]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub version: Option<u32>,
+    /// Variables by name
+    #[serde(default)]
+    pub vars: BTreeMap<String, FormulaVar>,
+    #[serde(default)]
+    pub steps: Vec<FormulaStep>,
+}
+
+impl Formula {
+    /// Parses and validates a formula
+    pub fn parse(contents: &str) -> Result<Self, FormulaError> {
+        let formula: Formula =
+            toml::from_str(contents).map_err(|err| FormulaError::Parse(err.message().into()))?;
+        formula.validate()?;
+        Ok(formula)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FormulaError> {
+        let contents = std::fs::read_to_string(path).map_err(|err| FormulaError::Io {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+        Self::parse(&contents)
+    }
+
+    pub fn step(&self, id: &str) -> Option<&FormulaStep> {
+        self.steps.iter().find(|step| step.id == id)
+    }
+
+    fn validate(&self) -> Result<(), FormulaError> {
+        let mut ids = HashSet::default();
+        for step in &self.steps {
+            if !ids.insert(step.id.as_str()) {
+                return Err(FormulaError::DuplicateStep(step.id.clone()));
+            }
+        }
+
+        for step in &self.steps {
+            if let Some(needs) = step
+                .needs
+                .iter()
+                .find(|needs| !ids.contains(needs.as_str()))
+            {
+                return Err(FormulaError::UnknownDependency {
+                    step: step.id.clone(),
+                    needs: needs.clone(),
+                });
+            }
+            for template in [&step.title, &step.description] {
+                if let Some(variable) = template_variables(template)
+                    .into_iter()
+                    .find(|variable| !self.vars.contains_key(variable))
+                {
+                    return Err(FormulaError::UndeclaredVariable {
+                        step: step.id.clone(),
+                        variable,
+                    });
+                }
+            }
+        }
+
+        let layers = self.layers();
+        let layered: HashSet<&str> = layers
+            .iter()
+            .flatten()
+            .map(|step| step.id.as_str())
+            .collect();
+        if layered.len() < self.steps.len() {
+            let cyclic = self
+                .steps
+                .iter()
+                .filter(|step| !layered.contains(step.id.as_str()))
+                .map(|step| step.id.clone())
+                .collect();
+            return Err(FormulaError::Cycle(cyclic));
+        }
+        Ok(())
+    }
+
+    /// Steps grouped by depth: a step's layer is one past the deepest step it needs.
+    ///
+    /// Layers keep the declaration order of their steps. Steps on a dependency
+    /// cycle are left out.
+    pub fn layers(&self) -> Vec<Vec<&FormulaStep>> {
+        let mut remaining: HashMap<&str, usize> = self
+            .steps
+            .iter()
+            .map(|step| (step.id.as_str(), step.needs.len()))
+            .collect();
+        let mut depth: HashMap<&str, usize> = HashMap::default();
+        let mut ready: VecDeque<&FormulaStep> = self
+            .steps
+            .iter()
+            .filter(|step| step.needs.is_empty())
+            .collect();
+
+        while let Some(step) = ready.pop_front() {
+            let step_depth = step
+                .needs
+                .iter()
+                .filter_map(|needs| depth.get(needs.as_str()))
+                .map(|depth| depth + 1)
+                .max()
+                .unwrap_or(0);
+            depth.insert(&step.id, step_depth);
+
+            for dependent in &self.steps {
+                let needs_step = dependent.needs.iter().filter(|needs| **needs == step.id);
+                for _ in needs_step {
+                    if let Some(count) = remaining.get_mut(dependent.id.as_str()) {
+                        *count -= 1;
+                        if *count == 0 {
+                            ready.push_back(dependent);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut layers: Vec<Vec<&FormulaStep>> = Vec::new();
+        for step in &self.steps {
+            if let Some(&step_depth) = depth.get(step.id.as_str()) {
+                if layers.len() <= step_depth {
+                    layers.resize_with(step_depth + 1, Vec::new);
+                }
+                layers[step_depth].push(step);
+            }
+        }
+        layers
+    }
+
+    /// Checks values entered for this formula's variables, filling in defaults.
+    ///
+    /// Blank values count as not given. Returns the value of every variable that
+    /// ends up with one, or every problem found.
+    pub fn resolve_vars(
+        &self,
+        values: &BTreeMap<String, String>,
+    ) -> Result<BTreeMap<String, String>, Vec<VarError>> {
+        let mut resolved = BTreeMap::new();
+        let mut errors: Vec<VarError> = values
+            .keys()
+            .filter(|name| !self.vars.contains_key(*name))
+            .map(|name| VarError::Unknown(name.clone()))
+            .collect();
+
+        for (name, var) in &self.vars {
+            let value = values
+                .get(name)
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .or(var.default.as_deref());
+            let Some(value) = value else {
+                if var.required {
+                    errors.push(VarError::Missing(name.clone()));
+                }
+                continue;
+            };
+
+            if !var.choices.is_empty() && !var.choices.iter().any(|choice| choice == value) {
+                errors.push(VarError::NotAChoice {
+                    variable: name.clone(),
+                    value: value.into(),
+                });
+                continue;
+            }
+            if let Some(pattern) = &var.pattern {
+                match regex::Regex::new(&format!("^(?:{})$", pattern)) {
+                    Ok(regex) if !regex.is_match(value) => {
+                        errors.push(VarError::PatternMismatch {
+                            variable: name.clone(),
+                            pattern: pattern.clone(),
+                        });
+                        continue;
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        errors.push(VarError::InvalidPattern {
+                            variable: name.clone(),
+                            message: err.to_string(),
+                        });
+                        continue;
+                    }
+                }
+            }
+            resolved.insert(name.clone(), value.to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(resolved)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Names of the formulas in a `.beads/` directory, sorted
+pub fn list_formulas(beads_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(beads_dir.join(FORMULAS_DIR)) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().into_string().ok()?;
+            Some(file_name.strip_suffix(FORMULA_EXTENSION)?.to_string())
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+pub fn formula_path(beads_dir: &Path, name: &str) -> PathBuf {
+    beads_dir
+        .join(FORMULAS_DIR)
+        .join(format!("{}{}", name, FORMULA_EXTENSION))
+}
+
+/// The variables a template refers to as `{{name}}`, in order of first use
+pub fn template_variables(template: &str) -> Vec<String> {
+    let mut variables = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + end].trim().to_string();
+        if !name.is_empty() && !variables.contains(&name) {
+            variables.push(name);
+        }
+        rest = &rest[start + end + 2..];
+    }
+    variables
+}
+
+/// Replaces each `{{name}}` in a template with its value, leaving unknown names as is
+pub fn render_template(template: &str, values: &BTreeMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let placeholder = &rest[start..start + end + 2];
+        let name = rest[start + 2..start + end].trim();
+        rendered.push_str(&rest[..start]);
+        rendered.push_str(values.get(name).map_or(placeholder, String::as_str));
+        rest = &rest[start + end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// How far a poured step has come
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepState {
+    /// Waiting on steps it needs, or not poured at all
+    Pending,
+    /// Every step it needs is done
+    Ready,
+    Active,
+    Blocked,
+    Done,
+}
+
+/// The issue a molecule created for one formula step
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoleculeStep {
+    pub issue_id: String,
+    pub status: IssueStatus,
+    pub assignee: Option<String>,
+}
+
+/// An instance of a formula poured into issues by `bd mol pour`.
+///
+/// The root issue carries a `formula:<name>` label. Each step issue is a child of
+/// the root (`parent-child` dependency) labelled `step:<id>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Molecule {
+    /// Id of the root issue
+    pub id: String,
+    pub title: String,
+    pub closed: bool,
+    /// Step issues by step id
+    pub steps: HashMap<String, MoleculeStep>,
+}
+
+impl Molecule {
+    pub fn step_state(&self, formula: &Formula, step_id: &str) -> StepState {
+        let Some(step) = self.steps.get(step_id) else {
+            return StepState::Pending;
+        };
+        match step.status {
+            IssueStatus::Closed => StepState::Done,
+            IssueStatus::InProgress | IssueStatus::Hooked => StepState::Active,
+            IssueStatus::Blocked => StepState::Blocked,
+            _ => {
+                let needs_done = formula.step(step_id).is_none_or(|step| {
+                    step.needs.iter().all(|needs| {
+                        self.steps
+                            .get(needs)
+                            .is_some_and(|needs| needs.status == IssueStatus::Closed)
+                    })
+                });
+                if needs_done {
+                    StepState::Ready
+                } else {
+                    StepState::Pending
+                }
+            }
+        }
+    }
+}
+
+/// The molecules poured from the named formula, open ones first
+pub fn molecules<'a>(
+    formula: &str,
+    issues: impl IntoIterator<Item = &'a BeadsIssue>,
+) -> Vec<Molecule> {
+    let issues: Vec<&BeadsIssue> = issues.into_iter().collect();
+    let formula_label = format!("{}{}", FORMULA_LABEL_PREFIX, formula);
+
+    let mut molecules: Vec<Molecule> = issues
+        .iter()
+        .filter(|issue| issue.labels.contains(&formula_label))
+        .map(|root| {
+            let steps = issues
+                .iter()
+                .filter(|issue| {
+                    issue.dependencies.iter().any(|dependency| {
+                        dependency.dependency_type == DependencyType::ParentChild
+                            && dependency.depends_on_id == root.id
+                    })
+                })
+                .filter_map(|issue| {
+                    let step_id = issue
+                        .labels
+                        .iter()
+                        .find_map(|label| label.strip_prefix(STEP_LABEL_PREFIX))?;
+                    Some((
+                        step_id.to_string(),
+                        MoleculeStep {
+                            issue_id: issue.id.clone(),
+                            status: issue.status.clone(),
+                            assignee: issue.assignee.clone(),
+                        },
+                    ))
+                })
+                .collect();
+            Molecule {
+                id: root.id.clone(),
+                title: root.title.clone(),
+                closed: root.is_closed(),
+                steps,
+            }
+        })
+        .collect();
+
+    molecules.sort_by(|a, b| a.closed.cmp(&b.closed).then_with(|| a.id.cmp(&b.id)));
+    molecules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beads::BeadsStore;
+
+    fn fixture_beads() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("test_fixtures/beads/release/.beads")
+    }
+
+    fn release() -> Formula {
+        Formula::load(&formula_path(&fixture_beads(), "release")).unwrap()
+    }
+
+    #[test]
+    fn test_parse_formula() {
+        assert_eq!(list_formulas(&fixture_beads()), vec!["release"]);
+
+        let formula = release();
+        assert_eq!(formula.name, "release");
+        assert_eq!(formula.version, Some(1));
+
+        let component = &formula.vars["component"];
+        assert!(component.required);
+        assert_eq!(component.description, "Component to release");
+        assert_eq!(
+            formula.vars["bump"].choices,
+            vec!["patch", "minor", "major"]
+        );
+        assert_eq!(formula.vars["bump"].default.as_deref(), Some("patch"));
+        assert_eq!(formula.vars["channel"].default.as_deref(), Some("stable"));
+
+        let steps: Vec<&str> = formula.steps.iter().map(|step| step.id.as_str()).collect();
+        assert_eq!(steps, vec!["changelog", "bump", "test", "tag", "announce"]);
+        assert_eq!(formula.step("tag").unwrap().needs, vec!["bump", "test"]);
+    }
+
+    #[test]
+    fn test_layers() {
+        let formula = release();
+        let layers: Vec<Vec<&str>> = formula
+            .layers()
+            .iter()
+            .map(|layer| layer.iter().map(|step| step.id.as_str()).collect())
+            .collect();
+
+        assert_eq!(
+            layers,
+            vec![
+                vec!["changelog", "bump"],
+                vec!["test"],
+                vec!["tag"],
+                vec!["announce"]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_formulas() {
+        let parse = |steps: &str| Formula::parse(&format!("formula = \"x\"\n{}", steps));
+
+        assert!(matches!(parse("steps = 3"), Err(FormulaError::Parse(_))));
+        assert_eq!(
+            parse("[[steps]]\nid = \"a\"\n[[steps]]\nid = \"a\""),
+            Err(FormulaError::DuplicateStep("a".into()))
+        );
+        assert_eq!(
+            parse("[[steps]]\nid = \"a\"\nneeds = [\"b\"]"),
+            Err(FormulaError::UnknownDependency {
+                step: "a".into(),
+                needs: "b".into()
+            })
+        );
+        assert_eq!(
+            parse("[[steps]]\nid = \"a\"\nneeds = [\"b\"]\n[[steps]]\nid = \"b\"\nneeds = [\"a\"]"),
+            Err(FormulaError::Cycle(vec!["a".into(), "b".into()]))
+        );
+        assert_eq!(
+            parse("[[steps]]\nid = \"a\"\ntitle = \"Ship {{ thing }}\""),
+            Err(FormulaError::UndeclaredVariable {
+                step: "a".into(),
+                variable: "thing".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_resolve_vars() {
+        let formula = release();
+        let values = |pairs: &[(&str, &str)]| -> BTreeMap<String, String> {
+            pairs
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect()
+        };
+
+        let resolved = formula
+            .resolve_vars(&values(&[("component", "frontend"), ("bump", "")]))
+            .unwrap();
+        assert_eq!(
+            resolved,
+            values(&[
+                ("bump", "patch"),
+                ("channel", "stable"),
+                ("component", "frontend"),
+            ])
+        );
+
+        assert_eq!(
+            formula.resolve_vars(&values(&[("component", "  ")])),
+            Err(vec![VarError::Missing("component".into())])
+        );
+        assert_eq!(
+            formula.resolve_vars(&values(&[
+                ("component", "Frontend"),
+                ("bump", "huge"),
+                ("owner", "nux"),
+            ])),
+            Err(vec![
+                VarError::Unknown("owner".into()),
+                VarError::NotAChoice {
+                    variable: "bump".into(),
+                    value: "huge".into(),
+                },
+                VarError::PatternMismatch {
+                    variable: "component".into(),
+                    pattern: "[a-z][a-z0-9-]*".into(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_templates() {
+        let values = BTreeMap::from_iter([("component".to_string(), "auth".to_string())]);
+
+        assert_eq!(
+            template_variables("Release {{component}} to {{ channel }} ({{component}})"),
+            vec!["component", "channel"]
+        );
+        assert_eq!(
+            render_template("Release {{ component }} to {{channel}}", &values),
+            "Release auth to {{channel}}"
+        );
+        assert_eq!(
+            render_template("Unclosed {{component", &values),
+            "Unclosed {{component"
+        );
+    }
+
+    #[test]
+    fn test_molecule_step_states() {
+        let formula = release();
+        let store = BeadsStore::load(fixture_beads()).unwrap();
+        let molecules = molecules("release", store.issues());
+
+        let ids: Vec<&str> = molecules.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["fe-mol-2", "fe-mol-1"]);
+
+        let active = &molecules[0];
+        assert_eq!(active.steps["test"].issue_id, "fe-mol-2.3");
+        let states: Vec<StepState> = ["changelog", "bump", "test", "tag", "announce"]
+            .iter()
+            .map(|step| active.step_state(&formula, step))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                StepState::Done,
+                StepState::Done,
+                StepState::Active,
+                StepState::Pending,
+                StepState::Pending,
+            ]
+        );
+        assert!(molecules[1].closed);
+    }
+}
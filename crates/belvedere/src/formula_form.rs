@@ -0,0 +1,505 @@
+use collections::BTreeMap;
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, Render, Task, Window, div,
+    prelude::*, px,
+};
+use serde::Deserialize;
+use ui::{ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, Disableable, LabelSize};
+use ui_input::InputField;
+
+use crate::formula::{Formula, VarError};
+use crate::gt_cli::{GtCli, GtCommandError};
+
+/// How a formula is run with `bd`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FormulaRunMode {
+    /// `bd cook`: compile the formula into a proto
+    Cook,
+    /// `bd mol pour`: create a molecule of issues from the formula
+    #[default]
+    Pour,
+}
+
+impl FormulaRunMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            FormulaRunMode::Cook => "Cook",
+            FormulaRunMode::Pour => "Pour",
+        }
+    }
+}
+
+/// A formula with validated variables, ready to hand to `bd`
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormulaRun {
+    pub formula: String,
+    pub mode: FormulaRunMode,
+    pub vars: BTreeMap<String, String>,
+}
+
+/// What `bd cook --json` and `bd mol pour --json` print
+#[derive(Deserialize)]
+struct FormulaRunOutput {
+    #[serde(alias = "root_id")]
+    id: String,
+}
+
+impl FormulaRun {
+    pub fn args(&self) -> Vec<String> {
+        let mut args: Vec<String> = match self.mode {
+            FormulaRunMode::Cook => vec!["cook".into()],
+            FormulaRunMode::Pour => vec!["mol".into(), "pour".into()],
+        };
+        args.push(self.formula.clone());
+        for (name, value) in &self.vars {
+            args.push("--var".into());
+            args.push(format!("{}={}", name, value));
+        }
+        args.push("--json".into());
+        args
+    }
+
+    /// Runs the formula and returns the id of the issue `bd` created
+    pub fn run(&self, bd: &GtCli) -> Result<String, GtCommandError> {
+        bd.run_json::<FormulaRunOutput, _, _>(self.args())
+            .map(|output| output.id)
+    }
+}
+
+/// Emitted by `FormulaForm` when it should be closed
+#[derive(Clone, Debug, PartialEq)]
+pub enum FormulaFormEvent {
+    Dismissed,
+    /// `bd` created the issue with this id: a proto when cooking, or a
+    /// molecule's root when pouring
+    Finished {
+        mode: FormulaRunMode,
+        id: String,
+    },
+}
+
+/// Modal form for a formula's variables, running the formula with `bd` on submit.
+///
+/// There is one input per declared variable. Defaults are shown as placeholders
+/// and used when a field is left blank.
+pub struct FormulaForm {
+    formula: Formula,
+    bd: GtCli,
+    mode: FormulaRunMode,
+    fields: Vec<(String, Entity<InputField>)>,
+    errors: Vec<VarError>,
+    run_error: Option<String>,
+    /// What the last successful run created, while the form stays open
+    status: Option<String>,
+    run_task: Option<Task<()>>,
+    focus_handle: FocusHandle,
+}
+
+impl EventEmitter<FormulaFormEvent> for FormulaForm {}
+
+impl FormulaForm {
+    /// `bd` should already run in the directory holding the formula's `.beads/`
+    pub fn new(formula: Formula, bd: GtCli, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let fields: Vec<(String, Entity<InputField>)> = formula
+            .vars
+            .iter()
+            .map(|(name, var)| {
+                let placeholder = match (&var.default, var.choices.is_empty()) {
+                    (Some(default), _) => default.clone(),
+                    (None, false) => var.choices.join(" | "),
+                    (None, true) if var.required => "required".into(),
+                    (None, true) => String::new(),
+                };
+                let label = if var.required {
+                    format!("{}*", name)
+                } else {
+                    name.clone()
+                };
+                let field = cx.new(|cx| InputField::new(window, cx, placeholder).label(label));
+                (name.clone(), field)
+            })
+            .collect();
+
+        if let Some((_, field)) = fields.first() {
+            window.focus(&field.focus_handle(cx), cx);
+        }
+
+        Self {
+            formula,
+            bd,
+            mode: FormulaRunMode::default(),
+            fields,
+            errors: Vec::new(),
+            run_error: None,
+            status: None,
+            run_task: None,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    pub fn mode(&self) -> FormulaRunMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: FormulaRunMode, cx: &mut Context<Self>) {
+        self.mode = mode;
+        cx.notify();
+    }
+
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.run_task.is_some()
+    }
+
+    /// The values typed into the form, by variable name
+    pub fn values(&self, cx: &App) -> BTreeMap<String, String> {
+        self.fields
+            .iter()
+            .map(|(name, field)| (name.clone(), field.read(cx).text(cx)))
+            .collect()
+    }
+
+    /// Validates the form and, if it is valid, runs the formula in the background
+    pub fn submit(&mut self, cx: &mut Context<Self>) {
+        if self.is_running() {
+            return;
+        }
+        self.run_error = None;
+        self.status = None;
+        let vars = match self.formula.resolve_vars(&self.values(cx)) {
+            Ok(vars) => vars,
+            Err(errors) => {
+                self.errors = errors;
+                cx.notify();
+                return;
+            }
+        };
+        self.errors.clear();
+
+        let mode = self.mode;
+        let run = FormulaRun {
+            formula: self.formula.name.clone(),
+            mode,
+            vars,
+        };
+        let bd = self.bd.clone();
+        let task = cx.background_spawn(async move { run.run(&bd) });
+        self.run_task = Some(cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |this, cx| {
+                this.run_task = None;
+                match result {
+                    Ok(id) => {
+                        // A proto is a template, which can be poured next
+                        if mode == FormulaRunMode::Cook {
+                            this.status = Some(format!("Cooked proto {}", id));
+                        }
+                        cx.emit(FormulaFormEvent::Finished { mode, id });
+                    }
+                    Err(err) => this.run_error = Some(err.to_string()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    pub fn dismiss(&mut self, cx: &mut Context<Self>) {
+        self.run_task = None;
+        cx.emit(FormulaFormEvent::Dismissed);
+    }
+
+    fn render_field(&self, name: &str, field: &Entity<InputField>, cx: &App) -> impl IntoElement {
+        let var = self.formula.vars.get(name);
+        let description = var
+            .map(|var| var.description.clone())
+            .filter(|description| !description.is_empty());
+        let errors: Vec<String> = self
+            .errors
+            .iter()
+            .filter(|error| error.variable() == name)
+            .map(|error| error.to_string())
+            .collect();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(2.0))
+            .child(field.clone())
+            .when_some(description, |this, description| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().colors().text_muted)
+                        .child(description),
+                )
+            })
+            .children(errors.into_iter().map(|error| {
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().status().error)
+                    .child(error)
+            }))
+    }
+}
+
+impl Focusable for FormulaForm {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for FormulaForm {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let running = self.is_running();
+        // Errors for variables without a field, i.e. ones the formula doesn't declare
+        let form_errors: Vec<String> = self
+            .errors
+            .iter()
+            .filter(|error| matches!(error, VarError::Unknown(_)))
+            .map(|error| error.to_string())
+            .chain(self.run_error.clone())
+            .collect();
+
+        let mode_button = |mode: FormulaRunMode| {
+            Button::new(mode.label(), mode.label())
+                .style(if self.mode == mode {
+                    ButtonStyle::Filled
+                } else {
+                    ButtonStyle::Subtle
+                })
+                .label_size(LabelSize::Small)
+                .on_click(cx.listener(move |this, _, _, cx| this.set_mode(mode, cx)))
+        };
+
+        div()
+            .id("formula-form")
+            .track_focus(&self.focus_handle)
+            .occlude()
+            .flex()
+            .flex_col()
+            .gap(px(12.0))
+            .w(px(420.0))
+            .max_h_full()
+            .overflow_y_scroll()
+            .p(px(16.0))
+            .rounded(px(8.0))
+            .border_1()
+            .border_color(colors.border_variant)
+            .bg(colors.elevated_surface_background)
+            .text_sm()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(4.0))
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_color(colors.text)
+                            .child(format!("Run {}", self.formula.name)),
+                    )
+                    .child(mode_button(FormulaRunMode::Pour))
+                    .child(mode_button(FormulaRunMode::Cook)),
+            )
+            .when(self.fields.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_color(colors.text_muted)
+                        .child("This formula has no variables"),
+                )
+            })
+            .children(
+                self.fields
+                    .iter()
+                    .map(|(name, field)| self.render_field(name, field, cx)),
+            )
+            .when_some(self.status.clone(), |this, status| {
+                this.child(div().text_xs().text_color(colors.text_muted).child(status))
+            })
+            .children(form_errors.into_iter().map(|error| {
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().status().error)
+                    .child(error)
+            }))
+            .child(
+                div()
+                    .flex()
+                    .justify_end()
+                    .gap(px(4.0))
+                    .child(
+                        Button::new("formula-form-cancel", "Cancel")
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, _, cx| this.dismiss(cx))),
+                    )
+                    .child(
+                        Button::new(
+                            "formula-form-submit",
+                            if running {
+                                "Running…".to_string()
+                            } else {
+                                self.mode.label().to_string()
+                            },
+                        )
+                        .style(ButtonStyle::Filled)
+                        .label_size(LabelSize::Small)
+                        .disabled(running)
+                        .on_click(cx.listener(|this, _, _, cx| this.submit(cx))),
+                    ),
+            )
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::fake_gt::FakeGt;
+
+    fn release_run(mode: FormulaRunMode) -> FormulaRun {
+        FormulaRun {
+            formula: "release".into(),
+            mode,
+            vars: BTreeMap::from_iter([
+                ("bump".to_string(), "minor".to_string()),
+                ("component".to_string(), "frontend".to_string()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_pour_returns_molecule_id() {
+        let fake = FakeGt::new().respond(
+            "mol pour release --var bump=minor --var component=frontend --json",
+            r#"{"id": "fe-mol-3", "title": "release frontend"}"#,
+        );
+        let bd = fake.bd_cli().town_path(fake.path());
+
+        let id = release_run(FormulaRunMode::Pour).run(&bd).unwrap();
+        assert_eq!(id, "fe-mol-3");
+        assert_eq!(
+            fake.calls(),
+            vec![(
+                fake.path().canonicalize().unwrap(),
+                "mol pour release --var bump=minor --var component=frontend --json".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_cook_and_failures() {
+        let fake = FakeGt::new()
+            .respond(
+                "cook release --var bump=minor --var component=frontend --json",
+                r#"{"root_id": "fe-proto-1"}"#,
+            )
+            .fail(
+                "mol pour release --var bump=minor --var component=frontend --json",
+                1,
+                "formula release: no such proto",
+            );
+        let bd = fake.bd_cli();
+
+        assert_eq!(
+            release_run(FormulaRunMode::Cook).run(&bd).unwrap(),
+            "fe-proto-1"
+        );
+
+        let error = release_run(FormulaRunMode::Pour).run(&bd).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`bd mol pour release --var bump=minor --var component=frontend --json` \
+             exited with status 1: formula release: no such proto"
+        );
+    }
+
+    #[gpui::test]
+    fn test_cooked_proto_is_not_a_molecule(cx: &mut gpui::TestAppContext) {
+        use crate::formula::formula_path;
+        use crate::formula_view::{FormulaView, FormulaViewEvent};
+        use crate::town_model::{FormulaFile, TownModel, TownStructure};
+        use gpui::AppContext as _;
+
+        let rig = tempfile::tempdir().unwrap();
+        let path = formula_path(&rig.path().join(".beads"), "release");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "formula = \"release\"\n[[steps]]\nid = \"tag\"\n").unwrap();
+        let model = cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(
+                TownStructure {
+                    formulas: vec![FormulaFile {
+                        name: "release".into(),
+                        rig: Some("frontend".into()),
+                        path,
+                        modified: None,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            );
+            model
+        });
+        let fake = FakeGt::new()
+            .respond("cook release --json", r#"{"root_id": "fe-proto-1"}"#)
+            .respond("mol pour release --json", r#"{"id": "fe-mol-3"}"#);
+        let view =
+            cx.new(|cx| FormulaView::new("frontend/release".into(), model, fake.bd_cli(), cx));
+        let events = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let events = events.clone();
+            cx.subscribe(&view, move |_, event: &FormulaViewEvent, _| {
+                events.borrow_mut().push(event.clone());
+            })
+            .detach();
+        });
+        cx.run_until_parked();
+
+        let cx = cx.add_empty_window();
+        let form = cx.update(|window, cx| {
+            view.update(cx, |view, cx| {
+                view.open_form(window, cx);
+                view.form().cloned().unwrap()
+            })
+        });
+        form.update(cx, |form, cx| {
+            form.set_mode(FormulaRunMode::Cook, cx);
+            form.submit(cx);
+        });
+        cx.run_until_parked();
+
+        view.read_with(cx, |view, _| {
+            assert_eq!(view.selected_molecule_id(), None);
+            assert!(view.form().is_some());
+        });
+        assert_eq!(
+            form.read_with(cx, |form, _| form.status().map(String::from)),
+            Some("Cooked proto fe-proto-1".into())
+        );
+
+        // The proto can be poured from the same form
+        form.update(cx, |form, cx| {
+            form.set_mode(FormulaRunMode::Pour, cx);
+            form.submit(cx);
+        });
+        cx.run_until_parked();
+
+        view.read_with(cx, |view, _| {
+            assert_eq!(view.selected_molecule_id(), Some("fe-mol-3"));
+            assert!(view.form().is_none());
+        });
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                FormulaViewEvent::ProtoCooked("fe-proto-1".into()),
+                FormulaViewEvent::MoleculeCreated("fe-mol-3".into()),
+            ]
+        );
+    }
+}
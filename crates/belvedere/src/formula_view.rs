@@ -0,0 +1,634 @@
+use gpui::{
+    App, Context, Div, ElementId, Entity, EventEmitter, FocusHandle, Focusable, Hsla, PathBuilder,
+    Pixels, Point, Render, SharedString, Stateful, Subscription, Task, Window, canvas, div, point,
+    prelude::*, px,
+};
+use std::time::SystemTime;
+use ui::{
+    ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, ContextMenu, DropdownMenu,
+    IconPosition, LabelSize,
+};
+
+use crate::formula::{Formula, FormulaError, Molecule, StepState, molecules};
+use crate::formula_form::{FormulaForm, FormulaFormEvent, FormulaRunMode};
+use crate::gt_cli::GtCli;
+use crate::town_item::{TownItem, TownItemEvent};
+use crate::town_model::{TownModel, TownModelEvent};
+
+const NODE_WIDTH: f32 = 200.0;
+const NODE_HEIGHT: f32 = 56.0;
+/// Horizontal space between layers, where the edges run
+const LAYER_GAP: f32 = 64.0;
+const ROW_GAP: f32 = 16.0;
+
+/// Where a step's node is drawn, relative to the top left of the graph
+#[derive(Clone, Debug, PartialEq)]
+pub struct StepNode {
+    pub step_id: String,
+    pub origin: Point<Pixels>,
+}
+
+/// Lays a formula's steps out left to right, one column per dependency layer
+pub fn layout_steps(formula: &Formula) -> Vec<StepNode> {
+    formula
+        .layers()
+        .iter()
+        .enumerate()
+        .flat_map(|(column, layer)| {
+            layer.iter().enumerate().map(move |(row, step)| StepNode {
+                step_id: step.id.clone(),
+                origin: point(
+                    px(column as f32 * (NODE_WIDTH + LAYER_GAP)),
+                    px(row as f32 * (NODE_HEIGHT + ROW_GAP)),
+                ),
+            })
+        })
+        .collect()
+}
+
+/// Events emitted by `FormulaView` for the town to act on
+#[derive(Clone, Debug, PartialEq)]
+pub enum FormulaViewEvent {
+    /// `bd` poured a molecule from the formula, so the town's beads are out of date
+    MoleculeCreated(String),
+    /// `bd` cooked the formula into a proto, which is also a new issue
+    ProtoCooked(String),
+}
+
+/// Center pane item drawing a formula's step DAG.
+///
+/// When a molecule poured from the formula is selected, each step is colored by
+/// the status of the issue the molecule created for it.
+pub struct FormulaView {
+    formula_id: String,
+    model: Entity<TownModel>,
+    /// Runs `bd` for the run form
+    bd: GtCli,
+    formula: Option<Result<Formula, FormulaError>>,
+    /// Modification time of the formula file that was loaded
+    loaded_version: Option<SystemTime>,
+    /// Root issue of the molecule to overlay; the first open one when unset
+    selected_molecule: Option<String>,
+    load_task: Option<Task<()>>,
+    /// Variable form shown over the graph while running the formula
+    form: Option<Entity<FormulaForm>>,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<TownItemEvent> for FormulaView {}
+impl EventEmitter<FormulaViewEvent> for FormulaView {}
+
+impl FormulaView {
+    pub fn new(
+        formula_id: String,
+        model: Entity<TownModel>,
+        bd: GtCli,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription =
+            cx.subscribe(
+                &model,
+                |this, model, event: &TownModelEvent, cx| match event {
+                    TownModelEvent::FormulasChanged => {
+                        let modified = model
+                            .read(cx)
+                            .formula(&this.formula_id)
+                            .and_then(|formula| formula.modified);
+                        if modified != this.loaded_version {
+                            this.reload(cx);
+                        }
+                    }
+                    TownModelEvent::IssuesChanged => cx.notify(),
+                    _ => {}
+                },
+            );
+
+        let mut view = Self {
+            formula_id,
+            model,
+            bd,
+            formula: None,
+            loaded_version: None,
+            selected_molecule: None,
+            load_task: None,
+            form: None,
+            focus_handle: cx.focus_handle(),
+            _subscription: subscription,
+        };
+        view.reload(cx);
+        view
+    }
+
+    pub fn formula_id(&self) -> &str {
+        &self.formula_id
+    }
+
+    /// The parsed formula; `None` until it has been read
+    pub fn formula(&self) -> Option<&Result<Formula, FormulaError>> {
+        self.formula.as_ref()
+    }
+
+    /// Reads the formula file again in the background
+    pub fn reload(&mut self, cx: &mut Context<Self>) {
+        let Some(file) = self.model.read(cx).formula(&self.formula_id).cloned() else {
+            self.formula = Some(Err(FormulaError::Io {
+                path: self.formula_id.clone().into(),
+                message: "no such formula in the town".into(),
+            }));
+            cx.notify();
+            return;
+        };
+
+        let load = cx.background_spawn(async move { Formula::load(&file.path) });
+        self.load_task = Some(cx.spawn(async move |this, cx| {
+            let formula = load.await;
+            this.update(cx, |this, cx| {
+                this.formula = Some(formula);
+                this.loaded_version = file.modified;
+                cx.emit(TownItemEvent::UpdateTab);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// The molecules poured from this formula, open ones first
+    pub fn molecules(&self, cx: &App) -> Vec<Molecule> {
+        let Some(Ok(formula)) = &self.formula else {
+            return Vec::new();
+        };
+        let model = self.model.read(cx);
+        molecules(&formula.name, model.issues().map(|issue| &issue.bead))
+    }
+
+    /// The molecule overlaid on the graph, if any
+    pub fn selected_molecule(&self, cx: &App) -> Option<Molecule> {
+        let molecules = self.molecules(cx);
+        match &self.selected_molecule {
+            Some(id) => molecules.into_iter().find(|molecule| &molecule.id == id),
+            None => molecules.into_iter().find(|molecule| !molecule.closed),
+        }
+    }
+
+    /// The root issue picked to overlay, as opposed to the first open molecule
+    pub fn selected_molecule_id(&self) -> Option<&str> {
+        self.selected_molecule.as_deref()
+    }
+
+    pub fn select_molecule(&mut self, id: Option<String>, cx: &mut Context<Self>) {
+        self.selected_molecule = id;
+        cx.notify();
+    }
+
+    pub fn form(&self) -> Option<&Entity<FormulaForm>> {
+        self.form.as_ref()
+    }
+
+    /// Shows the variable form for running the formula with `bd`
+    pub fn open_form(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(Ok(formula)) = self.formula.clone() else {
+            return;
+        };
+        // `<rig>/.beads/formulas/<name>.formula.toml`; `bd` runs in the rig
+        let rig_dir = self
+            .model
+            .read(cx)
+            .formula(&self.formula_id)
+            .and_then(|file| Some(file.path.parent()?.parent()?.parent()?.to_path_buf()));
+        let bd = match rig_dir {
+            Some(rig_dir) => self.bd.clone().town_path(rig_dir),
+            None => self.bd.clone(),
+        };
+
+        let form = cx.new(|cx| FormulaForm::new(formula, bd, window, cx));
+        cx.subscribe(&form, |this, _, event: &FormulaFormEvent, cx| {
+            match event {
+                FormulaFormEvent::Dismissed => this.form = None,
+                FormulaFormEvent::Finished {
+                    mode: FormulaRunMode::Pour,
+                    id,
+                } => {
+                    this.form = None;
+                    this.selected_molecule = Some(id.clone());
+                    cx.emit(FormulaViewEvent::MoleculeCreated(id.clone()));
+                }
+                // The form stays open, showing the proto it cooked
+                FormulaFormEvent::Finished {
+                    mode: FormulaRunMode::Cook,
+                    id,
+                } => cx.emit(FormulaViewEvent::ProtoCooked(id.clone())),
+            }
+            cx.notify();
+        })
+        .detach();
+        self.form = Some(form);
+        cx.notify();
+    }
+
+    fn render_molecule_picker(
+        &self,
+        molecules: &[Molecule],
+        selected: Option<&Molecule>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let label = selected
+            .map(|molecule| format!("{} {}", molecule.id, molecule.title))
+            .unwrap_or_else(|| "No molecule".into());
+        let selected = selected.map(|molecule| molecule.id.clone());
+        let entries: Vec<(String, String)> = molecules
+            .iter()
+            .map(|molecule| {
+                let state = if molecule.closed { " (closed)" } else { "" };
+                (
+                    molecule.id.clone(),
+                    format!("{} {}{}", molecule.id, molecule.title, state),
+                )
+            })
+            .collect();
+        let view = cx.entity().downgrade();
+
+        let menu = ContextMenu::build(window, cx, move |mut menu, _, _| {
+            for (id, label) in entries {
+                let view = view.clone();
+                let toggled = selected.as_ref() == Some(&id);
+                menu = menu.toggleable_entry(label, toggled, IconPosition::Start, None, {
+                    move |_, cx| {
+                        view.update(cx, |view, cx| view.select_molecule(Some(id.clone()), cx))
+                            .ok();
+                    }
+                });
+            }
+            menu
+        });
+        DropdownMenu::new("formula-molecule", label, menu)
+    }
+
+    fn render_graph(
+        &self,
+        formula: &Formula,
+        molecule: Option<&Molecule>,
+        cx: &App,
+    ) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+        let nodes = layout_steps(formula);
+        let width = nodes
+            .iter()
+            .map(|node| node.origin.x + px(NODE_WIDTH))
+            .max()
+            .unwrap_or_default();
+        let height = nodes
+            .iter()
+            .map(|node| node.origin.y + px(NODE_HEIGHT))
+            .max()
+            .unwrap_or_default();
+
+        let state_color = |state: Option<StepState>| -> Hsla {
+            match state {
+                Some(StepState::Done) => status.success,
+                Some(StepState::Active) => status.info,
+                Some(StepState::Blocked) => status.error,
+                Some(StepState::Ready) => colors.text_accent,
+                Some(StepState::Pending) | None => colors.border_variant,
+            }
+        };
+        let state = |step_id: &str| molecule.map(|molecule| molecule.step_state(formula, step_id));
+
+        let mut edges: Vec<(Point<Pixels>, Point<Pixels>, Hsla)> = Vec::new();
+        for node in &nodes {
+            let Some(step) = formula.step(&node.step_id) else {
+                continue;
+            };
+            for needs in &step.needs {
+                let Some(from) = nodes.iter().find(|node| &node.step_id == needs) else {
+                    continue;
+                };
+                let start = from.origin + point(px(NODE_WIDTH), px(NODE_HEIGHT / 2.0));
+                let end = node.origin + point(px(0.0), px(NODE_HEIGHT / 2.0));
+                let color = match state(needs) {
+                    Some(StepState::Done) => status.success,
+                    _ => colors.border_variant,
+                };
+                edges.push((start, end, color));
+            }
+        }
+
+        div()
+            .relative()
+            .flex_shrink_0()
+            .w(width)
+            .h(height)
+            .child(
+                canvas(
+                    |_, _, _| {},
+                    move |bounds, _, window, _| {
+                        for (start, end, color) in edges {
+                            let start = bounds.origin + start;
+                            let end = bounds.origin + end;
+                            let middle = start.x + (end.x - start.x) * 0.5;
+                            let mut builder = PathBuilder::stroke(px(1.5));
+                            builder.move_to(start);
+                            builder.line_to(point(middle, start.y));
+                            builder.line_to(point(middle, end.y));
+                            builder.line_to(end);
+                            if let Ok(path) = builder.build() {
+                                window.paint_path(path, color);
+                            }
+                        }
+                    },
+                )
+                .absolute()
+                .size_full(),
+            )
+            .children(nodes.iter().map(|node| {
+                let step = formula.step(&node.step_id);
+                let step_state = state(&node.step_id);
+                let issue = molecule.and_then(|molecule| molecule.steps.get(&node.step_id));
+                let detail = match issue {
+                    Some(issue) => match &issue.assignee {
+                        Some(assignee) => format!("{} · {}", issue.issue_id, assignee),
+                        None => issue.issue_id.clone(),
+                    },
+                    None => step.map(|step| step.title.clone()).unwrap_or_default(),
+                };
+
+                div()
+                    .id(ElementId::Name(
+                        format!("formula-step-{}", node.step_id).into(),
+                    ))
+                    .absolute()
+                    .left(node.origin.x)
+                    .top(node.origin.y)
+                    .w(px(NODE_WIDTH))
+                    .h(px(NODE_HEIGHT))
+                    .flex()
+                    .flex_col()
+                    .justify_center()
+                    .px(px(8.0))
+                    .rounded(px(6.0))
+                    .border_2()
+                    .border_color(state_color(step_state))
+                    .bg(colors.element_background)
+                    .overflow_hidden()
+                    .child(
+                        div()
+                            .text_color(colors.text)
+                            .truncate()
+                            .child(node.step_id.clone()),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(colors.text_muted)
+                            .truncate()
+                            .child(detail),
+                    )
+            }))
+    }
+}
+
+impl Focusable for FormulaView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TownItem for FormulaView {
+    type Event = TownItemEvent;
+
+    fn tab_content_text(&self, _cx: &App) -> SharedString {
+        match &self.formula {
+            Some(Ok(formula)) => format!("Formula {}", formula.name).into(),
+            _ => format!("Formula {}", self.formula_id).into(),
+        }
+    }
+
+    fn to_town_item_events(event: &Self::Event, mut f: impl FnMut(TownItemEvent)) {
+        f(*event);
+    }
+}
+
+impl Render for FormulaView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let overlay = cx.theme().colors().editor_background.opacity(0.7);
+        div()
+            .relative()
+            .size_full()
+            .child(self.render_content(window, cx))
+            .when_some(self.form.clone(), |this, form| {
+                this.child(
+                    div()
+                        .absolute()
+                        .inset_0()
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .p(px(16.0))
+                        .bg(overlay)
+                        .child(form),
+                )
+            })
+    }
+}
+
+impl FormulaView {
+    fn render_content(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Stateful<Div> {
+        let container = div()
+            .id("formula-view")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .gap(px(12.0))
+            .p(px(16.0))
+            .overflow_scroll()
+            .text_sm();
+
+        let formula = match &self.formula {
+            None => {
+                return container
+                    .text_color(cx.theme().colors().text_muted)
+                    .child("Loading formula…");
+            }
+            Some(Err(err)) => {
+                return container
+                    .text_color(cx.theme().status().error)
+                    .child(err.to_string());
+            }
+            Some(Ok(formula)) => formula.clone(),
+        };
+
+        let molecules = self.molecules(cx);
+        let molecule = self.selected_molecule(cx);
+        let picker = (!molecules.is_empty())
+            .then(|| self.render_molecule_picker(&molecules, molecule.as_ref(), window, cx));
+        let graph = self.render_graph(&formula, molecule.as_ref(), cx);
+        let colors = cx.theme().colors();
+
+        let variables: Vec<String> = formula
+            .vars
+            .iter()
+            .map(|(name, var)| {
+                let mut text = name.clone();
+                if var.required {
+                    text.push('*');
+                }
+                if let Some(default) = &var.default {
+                    text.push_str(&format!(" = {}", default));
+                }
+                text
+            })
+            .collect();
+
+        container
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .child(
+                        div()
+                            .text_lg()
+                            .text_color(colors.text)
+                            .child(format!("Formula {}", formula.name)),
+                    )
+                    .when_some(formula.version, |this, version| {
+                        this.child(
+                            div()
+                                .text_color(colors.text_muted)
+                                .child(format!("v{}", version)),
+                        )
+                    })
+                    .child(div().ml_auto().children(picker))
+                    .child(
+                        Button::new("formula-run", "Run…")
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.open_form(window, cx)),
+                            ),
+                    ),
+            )
+            .when(!formula.description.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_color(colors.text_muted)
+                        .child(formula.description.clone()),
+                )
+            })
+            .when(!variables.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_color(colors.text_muted)
+                        .child(format!("Variables: {}", variables.join(", "))),
+                )
+            })
+            .child(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beads::BeadsStore;
+    use crate::formula::formula_path;
+    use crate::town_model::{FormulaFile, Issue, TownStructure};
+    use gpui::{AppContext as _, TestAppContext};
+    use std::path::Path;
+
+    fn test_model(cx: &mut TestAppContext) -> Entity<TownModel> {
+        let beads_dir =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("test_fixtures/beads/release/.beads");
+        let store = BeadsStore::load(&beads_dir).unwrap();
+        let structure = TownStructure {
+            issues: store
+                .issues()
+                .iter()
+                .map(|bead| Issue {
+                    rig: Some("frontend".into()),
+                    beads_dir: beads_dir.clone(),
+                    bead: bead.clone(),
+                })
+                .collect(),
+            formulas: vec![FormulaFile {
+                name: "release".into(),
+                rig: Some("frontend".into()),
+                path: formula_path(&beads_dir, "release"),
+                modified: None,
+            }],
+            ..Default::default()
+        };
+        cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(structure, cx);
+            model
+        })
+    }
+
+    #[test]
+    fn test_layout_steps() {
+        let formula = Formula::parse(
+            r#"
+            formula = "x"
+            [[steps]]
+            id = "a"
+            [[steps]]
+            id = "b"
+            [[steps]]
+            id = "c"
+            needs = ["a", "b"]
+            "#,
+        )
+        .unwrap();
+
+        let origins: Vec<(String, Point<Pixels>)> = layout_steps(&formula)
+            .into_iter()
+            .map(|node| (node.step_id, node.origin))
+            .collect();
+        assert_eq!(
+            origins,
+            vec![
+                ("a".into(), point(px(0.0), px(0.0))),
+                ("b".into(), point(px(0.0), px(NODE_HEIGHT + ROW_GAP))),
+                ("c".into(), point(px(NODE_WIDTH + LAYER_GAP), px(0.0))),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    fn test_overlays_open_molecule(cx: &mut TestAppContext) {
+        let model = test_model(cx);
+        let view =
+            cx.new(|cx| FormulaView::new("frontend/release".into(), model, GtCli::new("bd"), cx));
+        cx.run_until_parked();
+
+        view.update(cx, |view, cx| {
+            let Some(Ok(formula)) = view.formula().cloned() else {
+                panic!("formula didn't load");
+            };
+            assert_eq!(formula.steps.len(), 5);
+
+            let molecule = view.selected_molecule(cx).unwrap();
+            assert_eq!(molecule.id, "fe-mol-2");
+            assert_eq!(molecule.step_state(&formula, "test"), StepState::Active);
+
+            view.select_molecule(Some("fe-mol-1".into()), cx);
+            let molecule = view.selected_molecule(cx).unwrap();
+            assert_eq!(molecule.step_state(&formula, "changelog"), StepState::Done);
+            assert_eq!(molecule.step_state(&formula, "bump"), StepState::Pending);
+        });
+    }
+
+    #[gpui::test]
+    fn test_unknown_formula(cx: &mut TestAppContext) {
+        let model = test_model(cx);
+        let view =
+            cx.new(|cx| FormulaView::new("backend/deploy".into(), model, GtCli::new("bd"), cx));
+
+        view.read_with(cx, |view, _| {
+            assert!(matches!(view.formula(), Some(Err(FormulaError::Io { .. }))));
+        });
+    }
+}
@@ -0,0 +1,653 @@
+use parking_lot::{Mutex, MutexGuard};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::agent_discovery::{AgentDiscovery, TOWN_AGENTS};
+use crate::beads::BeadsIndex;
+use crate::dashboard_buffer::{
+    AgentInfo, AgentStatus, ConvoyInfo, DashboardData, DashboardDataSource, DashboardError,
+    RigInfo, TokenUsage,
+};
+use crate::formula;
+use crate::gt_data_source::parse_agent_status;
+use crate::town_model::{ConvoyIssue, Crew, FormulaFile, Hook, Issue, Rig, TownStructure};
+
+/// Entries whose presence marks a town subdirectory as a rig
+const RIG_MARKERS: &[&str] = &[".beads", "crew", "polecats", "hooks", ".agents"];
+
+/// Optional per-agent state file written by the agent runtime
+const AGENT_STATE_FILE: &str = "state.json";
+
+/// Reads dashboard data directly from a Gas Town directory (e.g. `~/gt/`).
+///
+/// Expected layout:
+/// - `<town>/mayor/`, `<town>/deacon/` — town-level agents
+/// - `<town>/<rig>/` — any subdirectory containing `.beads/`, `crew/`, `polecats/`,
+///   `hooks/` or `.agents/`
+/// - `<rig>/crew/<name>/`, `<rig>/polecats/<name>/`, `<rig>/.agents/<role>-<id>/` — agents
+/// - `<rig>/hooks/<name>/` — hook worktree of the agent called `<name>`
+/// - `<agent>/state.json` — optional status, token usage and context fill
+/// - `<town>/.beads/issues.jsonl`, `<rig>/.beads/issues.jsonl` — beads issues; issues
+///   of type `convoy` become convoys, tracking the issues they depend on
+/// - `<town>/.beads/formulas/`, `<rig>/.beads/formulas/` — `<name>.formula.toml` formulas
+///
+/// Beads stores are cached between reads and only re-read when they change.
+pub struct FsDataSource {
+    town_path: PathBuf,
+    beads: Mutex<BeadsIndex>,
+}
+
+impl FsDataSource {
+    pub fn new(town_path: impl Into<PathBuf>) -> Self {
+        Self {
+            town_path: town_path.into(),
+            beads: Mutex::new(BeadsIndex::new()),
+        }
+    }
+
+    pub fn town_path(&self) -> &Path {
+        &self.town_path
+    }
+
+    /// Reads the town's directory structure for the `TownModel`: rigs, discovered agent
+    /// directories, crew workspaces, hook worktrees and the issues each convoy tracks.
+    pub fn read_structure(&self) -> Result<TownStructure, DashboardError> {
+        if !self.is_available() {
+            return Err(DashboardError::NotAvailable);
+        }
+
+        let rigs = self.read_rigs()?;
+        let discovery = AgentDiscovery::new(Some(self.town_path.clone()));
+
+        let mut structure = TownStructure {
+            agents: discovery.discover_town_agents(),
+            ..Default::default()
+        };
+        for rig in &rigs {
+            let rig_path = PathBuf::from(&rig.path);
+            for (name, path) in subdirectories(&rig_path.join("crew")) {
+                structure.crew.push(Crew {
+                    name,
+                    rig: rig.name.clone(),
+                    path,
+                });
+            }
+            for (name, path) in subdirectories(&rig_path.join("hooks")) {
+                structure.hooks.push(Hook {
+                    name,
+                    rig: rig.name.clone(),
+                    path,
+                    agent: None,
+                });
+            }
+            structure.rigs.push(Rig {
+                name: rig.name.clone(),
+                path: rig_path,
+            });
+        }
+
+        let beads = self.read_beads(&rigs)?;
+        for convoy in beads.convoys() {
+            let members = convoy
+                .dependencies
+                .iter()
+                .map(|dependency| {
+                    let Some(issue) = beads.issue(&dependency.depends_on_id) else {
+                        return ConvoyIssue {
+                            id: dependency.depends_on_id.clone(),
+                            ..Default::default()
+                        };
+                    };
+                    ConvoyIssue {
+                        id: issue.id.clone(),
+                        title: issue.title().map(str::to_string),
+                        status: issue.status.to_string(),
+                        assignee: issue.assignee.clone(),
+                        depends_on: issue.blockers().map(str::to_string).collect(),
+                        slung_at: issue.slung_at(),
+                        completed_at: issue.closed_at.clone(),
+                    }
+                })
+                .collect();
+            structure.convoy_issues.insert(convoy.id.clone(), members);
+        }
+
+        for store in beads.stores() {
+            let rig = rigs
+                .iter()
+                .find(|rig| Path::new(&rig.path).join(".beads") == store.dir())
+                .map(|rig| rig.name.clone());
+            for name in formula::list_formulas(store.dir()) {
+                let path = formula::formula_path(store.dir(), &name);
+                structure.formulas.push(FormulaFile {
+                    modified: std::fs::metadata(&path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok(),
+                    name,
+                    rig: rig.clone(),
+                    path,
+                });
+            }
+            structure
+                .issues
+                .extend(store.issues().iter().map(|bead| Issue {
+                    rig: rig.clone(),
+                    beads_dir: store.dir().to_path_buf(),
+                    bead: bead.clone(),
+                }));
+        }
+
+        Ok(structure)
+    }
+
+    fn read_rigs(&self) -> Result<Vec<RigInfo>, DashboardError> {
+        let entries = std::fs::read_dir(&self.town_path).map_err(|err| {
+            DashboardError::FetchFailed(format!("{}: {}", self.town_path.display(), err))
+        })?;
+
+        let mut rigs = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if name.starts_with('.') || TOWN_AGENTS.contains(&name) || !path.is_dir() {
+                continue;
+            }
+            if RIG_MARKERS.iter().any(|marker| path.join(marker).exists()) {
+                rigs.push(RigInfo {
+                    name: name.to_string(),
+                    path: path.to_string_lossy().into_owned(),
+                });
+            }
+        }
+
+        rigs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(rigs)
+    }
+
+    fn read_agents(&self) -> Vec<AgentInfo> {
+        let discovery = AgentDiscovery::new(Some(self.town_path.clone()));
+
+        let mut agents = Vec::new();
+        for directory in discovery.discover_town_agents() {
+            let hooked = directory.rig.as_ref().is_some_and(|rig| {
+                self.town_path
+                    .join(rig)
+                    .join("hooks")
+                    .join(&directory.instance_name)
+                    .is_dir()
+            });
+            agents.push(read_agent(directory.address(), &directory.path, hooked));
+        }
+
+        agents.sort_by(|a, b| a.name.cmp(&b.name));
+        agents
+    }
+
+    /// Reloads the town's beads store and those of each rig, in that order
+    fn read_beads(&self, rigs: &[RigInfo]) -> Result<MutexGuard<'_, BeadsIndex>, DashboardError> {
+        let mut beads = self.beads.lock();
+        let rig_dirs = rigs.iter().map(|rig| Path::new(&rig.path).join(".beads"));
+        beads.set_dirs(std::iter::once(self.town_path.join(".beads")).chain(rig_dirs));
+        beads.reload()?;
+        Ok(beads)
+    }
+
+    fn read_convoys(&self, rigs: &[RigInfo]) -> Result<Vec<ConvoyInfo>, DashboardError> {
+        let beads = self.read_beads(rigs)?;
+
+        let mut convoys: Vec<ConvoyInfo> = beads
+            .convoys()
+            .map(|convoy| {
+                let tracked: Vec<&str> = convoy
+                    .dependencies
+                    .iter()
+                    .map(|dependency| dependency.depends_on_id.as_str())
+                    .collect();
+
+                let progress = if tracked.is_empty() {
+                    if convoy.is_closed() { 1.0 } else { 0.0 }
+                } else {
+                    let closed = tracked
+                        .iter()
+                        .filter(|id| beads.issue(id).is_some_and(|issue| issue.is_closed()))
+                        .count();
+                    closed as f32 / tracked.len() as f32
+                };
+
+                ConvoyInfo {
+                    id: convoy.id.clone(),
+                    name: convoy.title.clone(),
+                    progress,
+                    issues: tracked.iter().map(|id| id.to_string()).collect(),
+                    owner: convoy.assignee.clone(),
+                    human: convoy.labels.iter().any(|label| label == "human"),
+                }
+            })
+            .collect();
+
+        convoys.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(convoys)
+    }
+}
+
+impl DashboardDataSource for FsDataSource {
+    fn fetch(&self) -> Result<DashboardData, DashboardError> {
+        if !self.is_available() {
+            return Err(DashboardError::NotAvailable);
+        }
+
+        let rigs = self.read_rigs()?;
+        let agents = self.read_agents();
+        let convoys = self.read_convoys(&rigs)?;
+
+        Ok(DashboardData {
+            agents,
+            convoys,
+            rigs,
+        })
+    }
+
+    fn is_available(&self) -> bool {
+        self.town_path.is_dir()
+    }
+
+    /// The town root, its rigs, their agent directories and every `.beads/` store.
+    ///
+    /// Each directory is listed explicitly since file watching is not recursive on
+    /// every platform.
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        if !self.is_available() {
+            return Vec::new();
+        }
+
+        let mut paths = vec![
+            self.town_path.clone(),
+            self.town_path.join(".beads"),
+            self.town_path.join(".beads").join(formula::FORMULAS_DIR),
+        ];
+        for name in TOWN_AGENTS {
+            paths.push(self.town_path.join(name));
+        }
+
+        for rig in self.read_rigs().unwrap_or_default() {
+            let rig_path = PathBuf::from(&rig.path);
+            paths.push(rig_path.join(".beads"));
+            paths.push(rig_path.join(".beads").join(formula::FORMULAS_DIR));
+            for group in ["crew", "polecats", "hooks", ".agents"] {
+                let group_path = rig_path.join(group);
+                paths.extend(
+                    subdirectories(&group_path)
+                        .into_iter()
+                        .map(|(_, path)| path),
+                );
+                paths.push(group_path);
+            }
+            paths.push(rig_path);
+        }
+
+        paths.retain(|path| path.is_dir());
+        paths.sort();
+        paths
+    }
+}
+
+/// Contents of `<agent>/state.json`
+#[derive(Deserialize, Default)]
+struct AgentStateFile {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    token_usage: Option<TokenUsageFile>,
+    #[serde(default)]
+    context_fill: Option<f32>,
+}
+
+#[derive(Deserialize)]
+struct TokenUsageFile {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+/// Reads an agent's state file. A state file that can't be understood marks only
+/// that agent as errored, so the rest of the dashboard still loads.
+fn read_agent(name: String, path: &Path, hooked: bool) -> AgentInfo {
+    let state_path = path.join(AGENT_STATE_FILE);
+    let state = match std::fs::read_to_string(&state_path) {
+        Ok(contents) => match serde_json::from_str::<AgentStateFile>(&contents) {
+            Ok(state) => state,
+            Err(err) => {
+                log::warn!("failed to parse {}: {}", state_path.display(), err);
+                return AgentInfo {
+                    name,
+                    status: AgentStatus::Error(format!("invalid {}: {}", AGENT_STATE_FILE, err)),
+                    token_usage: None,
+                    context_fill: None,
+                };
+            }
+        },
+        Err(_) => AgentStateFile::default(),
+    };
+
+    let status = match state.status.as_deref() {
+        Some(status) => parse_agent_status(status, state.error).unwrap_or_else(|| {
+            log::warn!(
+                "{}: unknown agent status '{}'",
+                state_path.display(),
+                status
+            );
+            AgentStatus::Error(format!("unknown status '{}'", status))
+        }),
+        None if state.error.is_some() => AgentStatus::Error(state.error.unwrap_or_default()),
+        None if hooked => AgentStatus::Active,
+        None => AgentStatus::Idle,
+    };
+
+    AgentInfo {
+        name,
+        status,
+        token_usage: state.token_usage.map(|usage| TokenUsage {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+        }),
+        context_fill: state.context_fill.map(|fill| fill.clamp(0.0, 1.0)),
+    }
+}
+
+/// Returns the non-hidden subdirectories of `dir` as `(name, path)` pairs
+fn subdirectories(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.to_string();
+            (!name.starts_with('.')).then_some((name, path))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, contents: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn mkdir(root: &Path, relative: &str) {
+        fs::create_dir_all(root.join(relative)).unwrap();
+    }
+
+    fn fixture_town() -> TempDir {
+        let town = TempDir::new().unwrap();
+        let root = town.path();
+
+        mkdir(root, "mayor");
+        mkdir(root, "notes");
+
+        mkdir(root, "frontend/.beads");
+        mkdir(root, "frontend/crew/alice");
+        mkdir(root, "frontend/polecats/toast");
+        mkdir(root, "frontend/polecats/nux");
+        mkdir(root, "frontend/hooks/toast");
+        write(
+            root,
+            "frontend/.beads/formulas/release.formula.toml",
+            "formula = \"release\"\n",
+        );
+        write(
+            root,
+            "frontend/polecats/nux/state.json",
+            r#"{"status": "active", "token_usage": {"input_tokens": 1500, "output_tokens": 500}, "context_fill": 0.75}"#,
+        );
+        write(
+            root,
+            "frontend/crew/alice/state.json",
+            r#"{"status": "error", "error": "rate limited"}"#,
+        );
+
+        mkdir(root, "backend/.agents/witness-backend");
+        mkdir(root, "backend/.agents/scratch");
+
+        write(
+            root,
+            ".beads/issues.jsonl",
+            concat!(
+                r#"{"id": "hq-cv-1", "title": "Auth rewrite", "status": "open", "issue_type": "convoy", "assignee": "mayor", "labels": ["human"], "dependencies": [{"depends_on_id": "fe-1"}, {"depends_on_id": "fe-2"}]}"#,
+                "\n",
+                r#"{"id": "hq-cv-2", "status": "closed", "issue_type": "convoy"}"#,
+                "\n",
+            ),
+        );
+        write(
+            root,
+            "frontend/.beads/issues.jsonl",
+            concat!(
+                r#"{"id": "fe-1", "title": "Add login form", "status": "closed", "issue_type": "task", "assignee": "frontend/polecats/nux", "started_at": "2026-01-05T09:00:00Z", "closed_at": "2026-01-05T11:30:00Z"}"#,
+                "\n\n",
+                r#"{"id": "fe-2", "status": "in_progress", "issue_type": "bug", "assignee": "frontend/polecats/toast", "updated_at": "2026-01-05T12:00:00Z", "dependencies": [{"depends_on_id": "fe-1", "type": "blocks"}, {"depends_on_id": "hq-1", "type": "related"}]}"#,
+                "\n",
+            ),
+        );
+
+        town
+    }
+
+    #[test]
+    fn test_missing_town_is_not_available() {
+        let source = FsDataSource::new("/nonexistent/gt");
+
+        assert!(!source.is_available());
+        assert!(matches!(source.fetch(), Err(DashboardError::NotAvailable)));
+    }
+
+    #[test]
+    fn test_fetch_reads_rigs() {
+        let town = fixture_town();
+        let data = FsDataSource::new(town.path()).fetch().unwrap();
+
+        let names: Vec<&str> = data.rigs.iter().map(|rig| rig.name.as_str()).collect();
+        assert_eq!(names, vec!["backend", "frontend"]);
+        assert_eq!(
+            data.rigs[1].path,
+            town.path().join("frontend").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_fetch_reads_agents() {
+        let town = fixture_town();
+        let data = FsDataSource::new(town.path()).fetch().unwrap();
+
+        let names: Vec<&str> = data
+            .agents
+            .iter()
+            .map(|agent| agent.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "backend/witness-backend",
+                "frontend/crew/alice",
+                "frontend/polecats/nux",
+                "frontend/polecats/toast",
+                "mayor",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_agent_status_from_state_file_and_hooks() {
+        let town = fixture_town();
+        let data = FsDataSource::new(town.path()).fetch().unwrap();
+        let agent = |name: &str| data.agents.iter().find(|a| a.name == name).unwrap();
+
+        assert_eq!(
+            agent("frontend/crew/alice").status,
+            AgentStatus::Error("rate limited".into())
+        );
+        assert_eq!(agent("frontend/polecats/toast").status, AgentStatus::Active);
+        assert_eq!(agent("mayor").status, AgentStatus::Idle);
+
+        let nux = agent("frontend/polecats/nux");
+        assert_eq!(nux.status, AgentStatus::Active);
+        assert_eq!(nux.context_fill, Some(0.75));
+        let usage = nux.token_usage.as_ref().unwrap();
+        assert_eq!(usage.input_tokens, 1500);
+        assert_eq!(usage.output_tokens, 500);
+    }
+
+    #[test]
+    fn test_fetch_computes_convoy_progress() {
+        let town = fixture_town();
+        let data = FsDataSource::new(town.path()).fetch().unwrap();
+
+        assert_eq!(data.convoys.len(), 2);
+        assert_eq!(data.convoys[0].id, "hq-cv-1");
+        assert_eq!(data.convoys[0].progress, 0.5);
+        assert_eq!(data.convoys[0].name, "Auth rewrite");
+        assert_eq!(data.convoys[0].issues, vec!["fe-1", "fe-2"]);
+        assert_eq!(data.convoys[0].owner.as_deref(), Some("mayor"));
+        assert!(data.convoys[0].human);
+        assert_eq!(data.convoys[1].id, "hq-cv-2");
+        assert_eq!(data.convoys[1].progress, 1.0);
+        assert_eq!(data.convoys[1].display_name(), "hq-cv-2");
+        assert!(!data.convoys[1].human);
+    }
+
+    #[test]
+    fn test_read_structure() {
+        let town = fixture_town();
+        let root = town.path();
+        let structure = FsDataSource::new(root).read_structure().unwrap();
+
+        let rigs: Vec<&str> = structure.rigs.iter().map(|rig| rig.name.as_str()).collect();
+        assert_eq!(rigs, vec!["backend", "frontend"]);
+        assert_eq!(structure.agents.len(), 5);
+
+        assert_eq!(structure.crew.len(), 1);
+        assert_eq!(structure.crew[0].agent_name(), "frontend/crew/alice");
+
+        assert_eq!(structure.hooks.len(), 1);
+        assert_eq!(structure.hooks[0].id(), "frontend/toast");
+        assert_eq!(structure.hooks[0].path, root.join("frontend/hooks/toast"));
+
+        let members = &structure.convoy_issues["hq-cv-1"];
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].id, "fe-1");
+        assert_eq!(members[0].title.as_deref(), Some("Add login form"));
+        assert_eq!(members[0].status, "closed");
+        assert_eq!(members[0].slung_at.as_deref(), Some("2026-01-05T09:00:00Z"));
+        assert_eq!(
+            members[0].completed_at.as_deref(),
+            Some("2026-01-05T11:30:00Z")
+        );
+        assert_eq!(
+            members[1].assignee.as_deref(),
+            Some("frontend/polecats/toast")
+        );
+        assert_eq!(members[1].depends_on, vec!["fe-1".to_string()]);
+        assert_eq!(members[1].slung_at.as_deref(), Some("2026-01-05T12:00:00Z"));
+        assert_eq!(members[1].completed_at, None);
+        assert!(structure.convoy_issues["hq-cv-2"].is_empty());
+
+        let issue = |id: &str| structure.issues.iter().find(|i| i.id() == id).unwrap();
+        assert_eq!(structure.issues.len(), 4);
+        assert_eq!(issue("hq-cv-1").rig, None);
+        assert_eq!(issue("fe-2").rig.as_deref(), Some("frontend"));
+        assert_eq!(issue("fe-2").beads_dir, root.join("frontend/.beads"));
+
+        assert_eq!(structure.formulas.len(), 1);
+        assert_eq!(structure.formulas[0].id(), "frontend/release");
+        assert!(structure.formulas[0].modified.is_some());
+    }
+
+    #[test]
+    fn test_watch_paths_cover_rigs_agents_and_beads() {
+        let town = fixture_town();
+        let root = town.path();
+        let paths = FsDataSource::new(root).watch_paths();
+
+        for expected in [
+            "",
+            ".beads",
+            "mayor",
+            "frontend",
+            "frontend/.beads",
+            "frontend/crew",
+            "frontend/polecats/nux",
+            "frontend/hooks/toast",
+            "backend/.agents/witness-backend",
+        ] {
+            let expected = if expected.is_empty() {
+                root.to_path_buf()
+            } else {
+                root.join(expected)
+            };
+            assert!(paths.contains(&expected), "missing {:?}", expected);
+        }
+        assert!(!paths.contains(&root.join("notes")));
+        assert!(!paths.contains(&root.join("backend/.beads")));
+    }
+
+    #[test]
+    fn test_bad_state_file_only_fails_its_agent() {
+        let town = fixture_town();
+        write(town.path(), "frontend/crew/alice/state.json", "{not json");
+        write(
+            town.path(),
+            "frontend/polecats/nux/state.json",
+            r#"{"status": "hibernating"}"#,
+        );
+        write(
+            town.path(),
+            "frontend/polecats/toast/state.json",
+            r#"{"status": "working"}"#,
+        );
+
+        let data = FsDataSource::new(town.path()).fetch().unwrap();
+        let agent = |name: &str| data.agents.iter().find(|a| a.name == name).unwrap();
+        assert!(matches!(
+            &agent("frontend/crew/alice").status,
+            AgentStatus::Error(message) if message.contains("state.json")
+        ));
+        assert_eq!(
+            agent("frontend/polecats/nux").status,
+            AgentStatus::Error("unknown status 'hibernating'".into())
+        );
+        assert_eq!(agent("frontend/polecats/toast").status, AgentStatus::Active);
+        assert_eq!(agent("mayor").status, AgentStatus::Idle);
+        assert!(!data.rigs.is_empty());
+        assert!(!data.convoys.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_issue_line_reports_line_number() {
+        let town = fixture_town();
+        write(
+            town.path(),
+            "backend/.beads/issues.jsonl",
+            "{\"id\": \"be-1\"}\n{oops\n",
+        );
+
+        let result = FsDataSource::new(town.path()).fetch();
+        assert!(matches!(
+            result,
+            Err(DashboardError::ParseError(message)) if message.contains("issues.jsonl:2")
+        ));
+    }
+}
@@ -0,0 +1,172 @@
+use serde::de::DeserializeOwned;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::dashboard_buffer::DashboardError;
+
+/// Errors produced when running the `gt` command
+#[derive(Debug, Clone)]
+pub enum GtCommandError {
+    /// The `gt` binary could not be found
+    NotFound(PathBuf),
+    /// The process could not be spawned
+    Spawn(String),
+    /// The command ran but exited unsuccessfully
+    Failed {
+        command: String,
+        status: Option<i32>,
+        stderr: String,
+    },
+    /// The command output could not be parsed
+    Parse { command: String, message: String },
+}
+
+impl std::fmt::Display for GtCommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GtCommandError::NotFound(path) => write!(f, "{} not found", path.display()),
+            GtCommandError::Spawn(msg) => write!(f, "Failed to run gt: {}", msg),
+            GtCommandError::Failed {
+                command,
+                status,
+                stderr,
+            } => {
+                match status {
+                    Some(code) => write!(f, "`{}` exited with status {}", command, code)?,
+                    None => write!(f, "`{}` was terminated", command)?,
+                }
+                if !stderr.is_empty() {
+                    write!(f, ": {}", stderr)?;
+                }
+                Ok(())
+            }
+            GtCommandError::Parse { command, message } => {
+                write!(f, "`{}` returned invalid output: {}", command, message)
+            }
+        }
+    }
+}
+
+impl From<GtCommandError> for DashboardError {
+    fn from(error: GtCommandError) -> Self {
+        match error {
+            GtCommandError::NotFound(_) => DashboardError::NotAvailable,
+            GtCommandError::Parse { .. } => DashboardError::ParseError(error.to_string()),
+            GtCommandError::Spawn(_) | GtCommandError::Failed { .. } => {
+                DashboardError::FetchFailed(error.to_string())
+            }
+        }
+    }
+}
+
+/// Runs `gt` subcommands against a town.
+///
+/// The binary defaults to `gt` on `PATH` and commands run with the town directory as
+/// their working directory, so tests can substitute a scripted fake. The same runner
+/// drives `bd` when created with `GtCli::new("bd")`.
+#[derive(Clone, Debug)]
+pub struct GtCli {
+    binary: PathBuf,
+    town_path: Option<PathBuf>,
+}
+
+impl Default for GtCli {
+    fn default() -> Self {
+        Self::new("gt")
+    }
+}
+
+impl GtCli {
+    pub fn new(binary: impl Into<PathBuf>) -> Self {
+        Self {
+            binary: binary.into(),
+            town_path: None,
+        }
+    }
+
+    pub fn town_path(mut self, town_path: impl Into<PathBuf>) -> Self {
+        self.town_path = Some(town_path.into());
+        self
+    }
+
+    pub fn binary(&self) -> &Path {
+        &self.binary
+    }
+
+    /// Runs `gt <args>` and returns its stdout
+    pub fn run<I, S>(&self, args: I) -> Result<String, GtCommandError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let args: Vec<_> = args
+            .into_iter()
+            .map(|arg| arg.as_ref().to_os_string())
+            .collect();
+        let command = self.describe(&args);
+
+        let mut process = Command::new(&self.binary);
+        process.args(&args);
+        if let Some(town_path) = &self.town_path {
+            process.current_dir(town_path);
+        }
+
+        let output = process.output().map_err(|err| {
+            if err.kind() == std::io::ErrorKind::NotFound {
+                GtCommandError::NotFound(self.binary.clone())
+            } else {
+                GtCommandError::Spawn(err.to_string())
+            }
+        })?;
+
+        if !output.status.success() {
+            return Err(GtCommandError::Failed {
+                command,
+                status: output.status.code(),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+
+        String::from_utf8(output.stdout).map_err(|err| GtCommandError::Parse {
+            command,
+            message: err.to_string(),
+        })
+    }
+
+    /// Runs `gt <args>` and deserializes its stdout as JSON
+    pub fn run_json<T, I, S>(&self, args: I) -> Result<T, GtCommandError>
+    where
+        T: DeserializeOwned,
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let args: Vec<_> = args
+            .into_iter()
+            .map(|arg| arg.as_ref().to_os_string())
+            .collect();
+        let stdout = self.run(&args)?;
+        serde_json::from_str(&stdout).map_err(|err| GtCommandError::Parse {
+            command: self.describe(&args),
+            message: err.to_string(),
+        })
+    }
+
+    /// Returns whether the `gt` binary can be executed
+    pub fn is_available(&self) -> bool {
+        self.run(["version"]).is_ok()
+    }
+
+    fn describe(&self, args: &[std::ffi::OsString]) -> String {
+        let mut command = self
+            .binary
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "gt".into());
+        for arg in args {
+            command.push(' ');
+            command.push_str(&arg.to_string_lossy());
+        }
+        command
+    }
+}
@@ -0,0 +1,310 @@
+use serde::Deserialize;
+
+use crate::dashboard_buffer::{
+    AgentInfo, AgentStatus, ConvoyInfo, DashboardData, DashboardDataSource, DashboardError,
+    RigInfo, TokenUsage,
+};
+use crate::gt_cli::GtCli;
+
+/// Reads dashboard data by running the `gt` command.
+///
+/// Uses `gt agents --json`, `gt convoy list --json` and `gt rig list --json`.
+pub struct GtDataSource {
+    cli: GtCli,
+}
+
+impl GtDataSource {
+    pub fn new(cli: GtCli) -> Self {
+        Self { cli }
+    }
+
+    pub fn cli(&self) -> &GtCli {
+        &self.cli
+    }
+
+    fn fetch_agents(&self) -> Result<Vec<AgentInfo>, DashboardError> {
+        let agents: Vec<GtAgent> = self.cli.run_json(["agents", "--json"])?;
+        Ok(agents
+            .into_iter()
+            .map(|agent| agent.into_agent_info())
+            .collect())
+    }
+
+    fn fetch_convoys(&self) -> Result<Vec<ConvoyInfo>, DashboardError> {
+        let convoys: Vec<GtConvoy> = self.cli.run_json(["convoy", "list", "--json"])?;
+        Ok(convoys
+            .into_iter()
+            .map(|convoy| convoy.into_convoy_info())
+            .collect())
+    }
+
+    fn fetch_rigs(&self) -> Result<Vec<RigInfo>, DashboardError> {
+        let rigs: Vec<GtRig> = self.cli.run_json(["rig", "list", "--json"])?;
+        Ok(rigs.into_iter().map(|rig| rig.into_rig_info()).collect())
+    }
+}
+
+impl DashboardDataSource for GtDataSource {
+    fn fetch(&self) -> Result<DashboardData, DashboardError> {
+        Ok(DashboardData {
+            agents: self.fetch_agents()?,
+            convoys: self.fetch_convoys()?,
+            rigs: self.fetch_rigs()?,
+        })
+    }
+
+    fn is_available(&self) -> bool {
+        self.cli.is_available()
+    }
+}
+
+/// An entry of `gt agents --json`, also used by the `gt dashboard` web API
+#[derive(Deserialize)]
+pub(crate) struct GtAgent {
+    name: String,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    token_usage: Option<GtTokenUsage>,
+    #[serde(default)]
+    context_fill: Option<f32>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct GtTokenUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+/// Maps an agent status as `gt` and agents' state files spell it, or `None` for a
+/// status Belvedere doesn't know
+pub(crate) fn parse_agent_status(status: &str, error: Option<String>) -> Option<AgentStatus> {
+    match status {
+        "active" | "working" | "running" => Some(AgentStatus::Active),
+        "idle" | "stopped" => Some(AgentStatus::Idle),
+        "error" | "stuck" | "dead" => Some(AgentStatus::Error(
+            error.unwrap_or_else(|| "unknown error".into()),
+        )),
+        _ => None,
+    }
+}
+
+impl GtAgent {
+    /// An unknown status marks just this agent with an error, like the filesystem
+    /// source does, rather than failing the whole fetch
+    pub(crate) fn into_agent_info(self) -> AgentInfo {
+        let status = match self.status.as_deref() {
+            Some(status) => parse_agent_status(status, self.error).unwrap_or_else(|| {
+                log::warn!("agent '{}' has unknown status '{}'", self.name, status);
+                AgentStatus::Error(format!("unknown status '{}'", status))
+            }),
+            None => AgentStatus::Idle,
+        };
+
+        AgentInfo {
+            name: self.name,
+            status,
+            token_usage: self.token_usage.map(|usage| TokenUsage {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+            }),
+            context_fill: self.context_fill.map(|fill| fill.clamp(0.0, 1.0)),
+        }
+    }
+}
+
+/// An entry of `gt convoy list --json`
+#[derive(Deserialize)]
+pub(crate) struct GtConvoy {
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    completed: usize,
+    #[serde(default)]
+    total: usize,
+    #[serde(default)]
+    issues: Vec<String>,
+    #[serde(default)]
+    owner: Option<String>,
+    #[serde(default)]
+    human: bool,
+}
+
+impl GtConvoy {
+    pub(crate) fn into_convoy_info(self) -> ConvoyInfo {
+        let progress = if self.total == 0 {
+            0.0
+        } else {
+            (self.completed.min(self.total)) as f32 / self.total as f32
+        };
+        ConvoyInfo {
+            id: self.id,
+            name: self.name,
+            progress,
+            issues: self.issues,
+            owner: self.owner,
+            human: self.human,
+        }
+    }
+}
+
+/// An entry of `gt rig list --json`
+#[derive(Deserialize)]
+pub(crate) struct GtRig {
+    name: String,
+    path: String,
+}
+
+impl GtRig {
+    pub(crate) fn into_rig_info(self) -> RigInfo {
+        RigInfo {
+            name: self.name,
+            path: self.path,
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::fake_gt::FakeGt;
+
+    const AGENTS_JSON: &str = r#"[
+        {"name": "frontend/polecats/toast", "status": "working", "token_usage": {"input_tokens": 1200, "output_tokens": 300}, "context_fill": 0.4},
+        {"name": "frontend/crew/alice", "status": "idle"},
+        {"name": "backend/polecats/nux", "status": "stuck", "error": "tests failing"}
+    ]"#;
+    const CONVOYS_JSON: &str = r#"[
+        {"id": "hq-cv-1", "name": "Auth rewrite", "completed": 1, "total": 4,
+         "issues": ["fe-1", "fe-2", "fe-3", "fe-4"], "owner": "mayor", "human": true},
+        {"id": "hq-cv-2"}
+    ]"#;
+    const RIGS_JSON: &str = r#"[{"name": "frontend", "path": "/gt/frontend"}]"#;
+
+    fn healthy_gt() -> FakeGt {
+        FakeGt::new()
+            .respond("agents --json", AGENTS_JSON)
+            .respond("convoy list --json", CONVOYS_JSON)
+            .respond("rig list --json", RIGS_JSON)
+    }
+
+    #[test]
+    fn test_fetch_parses_gt_output() {
+        let gt = healthy_gt();
+        let source = GtDataSource::new(gt.cli());
+
+        assert!(source.is_available());
+        let data = source.fetch().unwrap();
+
+        assert_eq!(data.agents.len(), 3);
+        assert_eq!(data.agents[0].name, "frontend/polecats/toast");
+        assert_eq!(data.agents[0].status, AgentStatus::Active);
+        assert_eq!(data.agents[0].context_fill, Some(0.4));
+        assert_eq!(
+            data.agents[0].token_usage.as_ref().unwrap().input_tokens,
+            1200
+        );
+        assert_eq!(data.agents[1].status, AgentStatus::Idle);
+        assert_eq!(
+            data.agents[2].status,
+            AgentStatus::Error("tests failing".into())
+        );
+
+        assert_eq!(data.convoys[0].id, "hq-cv-1");
+        assert_eq!(data.convoys[0].progress, 0.25);
+        assert_eq!(data.convoys[0].name, "Auth rewrite");
+        assert_eq!(data.convoys[0].issues.len(), 4);
+        assert_eq!(data.convoys[0].owner.as_deref(), Some("mayor"));
+        assert!(data.convoys[0].human);
+        assert!(!data.convoys[1].human);
+        assert_eq!(data.convoys[1].progress, 0.0);
+
+        assert_eq!(data.rigs[0].name, "frontend");
+        assert_eq!(data.rigs[0].path, "/gt/frontend");
+    }
+
+    #[test]
+    fn test_commands_run_in_town_directory() {
+        let gt = healthy_gt();
+        let town = tempfile::TempDir::new().unwrap();
+        let source = GtDataSource::new(gt.cli().town_path(town.path()));
+
+        source.fetch().unwrap();
+
+        let calls = gt.calls();
+        let commands: Vec<&str> = calls.iter().map(|(_, args)| args.as_str()).collect();
+        assert_eq!(
+            commands,
+            vec!["agents --json", "convoy list --json", "rig list --json"]
+        );
+        let town_path = town.path().canonicalize().unwrap();
+        assert!(
+            calls
+                .iter()
+                .all(|(cwd, _)| cwd.canonicalize().unwrap() == town_path)
+        );
+    }
+
+    #[test]
+    fn test_missing_binary_is_not_available() {
+        let source = GtDataSource::new(GtCli::new("/nonexistent/bin/gt"));
+
+        assert!(!source.is_available());
+        assert!(matches!(source.fetch(), Err(DashboardError::NotAvailable)));
+    }
+
+    #[test]
+    fn test_failing_command_is_fetch_failed() {
+        let gt = FakeGt::new().respond("agents --json", "[]").fail(
+            "convoy list --json",
+            2,
+            "not in a Gas Town workspace",
+        );
+        let source = GtDataSource::new(gt.cli());
+
+        match source.fetch() {
+            Err(DashboardError::FetchFailed(message)) => {
+                assert!(message.contains("gt convoy list --json"));
+                assert!(message.contains("status 2"));
+                assert!(message.contains("not in a Gas Town workspace"));
+            }
+            other => panic!("expected FetchFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_json_is_parse_error() {
+        let gt = FakeGt::new().respond("agents --json", "agents: toast, nux");
+        let source = GtDataSource::new(gt.cli());
+
+        assert!(matches!(
+            source.fetch(),
+            Err(DashboardError::ParseError(message)) if message.contains("gt agents --json")
+        ));
+    }
+
+    #[test]
+    fn test_unknown_agent_status_is_agent_error() {
+        let gt = FakeGt::new()
+            .respond(
+                "agents --json",
+                r#"[{"name": "toast", "status": "hibernating"}, {"name": "nux", "status": "idle"}]"#,
+            )
+            .respond("convoy list --json", CONVOYS_JSON)
+            .respond("rig list --json", RIGS_JSON);
+        let source = GtDataSource::new(gt.cli());
+
+        let data = source.fetch().unwrap();
+        assert_eq!(
+            data.agents[0].status,
+            AgentStatus::Error("unknown status 'hibernating'".into())
+        );
+        assert_eq!(data.agents[1].status, AgentStatus::Idle);
+        assert_eq!(data.rigs.len(), 1);
+    }
+}
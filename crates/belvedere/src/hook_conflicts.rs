@@ -0,0 +1,545 @@
+//! Detection of hooks whose work overlaps.
+//!
+//! Each hook's committed changes are read the same way a review reads them, against
+//! the merge base with its rig's default branch, and every pair of hooks in a rig is
+//! compared file by file. Changed rows are rows of each hook's merge base, so two
+//! hooks forked from different commits may be reported as overlapping a few rows off;
+//! changing the same file is reported either way.
+
+use anyhow::{Result, anyhow};
+use collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use git::repository::GitRepository as _;
+use gpui::BackgroundExecutor;
+use language::line_diff;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::hook_lifecycle::{open_repository, rig_repository};
+use crate::hook_review::{ChangedFile, HookChanges, load_hook_changes};
+use crate::town_model::{Hook, TownModel};
+
+/// How often the town compares its hooks again. Agents commit without the town's
+/// structure changing, and each analysis runs git in every changed hook.
+pub const CONFLICT_ANALYSIS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How badly two hooks' changes to a file collide, from least to most
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConflictKind {
+    /// Both hooks change the file, in different places
+    SameFile,
+    /// Both hooks change the same or adjacent rows, which git can't merge
+    OverlappingHunks,
+}
+
+impl ConflictKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ConflictKind::SameFile => "same file",
+            ConflictKind::OverlappingHunks => "overlapping hunks",
+        }
+    }
+}
+
+/// The rows a hook changed in each file, relative to its merge base
+#[derive(Clone, Debug, PartialEq)]
+pub struct HookDiff {
+    pub hook_id: String,
+    pub rig: String,
+    /// The agent owning the hook, or the hook id for an orphaned hook
+    pub owner: String,
+    /// Changed row ranges of the base text by path; an empty range marks an insertion
+    pub files: BTreeMap<String, Vec<Range<u32>>>,
+}
+
+impl HookDiff {
+    pub fn new(hook: &Hook, changes: &HookChanges) -> Self {
+        Self {
+            hook_id: hook.id(),
+            rig: hook.rig.clone(),
+            owner: Self::owner(hook),
+            files: changes
+                .files
+                .iter()
+                .map(|file| (file.path.clone(), changed_rows(file)))
+                .collect(),
+        }
+    }
+
+    fn owner(hook: &Hook) -> String {
+        hook.agent.clone().unwrap_or_else(|| hook.id())
+    }
+}
+
+fn changed_rows(file: &ChangedFile) -> Vec<Range<u32>> {
+    line_diff(
+        file.base_text.as_deref().unwrap_or_default(),
+        file.hook_text.as_deref().unwrap_or_default(),
+    )
+    .into_iter()
+    .map(|(base_rows, _)| base_rows)
+    .collect()
+}
+
+/// Whether two row ranges overlap or touch. Insertions at the same row and changes
+/// to adjacent rows count, as they do when git merges.
+fn rows_collide(a: &Range<u32>, b: &Range<u32>) -> bool {
+    a.start <= b.end && b.start <= a.end
+}
+
+/// Two hooks changing the same file
+#[derive(Clone, Debug, PartialEq)]
+pub struct HookConflict {
+    pub path: String,
+    /// Ids of the two hooks, in id order
+    pub hooks: (String, String),
+    /// Owners of `hooks`, in the same order
+    pub owners: (String, String),
+    pub kind: ConflictKind,
+    /// Base rows both hooks changed, merged per colliding pair of hunks
+    pub rows: Vec<Range<u32>>,
+}
+
+impl HookConflict {
+    pub fn involves_hook(&self, hook_id: &str) -> bool {
+        self.hooks.0 == hook_id || self.hooks.1 == hook_id
+    }
+
+    pub fn involves_owner(&self, owner: &str) -> bool {
+        self.owners.0 == owner || self.owners.1 == owner
+    }
+
+    /// One-line warning, e.g. `toast ↔ nux: src/main.rs (lines 3–5)`
+    pub fn description(&self) -> String {
+        let mut description = format!("{} ↔ {}: {}", self.owners.0, self.owners.1, self.path);
+        if let (Some(first), Some(last)) = (self.rows.first(), self.rows.last()) {
+            let start = first.start + 1;
+            let end = last.end.max(first.start + 1);
+            if start == end {
+                description.push_str(&format!(" (line {})", start));
+            } else {
+                description.push_str(&format!(" (lines {}–{})", start, end));
+            }
+        }
+        description
+    }
+}
+
+/// One cell of a `ConflictMatrix`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatrixCell {
+    /// Files both owners' hooks change
+    pub files: usize,
+    /// The worst conflict among those files
+    pub kind: ConflictKind,
+}
+
+/// Which owners conflict with which
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConflictMatrix {
+    /// Owners in at least one conflict, sorted
+    pub owners: Vec<String>,
+    /// `cells[i][j]` describes the conflicts between `owners[i]` and `owners[j]`.
+    /// The matrix is symmetric and its diagonal is empty.
+    pub cells: Vec<Vec<Option<MatrixCell>>>,
+}
+
+/// The conflicts between every active hook in the town
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConflictReport {
+    /// Sorted by path, then hooks
+    pub conflicts: Vec<HookConflict>,
+    /// Hooks whose changes couldn't be read, by id
+    pub errors: BTreeMap<String, String>,
+}
+
+impl ConflictReport {
+    /// Compares every pair of hooks in the same rig
+    pub fn from_diffs(diffs: &[HookDiff]) -> Self {
+        let mut diffs: Vec<&HookDiff> = diffs.iter().collect();
+        diffs.sort_by(|a, b| a.hook_id.cmp(&b.hook_id));
+
+        let mut conflicts = Vec::new();
+        for (index, left) in diffs.iter().enumerate() {
+            for right in &diffs[index + 1..] {
+                if left.rig != right.rig {
+                    continue;
+                }
+                for (path, left_rows) in &left.files {
+                    let Some(right_rows) = right.files.get(path) else {
+                        continue;
+                    };
+                    let rows: Vec<Range<u32>> = left_rows
+                        .iter()
+                        .flat_map(|a| {
+                            right_rows
+                                .iter()
+                                .filter(move |b| rows_collide(a, b))
+                                .map(move |b| a.start.min(b.start)..a.end.max(b.end))
+                        })
+                        .collect();
+                    conflicts.push(HookConflict {
+                        path: path.clone(),
+                        hooks: (left.hook_id.clone(), right.hook_id.clone()),
+                        owners: (left.owner.clone(), right.owner.clone()),
+                        kind: if rows.is_empty() {
+                            ConflictKind::SameFile
+                        } else {
+                            ConflictKind::OverlappingHunks
+                        },
+                        rows,
+                    });
+                }
+            }
+        }
+        conflicts.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.hooks.cmp(&b.hooks)));
+
+        Self {
+            conflicts,
+            errors: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conflicts.is_empty() && self.errors.is_empty()
+    }
+
+    /// The conflicts involving any of `hook_ids`
+    pub fn for_hooks<'a>(
+        &'a self,
+        hook_ids: &'a HashSet<String>,
+    ) -> impl Iterator<Item = &'a HookConflict> + 'a {
+        self.conflicts.iter().filter(move |conflict| {
+            hook_ids.contains(&conflict.hooks.0) || hook_ids.contains(&conflict.hooks.1)
+        })
+    }
+
+    pub fn matrix(&self) -> ConflictMatrix {
+        let owners: Vec<String> = self
+            .conflicts
+            .iter()
+            .flat_map(|conflict| [conflict.owners.0.clone(), conflict.owners.1.clone()])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let position = |owner: &str| owners.iter().position(|candidate| candidate == owner);
+
+        let mut files: BTreeMap<(usize, usize), BTreeSet<&str>> = BTreeMap::new();
+        let mut kinds: BTreeMap<(usize, usize), ConflictKind> = BTreeMap::new();
+        for conflict in &self.conflicts {
+            let (Some(a), Some(b)) = (position(&conflict.owners.0), position(&conflict.owners.1))
+            else {
+                continue;
+            };
+            if a == b {
+                continue;
+            }
+            for cell in [(a, b), (b, a)] {
+                files
+                    .entry(cell)
+                    .or_default()
+                    .insert(conflict.path.as_str());
+                let kind = kinds.entry(cell).or_insert(conflict.kind);
+                *kind = (*kind).max(conflict.kind);
+            }
+        }
+
+        let cells = (0..owners.len())
+            .map(|row| {
+                (0..owners.len())
+                    .map(|column| {
+                        let cell = (row, column);
+                        Some(MatrixCell {
+                            files: files.get(&cell)?.len(),
+                            kind: *kinds.get(&cell)?,
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        ConflictMatrix { owners, cells }
+    }
+}
+
+/// A hook owned by an agent, with the places its rig's repository may be found
+#[derive(Clone, Debug)]
+pub struct ActiveHook {
+    pub hook: Hook,
+    pub rig_path: PathBuf,
+    /// Worktrees of every hook in the rig, for rigs that aren't a checkout themselves
+    pub rig_hook_paths: Vec<PathBuf>,
+}
+
+/// The hooks worth comparing. Whether they are still on disk is checked by
+/// `analyze_hooks`, off the main thread.
+pub fn active_hooks(model: &TownModel) -> Vec<ActiveHook> {
+    model
+        .hooks()
+        .filter(|hook| hook.agent.is_some())
+        .filter_map(|hook| {
+            let rig = model.rig(&hook.rig)?;
+            Some(ActiveHook {
+                hook: hook.clone(),
+                rig_path: rig.path.clone(),
+                rig_hook_paths: model
+                    .hooks_in_rig(&hook.rig)
+                    .map(|hook| hook.path.clone())
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Diffs read by earlier analyses with the HEAD commit each was read at, by hook id
+#[derive(Clone, Debug, Default)]
+pub struct HookDiffCache(HashMap<String, (String, HookDiff)>);
+
+impl HookDiffCache {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Reads every hook's changes and compares them.
+///
+/// Hooks whose HEAD is the commit `cache` has a diff for aren't read again. The
+/// returned cache holds the diffs of this analysis's hooks only.
+pub async fn analyze_hooks(
+    hooks: Vec<ActiveHook>,
+    mut cache: HookDiffCache,
+    executor: BackgroundExecutor,
+) -> (ConflictReport, HookDiffCache) {
+    let mut diffs = Vec::new();
+    let mut errors = BTreeMap::new();
+    let mut next_cache = HookDiffCache::default();
+    for active in hooks {
+        let hook = active.hook;
+        if !hook.path.is_dir() {
+            continue;
+        }
+        let Some(repository) = rig_repository(&active.rig_path, &active.rig_hook_paths) else {
+            continue;
+        };
+        let cached = cache.0.remove(&hook.id());
+        match read_hook_diff(&hook, repository, cached, &executor).await {
+            Ok((head, diff)) => {
+                diffs.push(diff.clone());
+                next_cache.0.insert(hook.id(), (head, diff));
+            }
+            Err(err) => {
+                errors.insert(hook.id(), format!("{:#}", err));
+            }
+        }
+    }
+
+    let mut report = ConflictReport::from_diffs(&diffs);
+    report.errors = errors;
+    (report, next_cache)
+}
+
+/// The hook's diff and the HEAD commit it was read at, reusing `cached` when the
+/// hook hasn't committed since
+async fn read_hook_diff(
+    hook: &Hook,
+    repository: PathBuf,
+    cached: Option<(String, HookDiff)>,
+    executor: &BackgroundExecutor,
+) -> Result<(String, HookDiff)> {
+    let head = open_repository(&hook.path, executor)?
+        .head_sha()
+        .await
+        .ok_or_else(|| anyhow!("{} has no commits", hook.path.display()))?;
+    if let Some((cached_head, mut diff)) = cached {
+        if cached_head == head {
+            // The hook may have changed hands without new commits
+            diff.owner = HookDiff::owner(hook);
+            return Ok((head, diff));
+        }
+    }
+    let changes = load_hook_changes(repository, hook.path.clone(), executor.clone()).await?;
+    Ok((head, HookDiff::new(hook, &changes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use tempfile::TempDir;
+
+    use crate::hook_lifecycle::create_hook_worktree;
+    use crate::test_git::{init_repository, run_git};
+
+    fn diff(hook: &str, owner: &str, files: &[(&str, &[Range<u32>])]) -> HookDiff {
+        let (rig, _) = hook.split_once('/').unwrap();
+        HookDiff {
+            hook_id: hook.into(),
+            rig: rig.into(),
+            owner: owner.into(),
+            files: files
+                .iter()
+                .map(|(path, rows)| (path.to_string(), rows.to_vec()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_conflicts_and_matrix() {
+        let report = ConflictReport::from_diffs(&[
+            diff(
+                "frontend/toast",
+                "toast",
+                &[("README.md", &[2..4]), ("a.rs", &[0..1])],
+            ),
+            diff(
+                "frontend/nux",
+                "nux",
+                &[("README.md", &[4..4]), ("a.rs", &[5..6])],
+            ),
+            diff("frontend/slit", "slit", &[("b.rs", &[0..3])]),
+            // Same path, but a different repository
+            diff("backend/max", "max", &[("README.md", &[2..4])]),
+        ]);
+
+        assert_eq!(
+            report.conflicts,
+            vec![
+                HookConflict {
+                    path: "README.md".into(),
+                    hooks: ("frontend/nux".into(), "frontend/toast".into()),
+                    owners: ("nux".into(), "toast".into()),
+                    kind: ConflictKind::OverlappingHunks,
+                    rows: vec![2..4],
+                },
+                HookConflict {
+                    path: "a.rs".into(),
+                    hooks: ("frontend/nux".into(), "frontend/toast".into()),
+                    owners: ("nux".into(), "toast".into()),
+                    kind: ConflictKind::SameFile,
+                    rows: Vec::new(),
+                },
+            ]
+        );
+        assert_eq!(
+            report.conflicts[0].description(),
+            "nux ↔ toast: README.md (lines 3–4)"
+        );
+        assert_eq!(report.conflicts[1].description(), "nux ↔ toast: a.rs");
+
+        let cell = Some(MatrixCell {
+            files: 2,
+            kind: ConflictKind::OverlappingHunks,
+        });
+        assert_eq!(
+            report.matrix(),
+            ConflictMatrix {
+                owners: vec!["nux".into(), "toast".into()],
+                cells: vec![vec![None, cell], vec![cell, None]],
+            }
+        );
+
+        let convoy_hooks = HashSet::from_iter(["frontend/slit".to_string()]);
+        assert_eq!(report.for_hooks(&convoy_hooks).count(), 0);
+        let convoy_hooks = HashSet::from_iter(["frontend/toast".to_string()]);
+        assert_eq!(report.for_hooks(&convoy_hooks).count(), 2);
+    }
+
+    #[gpui::test]
+    async fn test_analyze_hooks(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let town = TempDir::new().unwrap();
+        let rig = town.path().join("frontend");
+        init_repository(&rig, &[("README.md", "one\ntwo\nthree\nfour\nfive\n")]);
+
+        let active = |name: &str, path: PathBuf| ActiveHook {
+            hook: Hook {
+                name: name.into(),
+                rig: "frontend".into(),
+                path,
+                agent: Some(format!("frontend/polecats/{}", name)),
+            },
+            rig_path: rig.clone(),
+            rig_hook_paths: Vec::new(),
+        };
+        let mut hooks = Vec::new();
+        for (name, readme) in [
+            ("toast", "one\nTWO\nthree\nfour\nfive\n"),
+            ("nux", "one\ntwo\nthree\nfour\nFIVE\n"),
+            ("slit", "one\n2\nthree\nfour\nfive\n"),
+        ] {
+            let path = create_hook_worktree(rig.clone(), Vec::new(), name.into(), cx.executor())
+                .await
+                .unwrap();
+            std::fs::write(path.join("README.md"), readme).unwrap();
+            run_git(&path, &["commit", "-qam", "Edit README"]);
+            hooks.push(active(name, path));
+        }
+        // Not a worktree
+        std::fs::create_dir_all(rig.join("hooks/furiosa")).unwrap();
+        hooks.push(active("furiosa", rig.join("hooks/furiosa")));
+        // Removed since the town was scanned
+        hooks.push(active("max", rig.join("hooks/max")));
+
+        let summary = |report: &ConflictReport| -> Vec<(String, ConflictKind)> {
+            report
+                .conflicts
+                .iter()
+                .map(|conflict| (conflict.description(), conflict.kind))
+                .collect()
+        };
+        let (report, cache) =
+            analyze_hooks(hooks.clone(), HookDiffCache::default(), cx.executor()).await;
+        assert_eq!(
+            summary(&report),
+            vec![
+                (
+                    "frontend/polecats/nux ↔ frontend/polecats/slit: README.md".to_string(),
+                    ConflictKind::SameFile
+                ),
+                (
+                    "frontend/polecats/nux ↔ frontend/polecats/toast: README.md".to_string(),
+                    ConflictKind::SameFile
+                ),
+                (
+                    "frontend/polecats/slit ↔ frontend/polecats/toast: README.md (line 2)"
+                        .to_string(),
+                    ConflictKind::OverlappingHunks
+                ),
+            ]
+        );
+        assert_eq!(
+            report.errors.keys().collect::<Vec<_>>(),
+            vec!["frontend/furiosa"]
+        );
+        assert_eq!(cache.len(), 3);
+
+        // Only slit committed since, so only its diff is read again
+        let slit = &hooks[2].hook.path;
+        std::fs::write(slit.join("README.md"), "one\ntwo\nthree\nFOUR\nfive\n").unwrap();
+        run_git(slit, &["commit", "-qam", "Edit README again"]);
+        let (report, cache) = analyze_hooks(hooks, cache, cx.executor()).await;
+        assert_eq!(
+            summary(&report),
+            vec![
+                (
+                    "frontend/polecats/nux ↔ frontend/polecats/slit: README.md (lines 4–5)"
+                        .to_string(),
+                    ConflictKind::OverlappingHunks
+                ),
+                (
+                    "frontend/polecats/nux ↔ frontend/polecats/toast: README.md".to_string(),
+                    ConflictKind::SameFile
+                ),
+                (
+                    "frontend/polecats/slit ↔ frontend/polecats/toast: README.md".to_string(),
+                    ConflictKind::SameFile
+                ),
+            ]
+        );
+        assert_eq!(cache.len(), 3);
+    }
+}
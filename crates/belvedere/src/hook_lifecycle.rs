@@ -0,0 +1,832 @@
+use anyhow::{Context as _, Result, anyhow};
+use collections::BTreeMap;
+use git::repository::{GitRepository, RealGitRepository};
+use gpui::{
+    App, BackgroundExecutor, Context, Entity, EventEmitter, FocusHandle, Focusable, Render,
+    SharedString, Subscription, Task, Window, div, prelude::*, px,
+};
+use std::path::{Path, PathBuf};
+use ui::{
+    ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, ContextMenu, DropdownMenu, LabelSize,
+};
+
+use crate::dashboard_buffer::AgentStatus;
+use crate::town_item::{TownItem, TownItemEvent};
+use crate::town_model::{TownModel, TownModelEvent};
+
+/// Where a hook is in its life: Created → Active → Suspended → Completed → Archived
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HookState {
+    /// The worktree exists but no work has been hooked to it yet
+    Created,
+    /// The owning agent is working in it
+    Active,
+    /// Work is hooked but the owner isn't running
+    Suspended,
+    /// All work hooked by the owner is closed
+    Completed,
+    /// Still registered with git, but the worktree directory is gone
+    Archived,
+}
+
+impl HookState {
+    pub fn label(self) -> &'static str {
+        match self {
+            HookState::Created => "Created",
+            HookState::Active => "Active",
+            HookState::Suspended => "Suspended",
+            HookState::Completed => "Completed",
+            HookState::Archived => "Archived",
+        }
+    }
+}
+
+/// Infers a hook's lifecycle state.
+///
+/// `open_work` and `closed_work` count the beads issues assigned to the owner.
+pub fn hook_state(
+    on_disk: bool,
+    owner_status: Option<&AgentStatus>,
+    open_work: usize,
+    closed_work: usize,
+) -> HookState {
+    if !on_disk {
+        return HookState::Archived;
+    }
+    match owner_status {
+        Some(AgentStatus::Active) => HookState::Active,
+        _ if open_work > 0 => HookState::Suspended,
+        _ if closed_work > 0 => HookState::Completed,
+        _ => HookState::Created,
+    }
+}
+
+/// What git knows about a worktree under a rig's `hooks/` directory
+#[derive(Clone, Debug, PartialEq)]
+pub struct HookWorktree {
+    pub path: PathBuf,
+    pub branch: String,
+    pub sha: String,
+    /// Whether the worktree has uncommitted changes; `None` if it couldn't be read
+    pub dirty: Option<bool>,
+    pub on_disk: bool,
+}
+
+/// The worktrees of one rig, or why they couldn't be listed
+pub type RigWorktrees = Result<Vec<HookWorktree>, String>;
+
+/// A hook with its lifecycle state and git details
+#[derive(Clone, Debug, PartialEq)]
+pub struct HookRecord {
+    pub rig: String,
+    pub name: String,
+    pub path: PathBuf,
+    /// The agent owning the hook, if it is still around
+    pub owner: Option<String>,
+    pub state: HookState,
+    /// `None` when the hook directory isn't a worktree git knows about
+    pub branch: Option<String>,
+    pub head: Option<String>,
+    pub dirty: Option<bool>,
+}
+
+impl HookRecord {
+    pub fn id(&self) -> String {
+        format!("{}/{}", self.rig, self.name)
+    }
+
+    /// A hook on disk that no live agent owns
+    pub fn is_orphaned(&self) -> bool {
+        self.owner.is_none() && self.state != HookState::Archived
+    }
+}
+
+/// The repository a rig's hooks are worktrees of.
+///
+/// This is the rig directory itself when it is a git checkout, otherwise the
+/// repository of any of its existing hooks.
+pub fn rig_repository(rig_path: &Path, hook_paths: &[PathBuf]) -> Option<PathBuf> {
+    std::iter::once(rig_path)
+        .chain(hook_paths.iter().map(PathBuf::as_path))
+        .find(|path| path.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+pub(crate) fn open_repository(
+    path: &Path,
+    executor: &BackgroundExecutor,
+) -> Result<RealGitRepository> {
+    RealGitRepository::new(
+        &path.join(".git"),
+        None,
+        Some("git".into()),
+        executor.clone(),
+    )
+    .with_context(|| format!("{} is not a git repository", path.display()))
+}
+
+/// Resolves symlinks so worktree paths reported by git compare equal to ours,
+/// including paths whose last component no longer exists
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    if let Ok(path) = path.canonicalize() {
+        return path;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Lists the worktrees of a rig's repository that live in its `hooks/` directory
+pub async fn scan_rig_worktrees(
+    rig_path: PathBuf,
+    hook_paths: Vec<PathBuf>,
+    executor: BackgroundExecutor,
+) -> Result<Vec<HookWorktree>> {
+    let Some(repository_path) = rig_repository(&rig_path, &hook_paths) else {
+        return Ok(Vec::new());
+    };
+    let repository = open_repository(&repository_path, &executor)?;
+    let hooks_dir = normalize(&rig_path.join("hooks"));
+
+    let mut hooks = Vec::new();
+    for worktree in repository.worktrees().await? {
+        let path = normalize(&worktree.path);
+        if path.parent() != Some(hooks_dir.as_path()) {
+            continue;
+        }
+
+        let on_disk = path.is_dir();
+        let dirty = if on_disk {
+            match open_repository(&path, &executor) {
+                Ok(repository) => repository.status(&[]).await.ok().map(|status| {
+                    status
+                        .entries
+                        .iter()
+                        .any(|(_, status)| !status.is_ignored())
+                }),
+                Err(_) => None,
+            }
+        } else {
+            None
+        };
+        // Porcelain lines after `branch`, like `prunable`, end up in the ref name
+        let branch = worktree.branch().lines().next().unwrap_or_default();
+        hooks.push(HookWorktree {
+            branch: branch.to_string(),
+            sha: worktree.sha.to_string(),
+            path,
+            dirty,
+            on_disk,
+        });
+    }
+    Ok(hooks)
+}
+
+/// Creates `<rig>/hooks/<name>` as a worktree on a new `<name>` branch from HEAD
+pub async fn create_hook_worktree(
+    rig_path: PathBuf,
+    hook_paths: Vec<PathBuf>,
+    name: String,
+    executor: BackgroundExecutor,
+) -> Result<PathBuf> {
+    let repository_path = rig_repository(&rig_path, &hook_paths)
+        .ok_or_else(|| anyhow!("{} has no git repository", rig_path.display()))?;
+    let repository = open_repository(&repository_path, &executor)?;
+    let hooks_dir = rig_path.join("hooks");
+    std::fs::create_dir_all(&hooks_dir)?;
+    repository
+        .create_worktree(name.clone(), hooks_dir.clone(), Some("HEAD".into()))
+        .await?;
+    Ok(hooks_dir.join(name))
+}
+
+/// Combines the model's hooks with what git reported for each rig.
+///
+/// Hooks git no longer has a directory for are included as archived. Records are
+/// sorted by rig, then name.
+pub fn hook_records(
+    model: &TownModel,
+    worktrees: &BTreeMap<String, RigWorktrees>,
+) -> Vec<HookRecord> {
+    let work = |rig: &str, owner: Option<&str>, name: &str| -> (usize, usize) {
+        // Hooks in different rigs may share a name
+        let assigned = model.issues().filter(|issue| {
+            issue.rig.as_deref() == Some(rig)
+                && issue
+                    .bead
+                    .assignee
+                    .as_deref()
+                    .is_some_and(|assignee| assignee == name || Some(assignee) == owner)
+        });
+        assigned.fold((0, 0), |(open, closed), issue| {
+            if issue.bead.is_closed() {
+                (open, closed + 1)
+            } else {
+                (open + 1, closed)
+            }
+        })
+    };
+
+    let mut records: Vec<HookRecord> = model
+        .hooks()
+        .map(|hook| {
+            let path = normalize(&hook.path);
+            let worktree = worktrees
+                .get(&hook.rig)
+                .and_then(|worktrees| worktrees.as_ref().ok())
+                .and_then(|worktrees| worktrees.iter().find(|worktree| worktree.path == path));
+            let owner_status = hook
+                .agent
+                .as_deref()
+                .and_then(|agent| model.agent(agent))
+                .map(|agent| &agent.status);
+            let (open_work, closed_work) = work(&hook.rig, hook.agent.as_deref(), &hook.name);
+
+            HookRecord {
+                rig: hook.rig.clone(),
+                name: hook.name.clone(),
+                path: hook.path.clone(),
+                owner: hook.agent.clone(),
+                state: hook_state(true, owner_status, open_work, closed_work),
+                branch: worktree.map(|worktree| worktree.branch.clone()),
+                head: worktree.map(|worktree| worktree.sha.clone()),
+                dirty: worktree.and_then(|worktree| worktree.dirty),
+            }
+        })
+        .collect();
+
+    for (rig, worktrees) in worktrees {
+        let Ok(worktrees) = worktrees else {
+            continue;
+        };
+        for worktree in worktrees.iter().filter(|worktree| !worktree.on_disk) {
+            let Some(name) = worktree.path.file_name() else {
+                continue;
+            };
+            records.push(HookRecord {
+                rig: rig.clone(),
+                name: name.to_string_lossy().into_owned(),
+                path: worktree.path.clone(),
+                owner: None,
+                state: HookState::Archived,
+                branch: Some(worktree.branch.clone()),
+                head: Some(worktree.sha.clone()),
+                dirty: None,
+            });
+        }
+    }
+
+    records.sort_by(|a, b| (&a.rig, &a.name).cmp(&(&b.rig, &b.name)));
+    records
+}
+
+/// Events emitted by `HookLifecycleView` for the town to act on
+#[derive(Clone, Debug, PartialEq)]
+pub enum HookLifecycleEvent {
+    /// A hook worktree was created, so the town structure is out of date
+    HookCreated(String),
+    /// Review of the hook with this id was requested
+    Review(String),
+}
+
+/// Center pane item listing every hook per rig with its lifecycle state and git status
+pub struct HookLifecycleView {
+    model: Entity<TownModel>,
+    worktrees: BTreeMap<String, RigWorktrees>,
+    records: Vec<HookRecord>,
+    scanning: bool,
+    error: Option<String>,
+    scan_task: Option<Task<()>>,
+    create_task: Option<Task<()>>,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<TownItemEvent> for HookLifecycleView {}
+impl EventEmitter<HookLifecycleEvent> for HookLifecycleView {}
+
+impl HookLifecycleView {
+    pub fn new(model: Entity<TownModel>, cx: &mut Context<Self>) -> Self {
+        let subscription =
+            cx.subscribe(&model, |this, _, event: &TownModelEvent, cx| match event {
+                TownModelEvent::HooksChanged | TownModelEvent::RigsChanged => this.refresh(cx),
+                TownModelEvent::AgentsChanged | TownModelEvent::IssuesChanged => this.rebuild(cx),
+                _ => {}
+            });
+
+        let mut view = Self {
+            model,
+            worktrees: BTreeMap::new(),
+            records: Vec::new(),
+            scanning: false,
+            error: None,
+            scan_task: None,
+            create_task: None,
+            focus_handle: cx.focus_handle(),
+            _subscription: subscription,
+        };
+        view.refresh(cx);
+        view
+    }
+
+    pub fn records(&self) -> &[HookRecord] {
+        &self.records
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        self.scanning
+    }
+
+    /// Errors listing a rig's worktrees, by rig
+    pub fn rig_errors(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.worktrees
+            .iter()
+            .filter_map(|(rig, worktrees)| Some((rig.as_str(), worktrees.as_ref().err()?.as_str())))
+    }
+
+    /// Lists the worktrees of every rig again in the background
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        let rigs: Vec<(String, PathBuf, Vec<PathBuf>)> = {
+            let model = self.model.read(cx);
+            model
+                .rigs()
+                .map(|rig| {
+                    let hook_paths = model
+                        .hooks_in_rig(&rig.name)
+                        .map(|hook| hook.path.clone())
+                        .collect();
+                    (rig.name.clone(), rig.path.clone(), hook_paths)
+                })
+                .collect()
+        };
+        let executor = cx.background_executor().clone();
+        let scan = cx.background_spawn(async move {
+            let mut worktrees = BTreeMap::new();
+            for (rig, rig_path, hook_paths) in rigs {
+                let scanned = scan_rig_worktrees(rig_path, hook_paths, executor.clone()).await;
+                worktrees.insert(rig, scanned.map_err(|err| format!("{:#}", err)));
+            }
+            worktrees
+        });
+
+        self.scanning = true;
+        self.scan_task = Some(cx.spawn(async move |this, cx| {
+            let worktrees = scan.await;
+            this.update(cx, |this, cx| {
+                this.worktrees = worktrees;
+                this.scanning = false;
+                this.rebuild(cx);
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn rebuild(&mut self, cx: &mut Context<Self>) {
+        self.records = hook_records(self.model.read(cx), &self.worktrees);
+        cx.notify();
+    }
+
+    /// Agents in a rig that could be given a hook: those without one yet
+    pub fn agents_without_hooks(&self, cx: &App) -> Vec<(String, String)> {
+        let model = self.model.read(cx);
+        model
+            .agents()
+            .filter(|agent| model.hook_for_agent(&agent.name).is_none())
+            .filter_map(|agent| {
+                let rig = agent.rig.clone()?;
+                let name = match &agent.path {
+                    Some(path) => path.file_name()?.to_string_lossy().into_owned(),
+                    None => agent.name.rsplit('/').next()?.to_string(),
+                };
+                (!self
+                    .records
+                    .iter()
+                    .any(|record| record.rig == rig && record.name == name))
+                .then_some((rig, name))
+            })
+            .collect()
+    }
+
+    /// Creates a hook worktree named `name` in `rig` with `create_worktree`
+    pub fn create_hook(&mut self, rig: String, name: String, cx: &mut Context<Self>) {
+        let (rig_path, hook_paths) = {
+            let model = self.model.read(cx);
+            let Some(rig_model) = model.rig(&rig) else {
+                return;
+            };
+            let hook_paths = model
+                .hooks_in_rig(&rig)
+                .map(|hook| hook.path.clone())
+                .collect::<Vec<_>>();
+            (rig_model.path.clone(), hook_paths)
+        };
+        let executor = cx.background_executor().clone();
+        let create = cx.background_spawn(create_hook_worktree(
+            rig_path,
+            hook_paths,
+            name.clone(),
+            executor,
+        ));
+
+        self.error = None;
+        self.create_task = Some(cx.spawn(async move |this, cx| {
+            let result = create.await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(_) => {
+                        cx.emit(HookLifecycleEvent::HookCreated(format!("{}/{}", rig, name)));
+                        this.refresh(cx);
+                    }
+                    Err(err) => {
+                        this.error = Some(format!("Couldn't create hook {}: {:#}", name, err))
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn render_new_hook(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let candidates = self.agents_without_hooks(cx);
+        let view = cx.entity().downgrade();
+        let menu = ContextMenu::build(window, cx, move |mut menu, _, _| {
+            if candidates.is_empty() {
+                return menu.header("Every agent has a hook");
+            }
+            for (rig, name) in candidates {
+                let view = view.clone();
+                menu = menu.entry(format!("{}/{}", rig, name), None, move |_, cx| {
+                    view.update(cx, |view, cx| {
+                        view.create_hook(rig.clone(), name.clone(), cx)
+                    })
+                    .ok();
+                });
+            }
+            menu
+        });
+        DropdownMenu::new("new-hook", "New Hook", menu)
+    }
+
+    fn render_record(&self, record: &HookRecord, cx: &Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+        let state_color = match record.state {
+            HookState::Active => status.success,
+            HookState::Suspended => status.warning,
+            HookState::Created => colors.text_accent,
+            HookState::Completed | HookState::Archived => colors.text_muted,
+        };
+        let cell = |width: f32| div().w(px(width)).flex_shrink_0().truncate();
+        let dirty = match record.dirty {
+            Some(true) => "modified",
+            Some(false) => "clean",
+            None => "—",
+        };
+
+        div()
+            .flex()
+            .items_center()
+            .gap(px(8.0))
+            .py(px(2.0))
+            .text_color(colors.text)
+            .child(cell(140.0).child(record.name.clone()))
+            .child(
+                cell(90.0)
+                    .text_color(state_color)
+                    .child(record.state.label()),
+            )
+            .child(cell(200.0).child(match &record.owner {
+                Some(owner) => div().child(owner.clone()),
+                None if record.is_orphaned() => div().text_color(status.warning).child("orphaned"),
+                None => div().text_color(colors.text_muted).child("—"),
+            }))
+            .child(cell(160.0).child(record.branch.clone().unwrap_or_else(|| "—".into())))
+            .child(
+                cell(80.0)
+                    .text_color(colors.text_muted)
+                    .child(match &record.head {
+                        Some(sha) => sha.chars().take(8).collect::<String>(),
+                        None => "—".into(),
+                    }),
+            )
+            .child(
+                cell(80.0)
+                    .when(record.dirty == Some(true), |this| {
+                        this.text_color(status.warning)
+                    })
+                    .child(dirty),
+            )
+            .when(record.state != HookState::Archived, |this| {
+                let id = record.id();
+                this.child(
+                    Button::new(SharedString::from(format!("review-{}", id)), "Review")
+                        .style(ButtonStyle::Subtle)
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener(move |_, _, _, cx| {
+                            cx.emit(HookLifecycleEvent::Review(id.clone()))
+                        })),
+                )
+            })
+    }
+}
+
+impl Focusable for HookLifecycleView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TownItem for HookLifecycleView {
+    type Event = TownItemEvent;
+
+    fn tab_content_text(&self, _cx: &App) -> SharedString {
+        "Hooks".into()
+    }
+
+    fn to_town_item_events(event: &Self::Event, mut f: impl FnMut(TownItemEvent)) {
+        f(*event);
+    }
+}
+
+impl Render for HookLifecycleView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let orphans = self
+            .records
+            .iter()
+            .filter(|record| record.is_orphaned())
+            .count();
+        let mut rigs: BTreeMap<&str, Vec<&HookRecord>> = BTreeMap::new();
+        for record in &self.records {
+            rigs.entry(record.rig.as_str()).or_default().push(record);
+        }
+        let rig_errors: BTreeMap<&str, &str> = self.rig_errors().collect();
+
+        div()
+            .id("hook-lifecycle")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .gap(px(12.0))
+            .p(px(16.0))
+            .overflow_y_scroll()
+            .text_sm()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .child(div().text_lg().text_color(colors.text).child("Hooks"))
+                    .when(orphans > 0, |this| {
+                        this.child(
+                            div()
+                                .text_color(cx.theme().status().warning)
+                                .child(format!("{} orphaned", orphans)),
+                        )
+                    })
+                    .when(self.scanning, |this| {
+                        this.child(div().text_color(colors.text_muted).child("Scanning…"))
+                    })
+                    .child(div().flex_1())
+                    .child(self.render_new_hook(window, cx))
+                    .child(
+                        Button::new("refresh-hooks", "Refresh")
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, _, cx| this.refresh(cx))),
+                    ),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(div().text_color(cx.theme().status().error).child(error))
+            })
+            .when(self.records.is_empty() && !self.scanning, |this| {
+                this.child(
+                    div()
+                        .text_color(colors.text_muted)
+                        .child("No hooks in this town"),
+                )
+            })
+            .children(rigs.into_iter().map(|(rig, records)| {
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(px(2.0))
+                    .child(
+                        div()
+                            .pb(px(4.0))
+                            .border_b_1()
+                            .border_color(colors.border_variant)
+                            .text_color(colors.text_muted)
+                            .child(rig.to_string()),
+                    )
+                    .when_some(rig_errors.get(rig), |this, error| {
+                        this.child(
+                            div()
+                                .text_color(cx.theme().status().error)
+                                .child(error.to_string()),
+                        )
+                    })
+                    .children(
+                        records
+                            .into_iter()
+                            .map(|record| self.render_record(record, cx)),
+                    )
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_discovery::AgentDirectory;
+    use crate::beads::{BeadsIssue, IssueStatus};
+    use crate::dashboard_buffer::{AgentInfo, DashboardData};
+    use crate::test_git::init_repository;
+    use crate::town_model::{Hook, Issue, Rig, TownStructure};
+    use git::libgit;
+    use gpui::{AppContext as _, TestAppContext};
+    use tempfile::TempDir;
+
+    fn issue(id: &str, assignee: &str, status: IssueStatus) -> Issue {
+        Issue {
+            rig: Some("frontend".into()),
+            beads_dir: PathBuf::new(),
+            bead: BeadsIssue {
+                id: id.into(),
+                assignee: Some(assignee.into()),
+                status,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_hook_state() {
+        assert_eq!(
+            hook_state(false, Some(&AgentStatus::Active), 1, 0),
+            HookState::Archived
+        );
+        assert_eq!(
+            hook_state(true, Some(&AgentStatus::Active), 0, 0),
+            HookState::Active
+        );
+        assert_eq!(
+            hook_state(true, Some(&AgentStatus::Idle), 1, 3),
+            HookState::Suspended
+        );
+        assert_eq!(hook_state(true, None, 0, 3), HookState::Completed);
+        assert_eq!(hook_state(true, None, 0, 0), HookState::Created);
+    }
+
+    #[gpui::test]
+    async fn test_hook_records_from_worktrees(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let town = TempDir::new().unwrap();
+        let rig_path = town.path().join("frontend");
+        init_repository(&rig_path, &[("README.md", "frontend\n")]);
+
+        let toast =
+            create_hook_worktree(rig_path.clone(), Vec::new(), "toast".into(), cx.executor())
+                .await
+                .unwrap();
+        let furiosa = create_hook_worktree(
+            rig_path.clone(),
+            Vec::new(),
+            "furiosa".into(),
+            cx.executor(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(toast, rig_path.join("hooks/toast"));
+        std::fs::write(toast.join("session.rs"), "// in progress\n").unwrap();
+        // Removed without `git worktree remove`, so git still lists it
+        std::fs::remove_dir_all(&furiosa).unwrap();
+        // A hook directory git doesn't know about, whose agent is gone
+        std::fs::create_dir_all(rig_path.join("hooks/nux")).unwrap();
+
+        let structure = TownStructure {
+            rigs: vec![Rig {
+                name: "frontend".into(),
+                path: rig_path.clone(),
+            }],
+            agents: vec![
+                AgentDirectory::from_rig_member(rig_path.join("polecats/toast")).unwrap(),
+                AgentDirectory::from_rig_member(rig_path.join("polecats/slit")).unwrap(),
+            ],
+            hooks: ["toast", "nux"]
+                .into_iter()
+                .map(|name| Hook {
+                    name: name.into(),
+                    rig: "frontend".into(),
+                    path: rig_path.join("hooks").join(name),
+                    agent: None,
+                })
+                .collect(),
+            issues: vec![
+                issue("fe-1", "toast", IssueStatus::Closed),
+                issue("fe-2", "frontend/polecats/toast", IssueStatus::Hooked),
+                // Work of another rig's nux
+                Issue {
+                    rig: Some("backend".into()),
+                    ..issue("be-1", "nux", IssueStatus::Open)
+                },
+            ],
+            ..Default::default()
+        };
+        let model = cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(structure, cx);
+            model.apply_dashboard_data(
+                DashboardData {
+                    agents: vec![AgentInfo {
+                        name: "frontend/polecats/toast".into(),
+                        status: AgentStatus::Idle,
+                        token_usage: None,
+                        context_fill: None,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            );
+            model
+        });
+
+        let worktrees = scan_rig_worktrees(rig_path.clone(), Vec::new(), cx.executor())
+            .await
+            .map_err(|err| err.to_string());
+        let records = model.read_with(cx, |model, _| {
+            hook_records(
+                model,
+                &BTreeMap::from_iter([("frontend".to_string(), worktrees)]),
+            )
+        });
+
+        let summary: Vec<(
+            &str,
+            HookState,
+            Option<&str>,
+            Option<&str>,
+            Option<bool>,
+            bool,
+        )> = records
+            .iter()
+            .map(|record| {
+                (
+                    record.name.as_str(),
+                    record.state,
+                    record.owner.as_deref(),
+                    record.branch.as_deref(),
+                    record.dirty,
+                    record.is_orphaned(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "furiosa",
+                    HookState::Archived,
+                    None,
+                    Some("furiosa"),
+                    None,
+                    false
+                ),
+                ("nux", HookState::Created, None, None, None, true),
+                (
+                    "toast",
+                    HookState::Suspended,
+                    Some("frontend/polecats/toast"),
+                    Some("toast"),
+                    Some(true),
+                    false
+                ),
+            ]
+        );
+
+        let head = libgit::Repository::open(&rig_path)
+            .unwrap()
+            .head()
+            .unwrap()
+            .target()
+            .unwrap()
+            .to_string();
+        assert_eq!(records[2].head.as_deref(), Some(head.as_str()));
+
+        let view = cx.new(|cx| HookLifecycleView::new(model, cx));
+        view.read_with(cx, |view, cx| {
+            assert_eq!(
+                view.agents_without_hooks(cx),
+                vec![("frontend".to_string(), "slit".to_string())]
+            );
+        });
+    }
+}
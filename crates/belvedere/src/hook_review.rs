@@ -0,0 +1,1045 @@
+//! Review of a hook's work against its rig's default branch.
+//!
+//! The changed files come from the same merge-base `diff-tree` that `git_ui`'s
+//! project diff uses for branch diffs, and each file is shown in a `git_ui`
+//! `FileDiffView` over read-only buffers of its two blobs.
+
+use anyhow::{Context as _, Result, anyhow};
+use git::repository::GitRepository;
+use git::status::{DiffTreeType, TreeDiffStatus};
+use git_ui::file_diff_view::{FileDiffView, build_buffer_diff};
+use gpui::{
+    App, BackgroundExecutor, Context, Entity, EventEmitter, FocusHandle, Focusable, Render,
+    SharedString, Subscription, Task, Window, div, prelude::*, px,
+};
+use language::{Buffer, Capability};
+use project::Project;
+use std::path::{Path, PathBuf};
+use ui::{
+    ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, Disableable, LabelSize, TintColor,
+};
+use ui_input::InputField;
+use util::ResultExt as _;
+use util::command::new_smol_command;
+
+use crate::agent_inspector::ActionStatus;
+use crate::gt_cli::GtCli;
+use crate::hook_lifecycle::{normalize, open_repository};
+use crate::town_item::{TownItem, TownItemEvent};
+use crate::town_model::{TownModel, TownModelEvent};
+
+/// Sent with `gt nudge` when changes are requested without a comment
+pub const DEFAULT_REQUEST_CHANGES_MESSAGE: &str =
+    "Changes requested on your hook. Please revise your work and ask for another review.";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
+/// A file the hook changed, with its text on both sides
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangedFile {
+    /// Repository-relative path, with `/` separators
+    pub path: String,
+    pub kind: ChangeKind,
+    /// Text at the merge base; `None` for added files
+    pub base_text: Option<String>,
+    /// Text at the hook's HEAD; `None` for deleted files
+    pub hook_text: Option<String>,
+}
+
+/// The committed work of a hook that merging it would bring in
+#[derive(Clone, Debug, PartialEq)]
+pub struct HookChanges {
+    pub base_branch: String,
+    pub hook_branch: String,
+    /// Sorted by path
+    pub files: Vec<ChangedFile>,
+}
+
+/// Runs git in `dir` and returns its trimmed stdout
+async fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = new_smol_command("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .await
+        .context("failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The branch hooks are merged into: the repository's default branch, or the
+/// branch the rig checkout is on when git doesn't know of one
+pub async fn base_branch(repository_path: &Path, executor: &BackgroundExecutor) -> Result<String> {
+    let repository = open_repository(repository_path, executor)?;
+    if let Some(branch) = repository.default_branch(false).await.ok().flatten() {
+        return Ok(branch.to_string());
+    }
+    let branches = repository.branches().await?;
+    branches
+        .iter()
+        .find(|branch| branch.is_head)
+        .map(|branch| branch.name().to_string())
+        .ok_or_else(|| anyhow!("{} has no branch checked out", repository_path.display()))
+}
+
+/// The main checkout of the repository a hook is a worktree of.
+///
+/// This is found from the hook with `git rev-parse --git-common-dir` rather than
+/// from the rig, which needn't be a checkout. Hooks that aren't linked worktrees
+/// of a non-bare repository are refused, so a merge or discard can never run in
+/// the hook itself.
+pub async fn hook_repository(hook_path: &Path) -> Result<PathBuf> {
+    let common_dir = git(
+        hook_path,
+        &["rev-parse", "--path-format=absolute", "--git-common-dir"],
+    )
+    .await?;
+    let common_dir = PathBuf::from(common_dir);
+    let repository = common_dir
+        .parent()
+        .filter(|_| common_dir.file_name().is_some_and(|name| name == ".git"))
+        .ok_or_else(|| {
+            anyhow!(
+                "{} belongs to a repository without a main checkout",
+                hook_path.display()
+            )
+        })?;
+    if normalize(repository) == normalize(hook_path) {
+        return Err(anyhow!(
+            "{} is a repository, not a hook worktree",
+            hook_path.display()
+        ));
+    }
+    Ok(repository.to_path_buf())
+}
+
+/// Lists what the hook's branch changed since it forked from the base branch
+pub async fn load_hook_changes(
+    repository_path: PathBuf,
+    hook_path: PathBuf,
+    executor: BackgroundExecutor,
+) -> Result<HookChanges> {
+    let base_branch = base_branch(&repository_path, &executor).await?;
+    let hook = open_repository(&hook_path, &executor)?;
+    let hook_branch = hook
+        .branches()
+        .await?
+        .into_iter()
+        .find(|branch| branch.is_head)
+        .map(|branch| branch.name().to_string())
+        .ok_or_else(|| anyhow!("{} is not on a branch", hook_path.display()))?;
+
+    let diff = hook
+        .diff_tree(DiffTreeType::MergeBase {
+            base: base_branch.clone().into(),
+            head: "HEAD".into(),
+        })
+        .await?;
+
+    let mut files = Vec::new();
+    for (path, status) in diff.entries {
+        let (kind, base_text) = match status {
+            TreeDiffStatus::Added => (ChangeKind::Added, None),
+            TreeDiffStatus::Modified { old } => (
+                ChangeKind::Modified,
+                Some(hook.load_blob_content(old).await?),
+            ),
+            TreeDiffStatus::Deleted { old } => (
+                ChangeKind::Deleted,
+                Some(hook.load_blob_content(old).await?),
+            ),
+        };
+        let hook_text = match kind {
+            ChangeKind::Deleted => None,
+            _ => hook.load_committed_text(path.clone()).await,
+        };
+        files.push(ChangedFile {
+            path: path.as_unix_str().to_string(),
+            kind,
+            base_text,
+            hook_text,
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(HookChanges {
+        base_branch,
+        hook_branch,
+        files,
+    })
+}
+
+/// Merges the hook's branch into the base branch, which must be checked out in
+/// the rig's repository with no uncommitted changes.
+///
+/// A merge that conflicts is aborted, so the checkout is left as it was, and the
+/// error lists the conflicts git reported.
+pub async fn merge_hook(
+    repository_path: PathBuf,
+    hook_branch: String,
+    executor: BackgroundExecutor,
+) -> Result<()> {
+    let base_branch = base_branch(&repository_path, &executor).await?;
+    let current = git(&repository_path, &["symbolic-ref", "--short", "HEAD"]).await?;
+    if current != base_branch {
+        return Err(anyhow!(
+            "{} has {} checked out, not {}",
+            repository_path.display(),
+            current,
+            base_branch
+        ));
+    }
+    // Untracked files are left out: hooks live in the rig, and git refuses to
+    // merge over an untracked file anyway
+    let uncommitted = git(
+        &repository_path,
+        &["status", "--porcelain", "--untracked-files=no"],
+    )
+    .await?;
+    if !uncommitted.is_empty() {
+        return Err(anyhow!(
+            "{} has uncommitted changes",
+            repository_path.display()
+        ));
+    }
+
+    let message = format!("Merge hook {}", hook_branch);
+    let output = new_smol_command("git")
+        .current_dir(&repository_path)
+        .args(["merge", "--no-ff", "-m", &message, &hook_branch])
+        .output()
+        .await
+        .context("failed to run git")?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    // A merge that stopped before touching the checkout leaves no MERGE_HEAD
+    if git(
+        &repository_path,
+        &["rev-parse", "-q", "--verify", "MERGE_HEAD"],
+    )
+    .await
+    .is_ok()
+    {
+        git(&repository_path, &["merge", "--abort"]).await?;
+    }
+    let conflicts = merge_conflicts(&String::from_utf8_lossy(&output.stdout));
+    if conflicts.is_empty() {
+        return Err(anyhow!(
+            "`git merge {}` failed: {}",
+            hook_branch,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Err(anyhow!(
+        "Merging {} conflicts with {}: {}",
+        hook_branch,
+        base_branch,
+        conflicts.join(", ")
+    ))
+}
+
+/// The paths of the `CONFLICT` lines in `git merge`'s output
+fn merge_conflicts(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .filter(|line| line.starts_with("CONFLICT"))
+        .map(|line| match line.split_once("Merge conflict in ") {
+            Some((_, path)) => path.trim().to_string(),
+            None => line
+                .split_once(": ")
+                .map_or(line, |(_, description)| description)
+                .trim()
+                .to_string(),
+        })
+        .collect()
+}
+
+/// Removes the hook's worktree, uncommitted changes included, and deletes its branch
+pub async fn discard_hook(
+    repository_path: PathBuf,
+    hook_path: PathBuf,
+    hook_branch: String,
+) -> Result<()> {
+    let hook_path = hook_path.to_string_lossy().into_owned();
+    git(
+        &repository_path,
+        &["worktree", "remove", "--force", &hook_path],
+    )
+    .await?;
+    git(&repository_path, &["branch", "-D", &hook_branch]).await?;
+    Ok(())
+}
+
+/// Events emitted by `HookReview` for the town to act on
+#[derive(Clone, Debug, PartialEq)]
+pub enum HookReviewEvent {
+    /// The hook's branch was merged
+    Merged(String),
+    /// The hook's worktree and branch were removed
+    Discarded(String),
+}
+
+/// Center pane item reviewing a hook's diff, with approve, request changes and discard
+pub struct HookReview {
+    hook_id: String,
+    model: Entity<TownModel>,
+    gt: GtCli,
+    /// The town's project, which diffs are shown in; `None` when agent sessions
+    /// aren't available, which leaves only the file list
+    project: Option<Entity<Project>>,
+    changes: Option<Result<HookChanges, String>>,
+    selected_file: Option<usize>,
+    diff_view: Option<Entity<FileDiffView>>,
+    /// Comment sent to the owner when requesting changes, while composing one
+    comment: Option<Entity<InputField>>,
+    confirming_discard: bool,
+    action_status: Option<ActionStatus>,
+    load_task: Option<Task<()>>,
+    diff_task: Option<Task<()>>,
+    action_task: Option<Task<()>>,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<TownItemEvent> for HookReview {}
+impl EventEmitter<HookReviewEvent> for HookReview {}
+
+impl HookReview {
+    pub fn new(
+        hook_id: String,
+        model: Entity<TownModel>,
+        gt: GtCli,
+        project: Option<Entity<Project>>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&model, |_, _, event: &TownModelEvent, cx| {
+            if matches!(
+                event,
+                TownModelEvent::HooksChanged | TownModelEvent::AgentsChanged
+            ) {
+                cx.notify();
+            }
+        });
+
+        let mut review = Self {
+            hook_id,
+            model,
+            gt,
+            project,
+            changes: None,
+            selected_file: None,
+            diff_view: None,
+            comment: None,
+            confirming_discard: false,
+            action_status: None,
+            load_task: None,
+            diff_task: None,
+            action_task: None,
+            focus_handle: cx.focus_handle(),
+            _subscription: subscription,
+        };
+        review.reload(cx);
+        review
+    }
+
+    pub fn hook_id(&self) -> &str {
+        &self.hook_id
+    }
+
+    pub fn changes(&self) -> Option<&Result<HookChanges, String>> {
+        self.changes.as_ref()
+    }
+
+    pub fn action_status(&self) -> Option<&ActionStatus> {
+        self.action_status.as_ref()
+    }
+
+    /// The hook's worktree. The repository it belongs to is resolved from it with
+    /// `hook_repository`, in the background.
+    fn hook_path(&self, cx: &App) -> Result<PathBuf, String> {
+        self.model
+            .read(cx)
+            .hook(&self.hook_id)
+            .map(|hook| hook.path.clone())
+            .ok_or_else(|| format!("Hook {} no longer exists", self.hook_id))
+    }
+
+    /// Reads the hook's changes again in the background
+    pub fn reload(&mut self, cx: &mut Context<Self>) {
+        let hook_path = match self.hook_path(cx) {
+            Ok(hook_path) => hook_path,
+            Err(err) => {
+                self.changes = Some(Err(err));
+                cx.notify();
+                return;
+            }
+        };
+        let executor = cx.background_executor().clone();
+        let load = cx.background_spawn(async move {
+            let repository = hook_repository(&hook_path).await?;
+            load_hook_changes(repository, hook_path, executor).await
+        });
+        self.load_task = Some(cx.spawn(async move |this, cx| {
+            let changes = load.await.map_err(|err| format!("{:#}", err));
+            this.update(cx, |this, cx| {
+                this.changes = Some(changes);
+                this.selected_file = None;
+                this.diff_view = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Shows the diff of the file at `index` in the change list
+    pub fn select_file(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(Ok(changes)) = &self.changes else {
+            return;
+        };
+        let Some(file) = changes.files.get(index).cloned() else {
+            return;
+        };
+        self.selected_file = Some(index);
+        let Some(project) = self.project.clone() else {
+            cx.notify();
+            return;
+        };
+
+        let read_only = |text: Option<String>, cx: &mut Context<Self>| {
+            cx.new(|cx| {
+                let mut buffer = Buffer::local(text.unwrap_or_default(), cx);
+                buffer.set_capability(Capability::ReadOnly, cx);
+                buffer
+            })
+        };
+        let old_buffer = read_only(file.base_text, cx);
+        let new_buffer = read_only(file.hook_text, cx);
+        let languages = project.read(cx).languages().clone();
+        self.diff_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let Some(diff) = build_buffer_diff(&old_buffer, &new_buffer, languages, cx)
+                .await
+                .log_err()
+            else {
+                return;
+            };
+            this.update_in(cx, |this, window, cx| {
+                this.diff_view = Some(cx.new(|cx| {
+                    FileDiffView::new(old_buffer, new_buffer, diff, project, window, cx)
+                }));
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn run_action(
+        &mut self,
+        running: String,
+        succeeded: String,
+        action: Task<Result<()>>,
+        on_success: impl FnOnce(&mut Self, &mut Context<Self>) + 'static,
+        cx: &mut Context<Self>,
+    ) {
+        self.action_status = Some(ActionStatus::Running(running));
+        self.action_task = Some(cx.spawn(async move |this, cx| {
+            let result = action.await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(()) => {
+                        this.action_status = Some(ActionStatus::Succeeded(succeeded));
+                        on_success(this, cx);
+                    }
+                    Err(err) => {
+                        this.action_status = Some(ActionStatus::Failed(format!("{:#}", err)))
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    /// Merges the hook's branch into the rig's default branch
+    pub fn approve(&mut self, cx: &mut Context<Self>) {
+        let Some(Ok(changes)) = &self.changes else {
+            return;
+        };
+        let hook_path = match self.hook_path(cx) {
+            Ok(hook_path) => hook_path,
+            Err(err) => {
+                self.action_status = Some(ActionStatus::Failed(err));
+                cx.notify();
+                return;
+            }
+        };
+        let branch = changes.hook_branch.clone();
+        let base = changes.base_branch.clone();
+        let executor = cx.background_executor().clone();
+        let merge = cx.background_spawn({
+            let branch = branch.clone();
+            async move {
+                let repository = hook_repository(&hook_path).await?;
+                merge_hook(repository, branch, executor).await
+            }
+        });
+        self.run_action(
+            format!("Merging {} into {}…", branch, base),
+            format!("Merged {} into {}", branch, base),
+            merge,
+            |this, cx| {
+                cx.emit(HookReviewEvent::Merged(this.hook_id.clone()));
+                this.reload(cx);
+            },
+            cx,
+        );
+    }
+
+    /// Starts composing a change request for the hook's owner
+    pub fn compose_request(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let comment = cx.new(|cx| {
+            InputField::new(window, cx, DEFAULT_REQUEST_CHANGES_MESSAGE).label("Changes")
+        });
+        window.focus(&comment.focus_handle(cx), cx);
+        self.comment = Some(comment);
+        cx.notify();
+    }
+
+    /// Nudges the hook's owner with `message`
+    pub fn request_changes(&mut self, message: String, cx: &mut Context<Self>) {
+        let owner = self
+            .model
+            .read(cx)
+            .hook(&self.hook_id)
+            .and_then(|hook| hook.agent.clone());
+        let Some(owner) = owner else {
+            self.action_status = Some(ActionStatus::Failed(format!(
+                "{} has no agent to nudge",
+                self.hook_id
+            )));
+            cx.notify();
+            return;
+        };
+
+        let gt = self.gt.clone();
+        let target = owner.clone();
+        let nudge = cx.background_spawn(async move {
+            gt.run(["nudge", target.as_str(), message.as_str()])
+                .map(|_| ())
+                .map_err(|err| anyhow!(err.to_string()))
+        });
+        self.run_action(
+            format!("Nudging {}…", owner),
+            format!("Requested changes from {}", owner),
+            nudge,
+            |this, _| this.comment = None,
+            cx,
+        );
+    }
+
+    fn send_request(&mut self, cx: &mut Context<Self>) {
+        let message = self
+            .comment
+            .as_ref()
+            .map(|comment| comment.read(cx).text(cx))
+            .filter(|message| !message.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_REQUEST_CHANGES_MESSAGE.to_string());
+        self.request_changes(message, cx);
+    }
+
+    /// Removes the hook's worktree and deletes its branch
+    pub fn discard(&mut self, cx: &mut Context<Self>) {
+        self.confirming_discard = false;
+        let Some(Ok(changes)) = &self.changes else {
+            return;
+        };
+        let hook_path = match self.hook_path(cx) {
+            Ok(hook_path) => hook_path,
+            Err(err) => {
+                self.action_status = Some(ActionStatus::Failed(err));
+                cx.notify();
+                return;
+            }
+        };
+        let branch = changes.hook_branch.clone();
+        let discard = cx.background_spawn(async move {
+            let repository = hook_repository(&hook_path).await?;
+            discard_hook(repository, hook_path, branch).await
+        });
+        self.run_action(
+            format!("Discarding {}…", self.hook_id),
+            format!("Discarded {}", self.hook_id),
+            discard,
+            |this, cx| cx.emit(HookReviewEvent::Discarded(this.hook_id.clone())),
+            cx,
+        );
+    }
+
+    fn render_actions(&self, cx: &Context<Self>) -> impl IntoElement {
+        let busy = matches!(self.action_status, Some(ActionStatus::Running(_)));
+        let loaded = matches!(self.changes, Some(Ok(_)));
+        let has_changes = matches!(&self.changes, Some(Ok(changes)) if !changes.files.is_empty());
+
+        div()
+            .flex()
+            .gap(px(4.0))
+            .child(
+                Button::new("approve-hook", "Approve & Merge")
+                    .style(ButtonStyle::Filled)
+                    .label_size(LabelSize::Small)
+                    .disabled(busy || !has_changes)
+                    .on_click(cx.listener(|this, _, _, cx| this.approve(cx))),
+            )
+            .child(
+                Button::new("request-changes", "Request Changes")
+                    .style(ButtonStyle::Subtle)
+                    .label_size(LabelSize::Small)
+                    .disabled(busy)
+                    .on_click(cx.listener(|this, _, window, cx| this.compose_request(window, cx))),
+            )
+            .child(if self.confirming_discard {
+                Button::new("discard-hook", "Really Discard?")
+                    .style(ButtonStyle::Tinted(TintColor::Error))
+                    .label_size(LabelSize::Small)
+                    .disabled(busy)
+                    .on_click(cx.listener(|this, _, _, cx| this.discard(cx)))
+            } else {
+                Button::new("discard-hook", "Discard")
+                    .style(ButtonStyle::Subtle)
+                    .label_size(LabelSize::Small)
+                    .disabled(busy || !loaded)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.confirming_discard = true;
+                        cx.notify();
+                    }))
+            })
+    }
+
+    fn render_files(&self, changes: &HookChanges, cx: &Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+
+        div()
+            .id("hook-review-files")
+            .flex()
+            .flex_col()
+            .w(px(240.0))
+            .flex_shrink_0()
+            .overflow_y_scroll()
+            .border_r_1()
+            .border_color(colors.border_variant)
+            .children(changes.files.iter().enumerate().map(|(index, file)| {
+                let (marker, color) = match file.kind {
+                    ChangeKind::Added => ("A", status.created),
+                    ChangeKind::Modified => ("M", status.modified),
+                    ChangeKind::Deleted => ("D", status.deleted),
+                };
+                div()
+                    .id(("hook-review-file", index))
+                    .flex()
+                    .gap(px(6.0))
+                    .px(px(8.0))
+                    .py(px(2.0))
+                    .cursor_pointer()
+                    .when(self.selected_file == Some(index), |this| {
+                        this.bg(colors.element_selected)
+                    })
+                    .hover(|style| style.bg(colors.element_hover))
+                    .child(div().text_color(color).child(marker))
+                    .child(
+                        div()
+                            .truncate()
+                            .text_color(colors.text)
+                            .child(file.path.clone()),
+                    )
+                    .on_click(
+                        cx.listener(move |this, _, window, cx| this.select_file(index, window, cx)),
+                    )
+            }))
+    }
+}
+
+impl Focusable for HookReview {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TownItem for HookReview {
+    type Event = TownItemEvent;
+
+    fn tab_content_text(&self, _cx: &App) -> SharedString {
+        format!("Review {}", self.hook_id).into()
+    }
+
+    fn to_town_item_events(event: &Self::Event, mut f: impl FnMut(TownItemEvent)) {
+        f(*event);
+    }
+}
+
+impl Render for HookReview {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let owner = self
+            .model
+            .read(cx)
+            .hook(&self.hook_id)
+            .map(|hook| hook.agent.clone().unwrap_or_else(|| "orphaned".into()));
+        let summary = match &self.changes {
+            None => "Loading changes…".to_string(),
+            Some(Err(err)) => err.clone(),
+            Some(Ok(changes)) => format!(
+                "{} → {} · {} file{} changed",
+                changes.hook_branch,
+                changes.base_branch,
+                changes.files.len(),
+                if changes.files.len() == 1 { "" } else { "s" }
+            ),
+        };
+        let status_line = self.action_status.clone().map(|status| {
+            let (message, color) = match status {
+                ActionStatus::Running(message) => (message, colors.text_muted),
+                ActionStatus::Succeeded(message) => (message, cx.theme().status().success),
+                ActionStatus::Failed(message) => (message, cx.theme().status().error),
+            };
+            div().text_xs().text_color(color).child(message)
+        });
+        let files = match &self.changes {
+            Some(Ok(changes)) => Some(self.render_files(changes, cx)),
+            _ => None,
+        };
+
+        div()
+            .id("hook-review")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .text_sm()
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(px(6.0))
+                    .p(px(12.0))
+                    .border_b_1()
+                    .border_color(colors.border_variant)
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(px(8.0))
+                            .child(
+                                div()
+                                    .text_lg()
+                                    .text_color(colors.text)
+                                    .child(format!("Hook {}", self.hook_id)),
+                            )
+                            .when_some(owner, |this, owner| {
+                                this.child(div().text_color(colors.text_muted).child(owner))
+                            })
+                            .child(div().flex_1())
+                            .child(self.render_actions(cx)),
+                    )
+                    .child(
+                        div()
+                            .text_color(match &self.changes {
+                                Some(Err(_)) => cx.theme().status().error,
+                                _ => colors.text_muted,
+                            })
+                            .child(summary),
+                    )
+                    .when_some(self.comment.clone(), |this, comment| {
+                        this.child(
+                            div()
+                                .flex()
+                                .items_end()
+                                .gap(px(4.0))
+                                .child(div().flex_1().child(comment))
+                                .child(
+                                    Button::new("send-request", "Send")
+                                        .style(ButtonStyle::Filled)
+                                        .label_size(LabelSize::Small)
+                                        .on_click(
+                                            cx.listener(|this, _, _, cx| this.send_request(cx)),
+                                        ),
+                                )
+                                .child(
+                                    Button::new("cancel-request", "Cancel")
+                                        .style(ButtonStyle::Subtle)
+                                        .label_size(LabelSize::Small)
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.comment = None;
+                                            cx.notify();
+                                        })),
+                                ),
+                        )
+                    })
+                    .children(status_line),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_1()
+                    .min_h_0()
+                    .children(files)
+                    .child(
+                        div()
+                            .flex_1()
+                            .h_full()
+                            .map(|this| match self.diff_view.clone() {
+                                Some(diff_view) => this.child(diff_view),
+                                None => this.p(px(12.0)).text_color(colors.text_muted).child(
+                                    if self.project.is_none() {
+                                        "Diffs aren't available without the town's project"
+                                    } else {
+                                        "Select a file to see its diff"
+                                    },
+                                ),
+                            }),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hook_lifecycle::create_hook_worktree;
+    use crate::test_git::{init_repository, run_git};
+    use git::libgit;
+    use gpui::TestAppContext;
+    use tempfile::TempDir;
+
+    fn init_rig(path: &Path) {
+        init_repository(
+            path,
+            &[("README.md", "frontend\n"), ("NOTES.md", "scratch\n")],
+        );
+    }
+
+    #[gpui::test]
+    async fn test_review_merge_and_discard(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let town = TempDir::new().unwrap();
+        let rig = town.path().join("frontend");
+        init_rig(&rig);
+        let checked_out = libgit::Repository::open(&rig)
+            .unwrap()
+            .head()
+            .unwrap()
+            .shorthand()
+            .unwrap()
+            .to_string();
+
+        let toast = create_hook_worktree(rig.clone(), Vec::new(), "toast".into(), cx.executor())
+            .await
+            .unwrap();
+        std::fs::write(toast.join("README.md"), "frontend\nsessions\n").unwrap();
+        std::fs::create_dir_all(toast.join("src")).unwrap();
+        std::fs::write(toast.join("src/session.rs"), "pub struct Session;\n").unwrap();
+        std::fs::remove_file(toast.join("NOTES.md")).unwrap();
+        run_git(&toast, &["add", "-A"]);
+        run_git(&toast, &["commit", "-qm", "Add sessions"]);
+        // Uncommitted work isn't part of the review
+        std::fs::write(toast.join("scratch.txt"), "wip\n").unwrap();
+
+        let repository = hook_repository(&toast).await.unwrap();
+        assert_eq!(normalize(&repository), normalize(&rig));
+        // The rig checkout isn't a hook, so nothing can be merged into it from itself
+        assert!(hook_repository(&rig).await.is_err());
+        assert!(hook_repository(town.path()).await.is_err());
+
+        let changes = load_hook_changes(rig.clone(), toast.clone(), cx.executor())
+            .await
+            .unwrap();
+        assert_eq!(changes.base_branch, checked_out);
+        assert_eq!(changes.hook_branch, "toast");
+        assert_eq!(
+            changes.files,
+            vec![
+                ChangedFile {
+                    path: "NOTES.md".into(),
+                    kind: ChangeKind::Deleted,
+                    base_text: Some("scratch\n".into()),
+                    hook_text: None,
+                },
+                ChangedFile {
+                    path: "README.md".into(),
+                    kind: ChangeKind::Modified,
+                    base_text: Some("frontend\n".into()),
+                    hook_text: Some("frontend\nsessions\n".into()),
+                },
+                ChangedFile {
+                    path: "src/session.rs".into(),
+                    kind: ChangeKind::Added,
+                    base_text: None,
+                    hook_text: Some("pub struct Session;\n".into()),
+                },
+            ]
+        );
+
+        merge_hook(rig.clone(), "toast".into(), cx.executor())
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(rig.join("README.md")).unwrap(),
+            "frontend\nsessions\n"
+        );
+        assert!(rig.join("src/session.rs").exists());
+        let merged = load_hook_changes(rig.clone(), toast.clone(), cx.executor())
+            .await
+            .unwrap();
+        assert_eq!(merged.files, Vec::new());
+
+        let furiosa =
+            create_hook_worktree(rig.clone(), Vec::new(), "furiosa".into(), cx.executor())
+                .await
+                .unwrap();
+        std::fs::write(furiosa.join("wip.txt"), "unsaved\n").unwrap();
+        discard_hook(rig.clone(), furiosa.clone(), "furiosa".into())
+            .await
+            .unwrap();
+        assert!(!furiosa.exists());
+        let repository = libgit::Repository::open(&rig).unwrap();
+        assert!(
+            repository
+                .find_branch("furiosa", libgit::BranchType::Local)
+                .is_err()
+        );
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    fn test_request_changes_nudges_owner(cx: &mut TestAppContext) {
+        use crate::agent_discovery::AgentDirectory;
+        use crate::fake_gt::FakeGt;
+        use crate::town_model::{Hook, Rig, TownStructure};
+        use gpui::AppContext as _;
+
+        let town = TempDir::new().unwrap();
+        let rig = town.path().join("frontend");
+        let gt = FakeGt::new().respond("nudge frontend/polecats/toast Please add tests", "");
+        let model = cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(
+                TownStructure {
+                    rigs: vec![Rig {
+                        name: "frontend".into(),
+                        path: rig.clone(),
+                    }],
+                    agents: vec![
+                        AgentDirectory::from_rig_member(rig.join("polecats/toast")).unwrap(),
+                    ],
+                    hooks: vec![Hook {
+                        name: "toast".into(),
+                        rig: "frontend".into(),
+                        path: rig.join("hooks/toast"),
+                        agent: None,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            );
+            model
+        });
+        let review =
+            cx.new(|cx| HookReview::new("frontend/toast".into(), model, gt.cli(), None, cx));
+        cx.run_until_parked();
+
+        review.update(cx, |review, cx| {
+            // The rig isn't a git checkout, which doesn't stop a change request
+            assert!(matches!(review.changes(), Some(Err(_))));
+            review.request_changes("Please add tests".into(), cx);
+        });
+        cx.run_until_parked();
+
+        review.read_with(cx, |review, _| {
+            assert_eq!(
+                review.action_status(),
+                Some(&ActionStatus::Succeeded(
+                    "Requested changes from frontend/polecats/toast".into()
+                ))
+            );
+        });
+        assert_eq!(gt.calls().len(), 1);
+    }
+
+    #[gpui::test]
+    async fn test_merge_requires_base_branch_checked_out(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let town = TempDir::new().unwrap();
+        let rig = town.path().join("frontend");
+        init_rig(&rig);
+        let base = base_branch(&rig, &cx.executor()).await.unwrap();
+        create_hook_worktree(rig.clone(), Vec::new(), "toast".into(), cx.executor())
+            .await
+            .unwrap();
+        run_git(&rig, &["checkout", "-qb", "release"]);
+
+        let error = merge_hook(rig.clone(), "toast".into(), cx.executor())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("{} has release checked out, not {}", rig.display(), base)
+        );
+    }
+
+    #[gpui::test]
+    async fn test_conflicting_merge_leaves_checkout_clean(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let town = TempDir::new().unwrap();
+        let rig = town.path().join("frontend");
+        init_rig(&rig);
+        let toast = create_hook_worktree(rig.clone(), Vec::new(), "toast".into(), cx.executor())
+            .await
+            .unwrap();
+        std::fs::write(toast.join("README.md"), "frontend\nsessions\n").unwrap();
+        run_git(&toast, &["commit", "-qam", "Add sessions"]);
+        std::fs::write(rig.join("README.md"), "frontend\nrouting\n").unwrap();
+
+        let error = merge_hook(rig.clone(), "toast".into(), cx.executor())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("{} has uncommitted changes", rig.display())
+        );
+
+        run_git(&rig, &["commit", "-qam", "Add routing"]);
+        let error = merge_hook(rig.clone(), "toast".into(), cx.executor())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Merging toast conflicts with main: README.md"
+        );
+        assert_eq!(
+            std::fs::read_to_string(rig.join("README.md")).unwrap(),
+            "frontend\nrouting\n"
+        );
+        assert!(!rig.join(".git/MERGE_HEAD").exists());
+        let status = git(&rig, &["status", "--porcelain", "--untracked-files=no"])
+            .await
+            .unwrap();
+        assert_eq!(status, "");
+    }
+}
@@ -0,0 +1,304 @@
+use futures::AsyncReadExt as _;
+use http_client::{AsyncBody, HttpClient, Method, Request, StatusCode, Url};
+use serde::Deserialize;
+use smol::future::FutureExt as _;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::dashboard_buffer::{DashboardData, DashboardDataSource, DashboardError};
+use crate::gt_data_source::{GtAgent, GtConvoy, GtRig};
+
+/// Default address of `gt dashboard --port 8080`
+pub const DEFAULT_DASHBOARD_URL: &str = "http://localhost:8080";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+const DASHBOARD_ENDPOINT: &str = "api/dashboard";
+
+/// Reads dashboard data from the `gt dashboard` web server.
+///
+/// Fetches `GET <base_url>/api/dashboard`, which returns the same agent, convoy and
+/// rig entries as the `gt ... --json` commands, bundled into one object.
+pub struct HttpDataSource {
+    client: Arc<dyn HttpClient>,
+    base_url: Url,
+    timeout: Duration,
+}
+
+/// Body of `GET /api/dashboard`
+#[derive(Deserialize)]
+struct DashboardResponse {
+    #[serde(default)]
+    agents: Vec<GtAgent>,
+    #[serde(default)]
+    convoys: Vec<GtConvoy>,
+    #[serde(default)]
+    rigs: Vec<GtRig>,
+}
+
+enum RequestError {
+    /// The server could not be reached at all
+    Unreachable(String),
+    /// The request did not complete within the timeout
+    TimedOut,
+    /// The server responded, but reading the response failed
+    Io(String),
+}
+
+impl HttpDataSource {
+    pub fn new(client: Arc<dyn HttpClient>, base_url: Url) -> Self {
+        Self {
+            client,
+            base_url,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    fn endpoint(&self) -> Result<Url, DashboardError> {
+        let mut base_url = self.base_url.clone();
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        base_url
+            .join(DASHBOARD_ENDPOINT)
+            .map_err(|err| DashboardError::FetchFailed(format!("Invalid dashboard URL: {}", err)))
+    }
+
+    /// Sends a request, returning the status and body
+    fn send(&self, method: Method, url: &Url) -> Result<(StatusCode, Vec<u8>), RequestError> {
+        let request = Request::builder()
+            .method(method)
+            .uri(url.as_str())
+            .header("Accept", "application/json")
+            .body(AsyncBody::empty())
+            .map_err(|err| RequestError::Unreachable(err.to_string()))?;
+
+        let response = self.client.send(request);
+        let timeout = self.timeout;
+
+        smol::block_on(
+            async move {
+                let mut response = response
+                    .await
+                    .map_err(|err| RequestError::Unreachable(format!("{:#}", err)))?;
+                let mut body = Vec::new();
+                response
+                    .body_mut()
+                    .read_to_end(&mut body)
+                    .await
+                    .map_err(|err| RequestError::Io(err.to_string()))?;
+                Ok((response.status(), body))
+            }
+            .or(async move {
+                smol::Timer::after(timeout).await;
+                Err(RequestError::TimedOut)
+            }),
+        )
+    }
+}
+
+impl DashboardDataSource for HttpDataSource {
+    fn fetch(&self) -> Result<DashboardData, DashboardError> {
+        let url = self.endpoint()?;
+        let (status, body) = self.send(Method::GET, &url).map_err(|err| match err {
+            RequestError::Unreachable(message) => {
+                log::debug!("dashboard server at {} unreachable: {}", url, message);
+                DashboardError::NotAvailable
+            }
+            RequestError::TimedOut => DashboardError::FetchFailed(format!(
+                "Request to {} timed out after {:?}",
+                url, self.timeout
+            )),
+            RequestError::Io(message) => DashboardError::FetchFailed(format!(
+                "Failed to read response from {}: {}",
+                url, message
+            )),
+        })?;
+
+        if !status.is_success() {
+            return Err(DashboardError::FetchFailed(format!(
+                "{} returned {}",
+                url, status
+            )));
+        }
+
+        let response: DashboardResponse = serde_json::from_slice(&body)
+            .map_err(|err| DashboardError::ParseError(format!("{}: {}", url, err)))?;
+
+        Ok(DashboardData {
+            agents: response
+                .agents
+                .into_iter()
+                .map(|agent| agent.into_agent_info())
+                .collect(),
+            convoys: response
+                .convoys
+                .into_iter()
+                .map(|convoy| convoy.into_convoy_info())
+                .collect(),
+            rigs: response
+                .rigs
+                .into_iter()
+                .map(|rig| rig.into_rig_info())
+                .collect(),
+        })
+    }
+
+    /// Any HTTP response, even an error status, means the server is up, so a HEAD
+    /// request saves transferring the dashboard
+    fn is_available(&self) -> bool {
+        match self.endpoint() {
+            Ok(url) => matches!(
+                self.send(Method::HEAD, &url),
+                Ok(_) | Err(RequestError::Io(_))
+            ),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard_buffer::AgentStatus;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    /// A minimal HTTP server that answers every request with a canned response
+    struct StubServer {
+        url: Url,
+    }
+
+    impl StubServer {
+        fn start(status: u16, body: &'static str, delay: Duration) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(mut stream) = stream else {
+                        continue;
+                    };
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).is_ok_and(|read| read > 0) {
+                        if line == "\r\n" {
+                            break;
+                        }
+                        line.clear();
+                    }
+
+                    std::thread::sleep(delay);
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {} Stub\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                }
+            });
+
+            Self { url }
+        }
+
+        fn source(&self) -> HttpDataSource {
+            HttpDataSource::new(
+                Arc::new(reqwest_client::ReqwestClient::new()),
+                self.url.clone(),
+            )
+        }
+    }
+
+    const DASHBOARD_JSON: &str = r#"{
+        "agents": [
+            {"name": "frontend/polecats/toast", "status": "active", "context_fill": 0.5},
+            {"name": "frontend/crew/alice", "status": "error", "error": "rate limited"}
+        ],
+        "convoys": [{"id": "hq-cv-1", "completed": 3, "total": 4}],
+        "rigs": [{"name": "frontend", "path": "/gt/frontend"}]
+    }"#;
+
+    #[test]
+    fn test_fetch_deserializes_dashboard() {
+        let server = StubServer::start(200, DASHBOARD_JSON, Duration::ZERO);
+        let source = server.source();
+
+        assert!(source.is_available());
+        let data = source.fetch().unwrap();
+
+        assert_eq!(data.agents.len(), 2);
+        assert_eq!(data.agents[0].status, AgentStatus::Active);
+        assert_eq!(
+            data.agents[1].status,
+            AgentStatus::Error("rate limited".into())
+        );
+        assert_eq!(data.convoys[0].progress, 0.75);
+        assert_eq!(data.rigs[0].path, "/gt/frontend");
+    }
+
+    #[test]
+    fn test_unreachable_server_is_not_available() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        drop(listener);
+
+        let source = HttpDataSource::new(Arc::new(reqwest_client::ReqwestClient::new()), url);
+
+        assert!(!source.is_available());
+        assert!(matches!(source.fetch(), Err(DashboardError::NotAvailable)));
+    }
+
+    #[test]
+    fn test_error_status_is_fetch_failed() {
+        let server = StubServer::start(500, "{}", Duration::ZERO);
+        let source = server.source();
+
+        assert!(source.is_available());
+        assert!(matches!(
+            source.fetch(),
+            Err(DashboardError::FetchFailed(message)) if message.contains("500")
+        ));
+    }
+
+    #[test]
+    fn test_invalid_body_is_parse_error() {
+        let server = StubServer::start(200, "<html>not json</html>", Duration::ZERO);
+
+        assert!(matches!(
+            server.source().fetch(),
+            Err(DashboardError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_slow_server_times_out() {
+        let server = StubServer::start(200, DASHBOARD_JSON, Duration::from_secs(2));
+        let source = server.source().timeout(Duration::from_millis(100));
+
+        assert!(matches!(
+            source.fetch(),
+            Err(DashboardError::FetchFailed(message)) if message.contains("timed out")
+        ));
+    }
+
+    #[test]
+    fn test_endpoint_preserves_base_path() {
+        let client = http_client::FakeHttpClient::with_404_response();
+        let source =
+            HttpDataSource::new(client, Url::parse("http://localhost:8080/gastown").unwrap());
+
+        assert_eq!(
+            source.endpoint().unwrap().as_str(),
+            "http://localhost:8080/gastown/api/dashboard"
+        );
+    }
+}
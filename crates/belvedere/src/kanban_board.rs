@@ -0,0 +1,789 @@
+use collections::{BTreeSet, HashMap};
+use gpui::{
+    App, Context, ElementId, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString,
+    Subscription, Task, Window, div, prelude::*, px,
+};
+use ui::{ActiveTheme, ContextMenu, DropdownMenu, IconPosition};
+
+use crate::beads::{self, IssueStatus, IssueType};
+use crate::town_item::{TownItem, TownItemEvent};
+use crate::town_model::{Issue, TownModel, TownModelEvent};
+
+/// Columns every board shows, in order; other statuses get a column when in use
+const COLUMNS: [IssueStatus; 4] = [
+    IssueStatus::Open,
+    IssueStatus::InProgress,
+    IssueStatus::Blocked,
+    IssueStatus::Closed,
+];
+
+/// The issues a board is about
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BoardScope {
+    /// Every issue in a rig's `.beads/`
+    Rig(String),
+    /// The issues a convoy tracks, wherever they live
+    Convoy(String),
+}
+
+/// Narrows a board to matching issues; `None` matches everything
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BoardFilter {
+    pub assignee: Option<String>,
+    pub label: Option<String>,
+    pub convoy: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoardColumn {
+    pub status: IssueStatus,
+    pub issues: Vec<Issue>,
+}
+
+/// A card being dragged between columns, or onto a rig or agent in the town tree
+#[derive(Clone, Debug)]
+pub struct DraggedIssue {
+    pub id: String,
+    pub title: String,
+}
+
+impl Render for DraggedIssue {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        div()
+            .w(px(200.0))
+            .p(px(6.0))
+            .rounded(px(4.0))
+            .border_1()
+            .border_color(colors.border_variant)
+            .bg(colors.element_background)
+            .text_sm()
+            .text_color(colors.text)
+            .child(format!("{} {}", self.id, self.title))
+    }
+}
+
+/// Center pane item laying out beads issues as cards in status columns.
+///
+/// Dropping a card on another column writes the new status to the issue's `.beads/`
+/// store. The card moves right away and stays there until the model, which follows
+/// the store on disk, agrees.
+pub struct KanbanBoard {
+    scope: BoardScope,
+    filter: BoardFilter,
+    model: Entity<TownModel>,
+    /// Statuses written to disk that the model hasn't picked up yet, by issue id
+    pending: HashMap<String, IssueStatus>,
+    /// In-flight status writes by issue id, removed once they finish
+    write_tasks: HashMap<String, Task<()>>,
+    error: Option<String>,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<TownItemEvent> for KanbanBoard {}
+
+impl KanbanBoard {
+    pub fn new(scope: BoardScope, model: Entity<TownModel>, cx: &mut Context<Self>) -> Self {
+        let subscription = cx.subscribe(&model, |this, model, event: &TownModelEvent, cx| {
+            if !matches!(
+                event,
+                TownModelEvent::IssuesChanged | TownModelEvent::ConvoysChanged
+            ) {
+                return;
+            }
+            let model = model.read(cx);
+            this.pending.retain(|id, status| {
+                model
+                    .issue(id)
+                    .is_some_and(|issue| &issue.bead.status != status)
+            });
+            cx.notify();
+        });
+
+        Self {
+            scope,
+            filter: BoardFilter::default(),
+            model,
+            pending: HashMap::default(),
+            write_tasks: HashMap::default(),
+            error: None,
+            focus_handle: cx.focus_handle(),
+            _subscription: subscription,
+        }
+    }
+
+    pub fn scope(&self) -> &BoardScope {
+        &self.scope
+    }
+
+    pub fn filter(&self) -> &BoardFilter {
+        &self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: BoardFilter, cx: &mut Context<Self>) {
+        self.filter = filter;
+        cx.notify();
+    }
+
+    /// The last status change that failed to be written
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// The scope's issues, before filtering, with pending status changes applied
+    fn scoped_issues(&self, cx: &App) -> Vec<Issue> {
+        let model = self.model.read(cx);
+        let issues: Vec<&Issue> = match &self.scope {
+            BoardScope::Rig(rig) => model
+                .issues_in_rig(rig)
+                .filter(|issue| issue.bead.issue_type != IssueType::Convoy)
+                .collect(),
+            BoardScope::Convoy(convoy) => model
+                .convoy(convoy)
+                .map(|convoy| {
+                    convoy
+                        .issues
+                        .iter()
+                        .filter_map(|member| model.issue(&member.id))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+
+        issues
+            .into_iter()
+            .map(|issue| {
+                let mut issue = issue.clone();
+                if let Some(status) = self.pending.get(issue.id()) {
+                    issue.bead.status = status.clone();
+                }
+                issue
+            })
+            .collect()
+    }
+
+    /// The issues on the board, after filtering
+    pub fn issues(&self, cx: &App) -> Vec<Issue> {
+        let model = self.model.read(cx);
+        let filter = &self.filter;
+        let convoy_members = filter.convoy.as_deref().map(|convoy| {
+            model
+                .convoy(convoy)
+                .map(|convoy| {
+                    convoy
+                        .issues
+                        .iter()
+                        .map(|issue| issue.id.clone())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        });
+
+        self.scoped_issues(cx)
+            .into_iter()
+            .filter(|issue| {
+                filter
+                    .assignee
+                    .as_ref()
+                    .is_none_or(|assignee| issue.bead.assignee.as_ref() == Some(assignee))
+            })
+            .filter(|issue| {
+                filter
+                    .label
+                    .as_ref()
+                    .is_none_or(|label| issue.bead.labels.contains(label))
+            })
+            .filter(|issue| {
+                convoy_members
+                    .as_ref()
+                    .is_none_or(|members| members.iter().any(|id| id == issue.id()))
+            })
+            .collect()
+    }
+
+    /// The filtered issues grouped by status
+    pub fn columns(&self, cx: &App) -> Vec<BoardColumn> {
+        let mut columns: Vec<BoardColumn> = COLUMNS
+            .iter()
+            .map(|status| BoardColumn {
+                status: status.clone(),
+                issues: Vec::new(),
+            })
+            .collect();
+
+        for issue in self.issues(cx) {
+            match columns
+                .iter_mut()
+                .find(|column| column.status == issue.bead.status)
+            {
+                Some(column) => column.issues.push(issue),
+                None => columns.push(BoardColumn {
+                    status: issue.bead.status.clone(),
+                    issues: vec![issue],
+                }),
+            }
+        }
+        columns
+    }
+
+    /// Assignees, labels and convoys that the scope's issues can be filtered by
+    pub fn filter_options(&self, cx: &App) -> (Vec<String>, Vec<String>, Vec<String>) {
+        let issues = self.scoped_issues(cx);
+        let assignees: BTreeSet<String> = issues
+            .iter()
+            .filter_map(|issue| issue.bead.assignee.clone())
+            .collect();
+        let labels: BTreeSet<String> = issues
+            .iter()
+            .flat_map(|issue| issue.bead.labels.iter().cloned())
+            .collect();
+        let convoys = self
+            .model
+            .read(cx)
+            .convoys()
+            .filter(|convoy| {
+                convoy
+                    .issues
+                    .iter()
+                    .any(|member| issues.iter().any(|issue| issue.id() == member.id))
+            })
+            .map(|convoy| convoy.id.clone())
+            .collect();
+
+        (
+            assignees.into_iter().collect(),
+            labels.into_iter().collect(),
+            convoys,
+        )
+    }
+
+    /// Moves an issue to another column, writing its new status to `.beads/`
+    pub fn move_issue(&mut self, id: &str, status: IssueStatus, cx: &mut Context<Self>) {
+        let Some(issue) = self.model.read(cx).issue(id) else {
+            return;
+        };
+        let current = self.pending.get(id).unwrap_or(&issue.bead.status);
+        if *current == status {
+            return;
+        }
+
+        let beads_dir = issue.beads_dir.clone();
+        let id = id.to_string();
+        self.pending.insert(id.clone(), status.clone());
+        self.error = None;
+        cx.notify();
+
+        let write = cx.background_spawn({
+            let id = id.clone();
+            async move {
+                let timestamp =
+                    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+                beads::write_status(&beads_dir, &id, &status, &timestamp)
+            }
+        });
+        let task = cx.spawn({
+            let id = id.clone();
+            async move |this, cx| {
+                let result = write.await;
+                this.update(cx, |this, cx| {
+                    this.write_tasks.remove(&id);
+                    if let Err(err) = result {
+                        this.pending.remove(&id);
+                        this.error = Some(format!("Couldn't move {}: {}", id, err));
+                        cx.notify();
+                    }
+                })
+                .ok();
+            }
+        });
+        self.write_tasks.insert(id, task);
+    }
+
+    fn render_filter(
+        &self,
+        kind: FilterKind,
+        options: Vec<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let selected = kind.get(&self.filter).cloned();
+        let label = selected
+            .clone()
+            .unwrap_or_else(|| kind.all_label().to_string());
+        let board = cx.entity().downgrade();
+        let menu = ContextMenu::build(window, cx, move |mut menu, _, _| {
+            let choices = std::iter::once((kind.all_label().to_string(), None)).chain(
+                options
+                    .into_iter()
+                    .map(|option| (option.clone(), Some(option))),
+            );
+            for (label, value) in choices {
+                let board = board.clone();
+                let toggled = value == selected;
+                menu = menu.toggleable_entry(
+                    label,
+                    toggled,
+                    IconPosition::Start,
+                    None,
+                    move |_, cx| {
+                        let value = value.clone();
+                        board
+                            .update(cx, |board, cx| {
+                                let mut filter = board.filter.clone();
+                                kind.set(&mut filter, value);
+                                board.set_filter(filter, cx);
+                            })
+                            .ok();
+                    },
+                );
+            }
+            menu
+        });
+        DropdownMenu::new(kind.id(), label, menu)
+    }
+
+    fn render_card(&self, issue: &Issue, cx: &App) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let bead = &issue.bead;
+        let dragged = DraggedIssue {
+            id: bead.id.clone(),
+            title: bead.title.clone(),
+        };
+
+        div()
+            .id(ElementId::Name(format!("kanban-card-{}", bead.id).into()))
+            .flex()
+            .flex_col()
+            .gap(px(2.0))
+            .p(px(6.0))
+            .rounded(px(4.0))
+            .border_1()
+            .border_color(colors.border_variant)
+            .bg(colors.element_background)
+            .cursor_grab()
+            .on_drag(dragged, |dragged, _, _, cx| cx.new(|_| dragged.clone()))
+            .child(
+                div()
+                    .flex()
+                    .gap(px(6.0))
+                    .child(div().text_color(colors.text_muted).child(bead.id.clone()))
+                    .child(
+                        div()
+                            .text_color(colors.text)
+                            .child(bead.title().unwrap_or("Untitled").to_string()),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap(px(8.0))
+                    .text_xs()
+                    .text_color(colors.text_muted)
+                    .child(bead.issue_type.to_string())
+                    .when_some(bead.priority, |this, priority| {
+                        this.child(format!("P{}", priority))
+                    })
+                    .when_some(bead.assignee.clone(), |this, assignee| this.child(assignee))
+                    .when(!bead.labels.is_empty(), |this| {
+                        this.child(bead.labels.join(", "))
+                    }),
+            )
+    }
+
+    fn render_column(&self, column: BoardColumn, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let status = column.status.clone();
+
+        div()
+            .id(ElementId::Name(
+                format!("kanban-column-{}", column.status).into(),
+            ))
+            .flex()
+            .flex_col()
+            .flex_shrink_0()
+            .w(px(240.0))
+            .h_full()
+            .gap(px(6.0))
+            .p(px(8.0))
+            .rounded(px(6.0))
+            .bg(colors.panel_background)
+            .overflow_y_scroll()
+            .drag_over::<DraggedIssue>(|style, _, _, cx| {
+                style.bg(cx.theme().colors().element_hover)
+            })
+            .on_drop(cx.listener(move |this, dragged: &DraggedIssue, _, cx| {
+                this.move_issue(&dragged.id, status.clone(), cx);
+            }))
+            .child(
+                div()
+                    .flex()
+                    .gap(px(6.0))
+                    .text_color(colors.text)
+                    .child(column.status.to_string())
+                    .child(
+                        div()
+                            .text_color(colors.text_muted)
+                            .child(column.issues.len().to_string()),
+                    ),
+            )
+            .children(
+                column
+                    .issues
+                    .iter()
+                    .map(|issue| self.render_card(issue, cx)),
+            )
+    }
+}
+
+#[derive(Clone, Copy)]
+enum FilterKind {
+    Assignee,
+    Label,
+    Convoy,
+}
+
+impl FilterKind {
+    fn id(self) -> &'static str {
+        match self {
+            FilterKind::Assignee => "kanban-assignee-filter",
+            FilterKind::Label => "kanban-label-filter",
+            FilterKind::Convoy => "kanban-convoy-filter",
+        }
+    }
+
+    fn all_label(self) -> &'static str {
+        match self {
+            FilterKind::Assignee => "All assignees",
+            FilterKind::Label => "All labels",
+            FilterKind::Convoy => "All convoys",
+        }
+    }
+
+    fn get(self, filter: &BoardFilter) -> Option<&String> {
+        match self {
+            FilterKind::Assignee => filter.assignee.as_ref(),
+            FilterKind::Label => filter.label.as_ref(),
+            FilterKind::Convoy => filter.convoy.as_ref(),
+        }
+    }
+
+    fn set(self, filter: &mut BoardFilter, value: Option<String>) {
+        match self {
+            FilterKind::Assignee => filter.assignee = value,
+            FilterKind::Label => filter.label = value,
+            FilterKind::Convoy => filter.convoy = value,
+        }
+    }
+}
+
+impl Focusable for KanbanBoard {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TownItem for KanbanBoard {
+    type Event = TownItemEvent;
+
+    fn tab_content_text(&self, _cx: &App) -> SharedString {
+        match &self.scope {
+            BoardScope::Rig(rig) => format!("{} Board", rig).into(),
+            BoardScope::Convoy(convoy) => format!("Convoy {} Board", convoy).into(),
+        }
+    }
+
+    fn to_town_item_events(event: &Self::Event, mut f: impl FnMut(TownItemEvent)) {
+        f(*event);
+    }
+}
+
+impl Render for KanbanBoard {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (assignees, labels, convoys) = self.filter_options(cx);
+        let columns = self.columns(cx);
+
+        let filters = div()
+            .flex()
+            .items_center()
+            .gap(px(8.0))
+            .child(self.render_filter(FilterKind::Assignee, assignees, window, cx))
+            .child(self.render_filter(FilterKind::Label, labels, window, cx))
+            .when(matches!(self.scope, BoardScope::Rig(_)), |this| {
+                this.child(self.render_filter(FilterKind::Convoy, convoys, window, cx))
+            });
+
+        let colors = cx.theme().colors();
+        div()
+            .id("kanban-board")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .gap(px(8.0))
+            .p(px(16.0))
+            .text_sm()
+            .child(filters)
+            .when_some(self.error.clone(), |this, error| {
+                this.child(div().text_color(cx.theme().status().error).child(error))
+            })
+            .child(
+                div()
+                    .id("kanban-columns")
+                    .flex()
+                    .flex_1()
+                    .min_h_0()
+                    .gap(px(8.0))
+                    .overflow_x_scroll()
+                    .text_color(colors.text)
+                    .children(
+                        columns
+                            .into_iter()
+                            .map(|column| self.render_column(column, cx)),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beads::tests::copy_fixture;
+    use crate::beads::{BeadsIssue, BeadsStore};
+    use crate::dashboard_buffer::{ConvoyInfo, DashboardData};
+    use crate::town_model::{ConvoyIssue, TownStructure};
+    use gpui::{AppContext as _, TestAppContext};
+    use std::path::Path;
+
+    /// Reads the checked-in frontend beads fixture as the frontend rig's issues
+    fn structure(beads_dir: &Path) -> TownStructure {
+        let store = BeadsStore::load(beads_dir).unwrap();
+        TownStructure {
+            issues: store
+                .issues()
+                .iter()
+                .map(|bead| Issue {
+                    rig: Some("frontend".into()),
+                    beads_dir: beads_dir.to_path_buf(),
+                    bead: bead.clone(),
+                })
+                .collect(),
+            convoy_issues: HashMap::from_iter([(
+                "hq-cv-1".to_string(),
+                vec![
+                    ConvoyIssue {
+                        id: "fe-1".into(),
+                        ..Default::default()
+                    },
+                    ConvoyIssue {
+                        id: "fe-2".into(),
+                        ..Default::default()
+                    },
+                ],
+            )]),
+            ..Default::default()
+        }
+    }
+
+    fn test_model(beads_dir: &Path, cx: &mut TestAppContext) -> Entity<TownModel> {
+        let structure = structure(beads_dir);
+        cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(structure, cx);
+            model.apply_dashboard_data(
+                DashboardData {
+                    convoys: vec![ConvoyInfo {
+                        id: "hq-cv-1".into(),
+                        progress: 0.5,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            );
+            model
+        })
+    }
+
+    fn column_ids(columns: &[BoardColumn]) -> Vec<(String, Vec<String>)> {
+        columns
+            .iter()
+            .map(|column| {
+                (
+                    column.status.to_string(),
+                    column
+                        .issues
+                        .iter()
+                        .map(|issue| issue.id().to_string())
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    fn test_columns_group_by_status(cx: &mut TestAppContext) {
+        let (_temp, dir) = copy_fixture("frontend");
+        let model = test_model(&dir, cx);
+        let rig_board =
+            cx.new(|cx| KanbanBoard::new(BoardScope::Rig("frontend".into()), model.clone(), cx));
+        let convoy_board =
+            cx.new(|cx| KanbanBoard::new(BoardScope::Convoy("hq-cv-1".into()), model, cx));
+
+        rig_board.read_with(cx, |board, cx| {
+            assert_eq!(
+                column_ids(&board.columns(cx)),
+                vec![
+                    ("open".into(), vec![]),
+                    ("in_progress".into(), vec!["fe-2".into()]),
+                    ("blocked".into(), vec!["fe-3".into()]),
+                    ("closed".into(), vec!["fe-1".into()]),
+                    ("review".into(), vec!["fe-4".into()]),
+                ]
+            );
+        });
+        convoy_board.read_with(cx, |board, cx| {
+            let ids: Vec<String> = board
+                .issues(cx)
+                .iter()
+                .map(|issue| issue.id().to_string())
+                .collect();
+            assert_eq!(ids, vec!["fe-1", "fe-2"]);
+        });
+    }
+
+    #[gpui::test]
+    fn test_filters(cx: &mut TestAppContext) {
+        let (_temp, dir) = copy_fixture("frontend");
+        let model = test_model(&dir, cx);
+        let board = cx.new(|cx| KanbanBoard::new(BoardScope::Rig("frontend".into()), model, cx));
+        let ids = |board: &KanbanBoard, cx: &App| -> Vec<String> {
+            board
+                .issues(cx)
+                .iter()
+                .map(|issue| issue.id().to_string())
+                .collect()
+        };
+
+        board.read_with(cx, |board, cx| {
+            let (assignees, labels, convoys) = board.filter_options(cx);
+            assert_eq!(
+                assignees,
+                vec!["frontend/polecats/nux", "frontend/polecats/toast"]
+            );
+            assert_eq!(labels, vec!["auth", "ui"]);
+            assert_eq!(convoys, vec!["hq-cv-1"]);
+        });
+
+        board.update(cx, |board, cx| {
+            board.set_filter(
+                BoardFilter {
+                    label: Some("auth".into()),
+                    ..Default::default()
+                },
+                cx,
+            )
+        });
+        board.read_with(cx, |board, cx| {
+            assert_eq!(ids(board, cx), vec!["fe-1", "fe-2"])
+        });
+
+        board.update(cx, |board, cx| {
+            board.set_filter(
+                BoardFilter {
+                    assignee: Some("frontend/polecats/toast".into()),
+                    label: Some("auth".into()),
+                    ..Default::default()
+                },
+                cx,
+            )
+        });
+        board.read_with(cx, |board, cx| assert_eq!(ids(board, cx), vec!["fe-2"]));
+
+        board.update(cx, |board, cx| {
+            board.set_filter(
+                BoardFilter {
+                    convoy: Some("hq-cv-1".into()),
+                    ..Default::default()
+                },
+                cx,
+            )
+        });
+        board.read_with(cx, |board, cx| {
+            assert_eq!(ids(board, cx), vec!["fe-1", "fe-2"])
+        });
+    }
+
+    #[gpui::test]
+    fn test_move_issue_writes_status(cx: &mut TestAppContext) {
+        let (_temp, dir) = copy_fixture("frontend");
+        let model = test_model(&dir, cx);
+        let board =
+            cx.new(|cx| KanbanBoard::new(BoardScope::Rig("frontend".into()), model.clone(), cx));
+
+        board.update(cx, |board, cx| {
+            board.move_issue("fe-3", IssueStatus::InProgress, cx)
+        });
+        board.read_with(cx, |board, cx| {
+            let columns = board.columns(cx);
+            assert_eq!(column_ids(&columns)[1].1, vec!["fe-2", "fe-3"]);
+        });
+
+        cx.run_until_parked();
+        board.read_with(cx, |board, _| assert!(board.write_tasks.is_empty()));
+        let store = BeadsStore::load(&dir).unwrap();
+        let written = store.issue("fe-3").unwrap();
+        assert_eq!(written.status, IssueStatus::InProgress);
+        assert!(written.updated_at.as_deref() > Some("2026-01-05T08:00:00Z"));
+
+        // The model catching up with the store settles the pending move
+        model.update(cx, |model, cx| model.apply_structure(structure(&dir), cx));
+        board.read_with(cx, |board, cx| {
+            assert!(board.pending.is_empty());
+            assert_eq!(board.error(), None);
+            assert_eq!(column_ids(&board.columns(cx))[1].1, vec!["fe-2", "fe-3"]);
+        });
+    }
+
+    #[gpui::test]
+    fn test_failed_move_is_reverted(cx: &mut TestAppContext) {
+        let (temp, dir) = copy_fixture("frontend");
+        let model = test_model(&dir, cx);
+        let board = cx.new(|cx| KanbanBoard::new(BoardScope::Rig("frontend".into()), model, cx));
+        drop(temp);
+
+        board.update(cx, |board, cx| {
+            board.move_issue("fe-3", IssueStatus::Closed, cx)
+        });
+        cx.run_until_parked();
+
+        board.read_with(cx, |board, cx| {
+            assert!(board.error().unwrap().starts_with("Couldn't move fe-3"));
+            assert_eq!(column_ids(&board.columns(cx))[2].1, vec!["fe-3"]);
+        });
+    }
+
+    #[gpui::test]
+    fn test_external_changes_show_up(cx: &mut TestAppContext) {
+        let (_temp, dir) = copy_fixture("frontend");
+        let model = test_model(&dir, cx);
+        let board =
+            cx.new(|cx| KanbanBoard::new(BoardScope::Rig("frontend".into()), model.clone(), cx));
+
+        let mut structure = structure(&dir);
+        structure.issues.push(Issue {
+            rig: Some("frontend".into()),
+            beads_dir: dir.clone(),
+            bead: BeadsIssue {
+                id: "fe-5".into(),
+                title: "Dark mode".into(),
+                ..Default::default()
+            },
+        });
+        model.update(cx, |model, cx| model.apply_structure(structure, cx));
+
+        board.read_with(cx, |board, cx| {
+            assert_eq!(column_ids(&board.columns(cx))[0].1, vec!["fe-5"]);
+        });
+    }
+}
@@ -0,0 +1,363 @@
+use collections::{BTreeMap, HashSet};
+use serde::Deserialize;
+
+use crate::agent_discovery::AgentRole;
+use crate::gt_cli::{GtCli, GtCommandError};
+use crate::town_model::parse_address;
+
+/// One message of `gt mail inbox <address> --json`
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct MailMessage {
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub subject: String,
+    /// Markdown
+    #[serde(default)]
+    pub body: String,
+    /// RFC 3339
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub read: bool,
+    /// `low`, `normal`, `high` or `urgent`
+    #[serde(default)]
+    pub priority: Option<String>,
+    /// e.g. `task`, `notification`, `reply` or `escalation`
+    #[serde(default, rename = "type")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    #[serde(default)]
+    pub reply_to: Option<String>,
+}
+
+impl MailMessage {
+    /// Whether the message escalates a problem: sent with `gt escalate`, marked
+    /// urgent, or with an `ESCALATION` subject
+    pub fn is_escalation(&self) -> bool {
+        self.kind.as_deref() == Some("escalation")
+            || self.priority.as_deref() == Some("urgent")
+            || self
+                .subject
+                .trim_start_matches('[')
+                .to_uppercase()
+                .starts_with("ESCALATION")
+    }
+
+    /// Who the message went from and to, e.g. `Mayor → Polecat frontend/toast`
+    pub fn route(&self) -> String {
+        format!("{} → {}", route_label(&self.from), route_label(&self.to))
+    }
+}
+
+/// Mail addresses may end in `/`, e.g. `mayor/`
+pub fn same_address(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+fn route_label(address: &str) -> String {
+    let address = address.trim_end_matches('/');
+    match parse_address(address) {
+        (role @ (AgentRole::Mayor | AgentRole::Deacon), None, _) => role.to_string(),
+        (role @ (AgentRole::Polecat | AgentRole::Crew), Some(rig), _) => {
+            let name = address.rsplit('/').next().unwrap_or(address);
+            format!("{} {}/{}", role, rig, name)
+        }
+        (AgentRole::Witness, Some(rig), _) => format!("Witness {}", rig),
+        _ => address.to_string(),
+    }
+}
+
+/// Whether a thread is listed for its recipients or its senders
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MailFolder {
+    #[default]
+    Inbox,
+    Outbox,
+}
+
+impl MailFolder {
+    pub fn label(self) -> &'static str {
+        match self {
+            MailFolder::Inbox => "Inbox",
+            MailFolder::Outbox => "Outbox",
+        }
+    }
+}
+
+/// Messages replying to each other, oldest first
+#[derive(Clone, Debug, PartialEq)]
+pub struct MailThread {
+    pub id: String,
+    pub messages: Vec<MailMessage>,
+}
+
+impl MailThread {
+    /// The subject of the message that started the thread
+    pub fn subject(&self) -> &str {
+        self.messages
+            .first()
+            .map(|message| message.subject.as_str())
+            .unwrap_or_default()
+    }
+
+    pub fn latest(&self) -> Option<&MailMessage> {
+        self.messages.last()
+    }
+
+    pub fn is_escalation(&self) -> bool {
+        self.messages.iter().any(MailMessage::is_escalation)
+    }
+
+    /// Messages to `address` it hasn't read yet
+    pub fn unread_for(&self, address: &str) -> usize {
+        self.messages
+            .iter()
+            .filter(|message| !message.read && same_address(&message.to, address))
+            .count()
+    }
+
+    pub fn is_in(&self, address: &str, folder: MailFolder) -> bool {
+        self.messages.iter().any(|message| match folder {
+            MailFolder::Inbox => same_address(&message.to, address),
+            MailFolder::Outbox => same_address(&message.from, address),
+        })
+    }
+}
+
+/// Every message read from the town's mailboxes, grouped into threads
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MailIndex {
+    /// Newest activity first
+    pub threads: Vec<MailThread>,
+    /// Mailboxes that couldn't be read, by address
+    pub errors: BTreeMap<String, String>,
+}
+
+impl MailIndex {
+    /// Groups messages by `thread_id`, or else by the first message of their reply
+    /// chain. A message seen in several mailboxes is kept once.
+    pub fn new(messages: Vec<MailMessage>) -> Self {
+        let mut by_id: BTreeMap<String, MailMessage> = BTreeMap::new();
+        for message in messages {
+            by_id.entry(message.id.clone()).or_insert(message);
+        }
+
+        let thread_of = |message: &MailMessage| -> String {
+            if let Some(thread_id) = &message.thread_id {
+                return thread_id.clone();
+            }
+            let mut root = message;
+            let mut seen = HashSet::default();
+            while let Some(parent) = root.reply_to.as_ref().and_then(|id| by_id.get(id)) {
+                if !seen.insert(parent.id.as_str()) {
+                    break;
+                }
+                if let Some(thread_id) = &parent.thread_id {
+                    return thread_id.clone();
+                }
+                root = parent;
+            }
+            root.id.clone()
+        };
+
+        let mut threads: BTreeMap<String, Vec<MailMessage>> = BTreeMap::new();
+        for message in by_id.values() {
+            threads
+                .entry(thread_of(message))
+                .or_default()
+                .push(message.clone());
+        }
+
+        let mut threads: Vec<MailThread> = threads
+            .into_iter()
+            .map(|(id, mut messages)| {
+                messages.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then(a.id.cmp(&b.id)));
+                MailThread { id, messages }
+            })
+            .collect();
+        threads.sort_by(|a, b| {
+            let latest = |thread: &MailThread| thread.latest().and_then(|m| m.timestamp.clone());
+            latest(b).cmp(&latest(a)).then(a.id.cmp(&b.id))
+        });
+
+        Self {
+            threads,
+            errors: BTreeMap::new(),
+        }
+    }
+
+    /// Reads the inbox of every address with `gt mail inbox <address> --json`.
+    ///
+    /// Outboxes are the messages other inboxes received from an address.
+    pub fn fetch(gt: &GtCli, addresses: &[String]) -> Self {
+        let mut messages = Vec::new();
+        let mut errors = BTreeMap::new();
+        for address in addresses {
+            let inbox: Result<Vec<MailMessage>, GtCommandError> =
+                gt.run_json(["mail", "inbox", address.as_str(), "--json"]);
+            match inbox {
+                Ok(inbox) => messages.extend(inbox),
+                Err(err) => {
+                    errors.insert(address.clone(), err.to_string());
+                }
+            }
+        }
+
+        let mut index = Self::new(messages);
+        index.errors = errors;
+        index
+    }
+
+    pub fn threads(&self, address: &str, folder: MailFolder) -> Vec<&MailThread> {
+        self.threads
+            .iter()
+            .filter(|thread| thread.is_in(address, folder))
+            .collect()
+    }
+
+    pub fn unread(&self, address: &str) -> usize {
+        self.threads
+            .iter()
+            .map(|thread| thread.unread_for(address))
+            .sum()
+    }
+
+    pub fn escalations(&self) -> impl Iterator<Item = &MailMessage> {
+        self.threads
+            .iter()
+            .flat_map(|thread| &thread.messages)
+            .filter(|message| message.is_escalation())
+    }
+
+    pub fn thread(&self, id: &str) -> Option<&MailThread> {
+        self.threads.iter().find(|thread| thread.id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOAST: &str = "frontend/polecats/toast";
+
+    fn message(id: &str, from: &str, to: &str, timestamp: &str) -> MailMessage {
+        MailMessage {
+            id: id.into(),
+            from: from.into(),
+            to: to.into(),
+            subject: format!("subject {}", id),
+            timestamp: Some(timestamp.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_threads_and_folders() {
+        let index = MailIndex::new(vec![
+            message("m1", "mayor/", TOAST, "2026-01-05T09:00:00Z"),
+            MailMessage {
+                reply_to: Some("m1".into()),
+                read: true,
+                ..message("m2", TOAST, "mayor/", "2026-01-05T09:30:00Z")
+            },
+            MailMessage {
+                reply_to: Some("m2".into()),
+                ..message("m3", "mayor/", TOAST, "2026-01-05T10:00:00Z")
+            },
+            // Seen again in a second mailbox
+            message("m1", "mayor/", TOAST, "2026-01-05T09:00:00Z"),
+            MailMessage {
+                thread_id: Some("t-stuck".into()),
+                priority: Some("urgent".into()),
+                ..message("m4", "frontend/witness", "mayor/", "2026-01-05T09:45:00Z")
+            },
+        ]);
+
+        let summary: Vec<(&str, Vec<&str>)> = index
+            .threads
+            .iter()
+            .map(|thread| {
+                let ids = thread.messages.iter().map(|m| m.id.as_str()).collect();
+                (thread.id.as_str(), ids)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("m1", vec!["m1", "m2", "m3"]), ("t-stuck", vec!["m4"])]
+        );
+        assert_eq!(index.threads[0].subject(), "subject m1");
+
+        assert_eq!(index.threads(TOAST, MailFolder::Inbox).len(), 1);
+        assert_eq!(index.threads(TOAST, MailFolder::Outbox).len(), 1);
+        assert_eq!(index.threads("mayor", MailFolder::Inbox).len(), 2);
+        assert_eq!(
+            index.threads("frontend/witness", MailFolder::Inbox).len(),
+            0
+        );
+        assert_eq!(index.unread(TOAST), 2);
+        assert_eq!(index.unread("mayor"), 1);
+
+        let escalations: Vec<&str> = index.escalations().map(|m| m.id.as_str()).collect();
+        assert_eq!(escalations, vec!["m4"]);
+        assert!(index.thread("t-stuck").unwrap().is_escalation());
+    }
+
+    #[test]
+    fn test_route() {
+        assert_eq!(
+            message("m1", "mayor/", TOAST, "").route(),
+            "Mayor → Polecat frontend/toast"
+        );
+        assert_eq!(
+            message("m2", "frontend/witness", "deacon", "").route(),
+            "Witness frontend → Deacon"
+        );
+        assert_eq!(
+            message("m3", "overseer", "mayor", "").route(),
+            "overseer → Mayor"
+        );
+    }
+
+    #[test]
+    fn test_escalation_subjects() {
+        let escalation = MailMessage {
+            subject: "[ESCALATION] toast is stuck on migrations".into(),
+            ..Default::default()
+        };
+        assert!(escalation.is_escalation());
+        assert!(!MailMessage::default().is_escalation());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fetch_reads_every_inbox() {
+        use crate::fake_gt::FakeGt;
+
+        let fake = FakeGt::new()
+            .respond(
+                "mail inbox mayor --json",
+                r#"[{"id": "m2", "from": "frontend/polecats/toast", "to": "mayor/",
+                     "subject": "Done", "body": "All **green**", "read": false}]"#,
+            )
+            .fail(
+                &format!("mail inbox {} --json", TOAST),
+                1,
+                "mailbox not found",
+            );
+
+        let index = MailIndex::fetch(&fake.cli(), &["mayor".to_string(), TOAST.to_string()]);
+        assert_eq!(index.threads.len(), 1);
+        assert_eq!(index.threads[0].messages[0].body, "All **green**");
+        assert_eq!(index.unread("mayor"), 1);
+        assert_eq!(
+            index.errors.get(TOAST).map(String::as_str),
+            Some(
+                "`gt mail inbox frontend/polecats/toast --json` exited with status 1: \
+                 mailbox not found"
+            )
+        );
+    }
+}
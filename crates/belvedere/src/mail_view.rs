@@ -0,0 +1,639 @@
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString, Subscription,
+    Task, Window, div, prelude::*, px,
+};
+use markdown::{Markdown, MarkdownElement};
+use std::time::Duration;
+use ui::{ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, LabelSize};
+
+use crate::gt_cli::GtCli;
+use crate::mail::{MailFolder, MailIndex, MailMessage, MailThread, same_address};
+use crate::town_item::{TownItem, TownItemEvent, markdown_style};
+use crate::town_model::{TownModel, TownModelEvent};
+
+/// How often the view reads every mailbox again on its own
+pub const MAIL_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Center pane item showing every agent's inbox and outbox as threads.
+///
+/// Mail is read with `gt mail inbox`, which leaves it unread for its recipient, so
+/// looking at an agent's mail here doesn't keep it from `gt mail check`. Each
+/// refresh runs one command per mailbox, so the view refreshes only when mailboxes
+/// come or go, every `MAIL_REFRESH_INTERVAL` and on request.
+pub struct MailView {
+    model: Entity<TownModel>,
+    gt: GtCli,
+    mail: Option<MailIndex>,
+    loading: bool,
+    /// The addresses read by the latest refresh
+    fetched_addresses: Vec<String>,
+    selected_address: Option<String>,
+    folder: MailFolder,
+    selected_thread: Option<String>,
+    /// The expanded message and its rendered body
+    open_message: Option<(String, Entity<Markdown>)>,
+    load_task: Option<Task<()>>,
+    _poll_task: Task<()>,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<TownItemEvent> for MailView {}
+
+impl MailView {
+    pub fn new(model: Entity<TownModel>, gt: GtCli, cx: &mut Context<Self>) -> Self {
+        let subscription = cx.subscribe(&model, |this, _, event: &TownModelEvent, cx| {
+            // Agents change on every poll, with their status and token usage
+            if *event == TownModelEvent::AgentsChanged
+                && this.addresses(cx) != this.fetched_addresses
+            {
+                this.refresh(cx);
+            }
+        });
+        let poll_task = cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(MAIL_REFRESH_INTERVAL).await;
+                if this.update(cx, |this, cx| this.refresh(cx)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut view = Self {
+            model,
+            gt,
+            mail: None,
+            loading: false,
+            fetched_addresses: Vec::new(),
+            selected_address: None,
+            folder: MailFolder::default(),
+            selected_thread: None,
+            open_message: None,
+            load_task: None,
+            _poll_task: poll_task,
+            focus_handle: cx.focus_handle(),
+            _subscription: subscription,
+        };
+        view.refresh(cx);
+        view
+    }
+
+    pub fn mail(&self) -> Option<&MailIndex> {
+        self.mail.as_ref()
+    }
+
+    pub fn selected_address(&self) -> Option<&str> {
+        self.selected_address.as_deref()
+    }
+
+    pub fn folder(&self) -> MailFolder {
+        self.folder
+    }
+
+    /// Every agent address in the town, i.e. every mailbox
+    pub fn addresses(&self, cx: &App) -> Vec<String> {
+        self.model
+            .read(cx)
+            .agents()
+            .map(|agent| agent.name.clone())
+            .collect()
+    }
+
+    /// Reads every mailbox again in the background
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        let addresses = self.addresses(cx);
+        self.fetched_addresses = addresses.clone();
+        let gt = self.gt.clone();
+        let fetch = cx.background_spawn(async move { MailIndex::fetch(&gt, &addresses) });
+
+        self.loading = true;
+        self.load_task = Some(cx.spawn(async move |this, cx| {
+            let mail = fetch.await;
+            this.update(cx, |this, cx| {
+                this.loading = false;
+                this.mail = Some(mail);
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    pub fn select_address(&mut self, address: Option<String>, cx: &mut Context<Self>) {
+        if self.selected_address != address {
+            self.selected_address = address;
+            self.selected_thread = None;
+            self.open_message = None;
+            cx.notify();
+        }
+    }
+
+    pub fn set_folder(&mut self, folder: MailFolder, cx: &mut Context<Self>) {
+        self.folder = folder;
+        self.selected_thread = None;
+        self.open_message = None;
+        cx.notify();
+    }
+
+    pub fn select_thread(&mut self, thread_id: String, cx: &mut Context<Self>) {
+        self.selected_thread = Some(thread_id);
+        self.open_message = None;
+        cx.notify();
+    }
+
+    /// Expands a message of the selected thread, rendering its body as markdown
+    pub fn open_message(&mut self, message_id: String, cx: &mut Context<Self>) {
+        let Some(message) = self.thread().and_then(|thread| {
+            thread
+                .messages
+                .iter()
+                .find(|message| message.id == message_id)
+                .cloned()
+        }) else {
+            return;
+        };
+        let body = cx.new(|cx| Markdown::new(message.body.into(), None, None, cx));
+        self.open_message = Some((message_id, body));
+        cx.notify();
+    }
+
+    /// The threads of the selected mailbox and folder
+    pub fn threads(&self) -> Vec<&MailThread> {
+        match (&self.mail, &self.selected_address) {
+            (Some(mail), Some(address)) => mail.threads(address, self.folder),
+            _ => Vec::new(),
+        }
+    }
+
+    fn thread(&self) -> Option<&MailThread> {
+        let id = self.selected_thread.as_deref()?;
+        self.mail.as_ref()?.thread(id)
+    }
+
+    fn render_mailboxes(&self, cx: &Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let addresses = self.addresses(cx);
+
+        div()
+            .id("mailboxes")
+            .flex()
+            .flex_col()
+            .w(px(220.0))
+            .flex_shrink_0()
+            .overflow_y_scroll()
+            .border_r_1()
+            .border_color(colors.border_variant)
+            .children(addresses.into_iter().map(|address| {
+                let unread = self.mail.as_ref().map_or(0, |mail| mail.unread(&address));
+                let selected = self.selected_address.as_deref() == Some(address.as_str());
+                let label = address.clone();
+                div()
+                    .id(SharedString::from(format!("mailbox-{}", address)))
+                    .flex()
+                    .items_center()
+                    .gap(px(6.0))
+                    .px(px(8.0))
+                    .py(px(3.0))
+                    .cursor_pointer()
+                    .when(selected, |this| this.bg(colors.element_selected))
+                    .hover(|style| style.bg(colors.element_hover))
+                    .child(
+                        div()
+                            .flex_1()
+                            .truncate()
+                            .text_color(colors.text)
+                            .when(unread > 0, |this| this.font_weight(gpui::FontWeight::BOLD))
+                            .child(label),
+                    )
+                    .when(unread > 0, |this| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(colors.text_accent)
+                                .child(unread.to_string()),
+                        )
+                    })
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.select_address(Some(address.clone()), cx)
+                    }))
+            }))
+    }
+
+    fn render_threads(&self, cx: &Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+        let threads = self.threads();
+        let address = self.selected_address.clone().unwrap_or_default();
+
+        let folder_button = |folder: MailFolder| {
+            Button::new(folder.label(), folder.label())
+                .style(if self.folder == folder {
+                    ButtonStyle::Filled
+                } else {
+                    ButtonStyle::Subtle
+                })
+                .label_size(LabelSize::Small)
+                .on_click(cx.listener(move |this, _, _, cx| this.set_folder(folder, cx)))
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .w(px(320.0))
+            .flex_shrink_0()
+            .border_r_1()
+            .border_color(colors.border_variant)
+            .child(
+                div()
+                    .flex()
+                    .gap(px(4.0))
+                    .p(px(6.0))
+                    .child(folder_button(MailFolder::Inbox))
+                    .child(folder_button(MailFolder::Outbox)),
+            )
+            .child(
+                div()
+                    .id("mail-threads")
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .when(self.selected_address.is_none(), |this| {
+                        this.child(
+                            div()
+                                .p(px(8.0))
+                                .text_color(colors.text_muted)
+                                .child("Select a mailbox"),
+                        )
+                    })
+                    .when(
+                        self.selected_address.is_some() && threads.is_empty(),
+                        |this| {
+                            this.child(
+                                div()
+                                    .p(px(8.0))
+                                    .text_color(colors.text_muted)
+                                    .child(format!("No mail in {}", self.folder.label())),
+                            )
+                        },
+                    )
+                    .children(threads.into_iter().map(|thread| {
+                        let unread = thread.unread_for(&address);
+                        let selected = self.selected_thread.as_deref() == Some(thread.id.as_str());
+                        let route = thread.latest().map(MailMessage::route).unwrap_or_default();
+                        let thread_id = thread.id.clone();
+                        div()
+                            .id(SharedString::from(format!("mail-thread-{}", thread.id)))
+                            .flex()
+                            .flex_col()
+                            .px(px(8.0))
+                            .py(px(4.0))
+                            .border_b_1()
+                            .border_color(colors.border_variant)
+                            .cursor_pointer()
+                            .when(selected, |this| this.bg(colors.element_selected))
+                            .hover(|style| style.bg(colors.element_hover))
+                            .child(
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap(px(6.0))
+                                    .when(unread > 0, |this| {
+                                        this.child(div().text_color(colors.text_accent).child("●"))
+                                    })
+                                    .when(thread.is_escalation(), |this| {
+                                        this.child(
+                                            div()
+                                                .text_xs()
+                                                .text_color(status.error)
+                                                .child("ESCALATION"),
+                                        )
+                                    })
+                                    .child(
+                                        div()
+                                            .flex_1()
+                                            .truncate()
+                                            .text_color(colors.text)
+                                            .when(unread > 0, |this| {
+                                                this.font_weight(gpui::FontWeight::BOLD)
+                                            })
+                                            .child(thread.subject().to_string()),
+                                    )
+                                    .when(thread.messages.len() > 1, |this| {
+                                        this.child(
+                                            div()
+                                                .text_xs()
+                                                .text_color(colors.text_muted)
+                                                .child(thread.messages.len().to_string()),
+                                        )
+                                    }),
+                            )
+                            .child(div().text_xs().text_color(colors.text_muted).child(route))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.select_thread(thread_id.clone(), cx)
+                            }))
+                    })),
+            )
+    }
+
+    fn render_message(
+        &self,
+        message: &MailMessage,
+        window: &Window,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+        let body = self
+            .open_message
+            .as_ref()
+            .filter(|(id, _)| *id == message.id)
+            .map(|(_, body)| body.clone());
+        let unread_here = !message.read
+            && self
+                .selected_address
+                .as_deref()
+                .is_some_and(|address| same_address(&message.to, address));
+        let message_id = message.id.clone();
+
+        div()
+            .id(SharedString::from(format!("mail-message-{}", message.id)))
+            .flex()
+            .flex_col()
+            .gap(px(4.0))
+            .p(px(8.0))
+            .rounded(px(4.0))
+            .border_1()
+            .border_color(if message.is_escalation() {
+                status.error
+            } else {
+                colors.border_variant
+            })
+            .cursor_pointer()
+            .on_click(cx.listener(move |this, _, _, cx| this.open_message(message_id.clone(), cx)))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_color(colors.text)
+                            .child(message.route()),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(if unread_here {
+                                colors.text_accent
+                            } else {
+                                colors.text_muted
+                            })
+                            .child(if message.read { "read" } else { "unread" }),
+                    )
+                    .when_some(message.timestamp.clone(), |this, timestamp| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(colors.text_muted)
+                                .child(timestamp),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .text_color(colors.text_muted)
+                    .child(message.subject.clone()),
+            )
+            .when_some(body, |this, body| {
+                this.child(div().pt(px(4.0)).child(MarkdownElement::new(
+                    body,
+                    markdown_style(cx.theme().colors().text, window, cx),
+                )))
+            })
+    }
+}
+
+impl Focusable for MailView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TownItem for MailView {
+    type Event = TownItemEvent;
+
+    fn tab_content_text(&self, _cx: &App) -> SharedString {
+        "Mail".into()
+    }
+
+    fn to_town_item_events(event: &Self::Event, mut f: impl FnMut(TownItemEvent)) {
+        f(*event);
+    }
+}
+
+impl Render for MailView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+        let escalations = self
+            .mail
+            .as_ref()
+            .map_or(0, |mail| mail.escalations().count());
+        let errors: Vec<String> = self
+            .mail
+            .iter()
+            .flat_map(|mail| &mail.errors)
+            .map(|(address, error)| format!("{}: {}", address, error))
+            .collect();
+        let thread = self.thread().cloned();
+
+        div()
+            .id("mail-view")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .text_sm()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .p(px(12.0))
+                    .border_b_1()
+                    .border_color(colors.border_variant)
+                    .child(div().text_lg().text_color(colors.text).child("Mail"))
+                    .when(escalations > 0, |this| {
+                        this.child(
+                            div()
+                                .text_color(status.error)
+                                .child(format!("⚠ {} escalations", escalations)),
+                        )
+                    })
+                    .when(self.loading, |this| {
+                        this.child(div().text_color(colors.text_muted).child("Loading…"))
+                    })
+                    .child(div().flex_1())
+                    .child(
+                        Button::new("refresh-mail", "Refresh")
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, _, cx| this.refresh(cx))),
+                    ),
+            )
+            .children(errors.into_iter().map(|error| {
+                div()
+                    .px(px(12.0))
+                    .text_xs()
+                    .text_color(status.error)
+                    .child(error)
+            }))
+            .child(
+                div()
+                    .flex()
+                    .flex_1()
+                    .min_h_0()
+                    .child(self.render_mailboxes(cx))
+                    .child(self.render_threads(cx))
+                    .child(
+                        div()
+                            .id("mail-thread")
+                            .flex()
+                            .flex_col()
+                            .flex_1()
+                            .gap(px(8.0))
+                            .p(px(12.0))
+                            .overflow_y_scroll()
+                            .map(|this| match &thread {
+                                Some(thread) => this
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .text_color(colors.text)
+                                            .child(thread.subject().to_string()),
+                                    )
+                                    .children(
+                                        thread.messages.iter().map(|message| {
+                                            self.render_message(message, window, cx)
+                                        }),
+                                    ),
+                                None => this
+                                    .text_color(colors.text_muted)
+                                    .child("Select a thread to read it"),
+                            }),
+                    ),
+            )
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::agent_discovery::AgentDirectory;
+    use crate::dashboard_buffer::{AgentInfo, AgentStatus, DashboardData};
+    use crate::fake_gt::FakeGt;
+    use crate::town_model::TownStructure;
+    use gpui::{AppContext as _, TestAppContext};
+
+    fn polecats(names: &[&str]) -> TownStructure {
+        TownStructure {
+            agents: names
+                .iter()
+                .map(|name| {
+                    AgentDirectory::from_rig_member(
+                        format!("/gt/frontend/polecats/{}", name).into(),
+                    )
+                    .unwrap()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[gpui::test]
+    fn test_mail_view_lists_threads(cx: &mut TestAppContext) {
+        let gt = FakeGt::new()
+            .respond(
+                "mail inbox frontend/polecats/toast --json",
+                r#"[{"id": "m1", "from": "mayor/", "to": "frontend/polecats/toast",
+                     "subject": "Pick up fe-12", "body": "See `fe-12`", "read": false,
+                     "timestamp": "2026-01-05T09:00:00Z"}]"#,
+            )
+            .respond(
+                "mail inbox frontend/polecats/nux --json",
+                r#"[{"id": "m2", "from": "frontend/polecats/toast",
+                     "to": "frontend/polecats/nux", "subject": "Rebase please",
+                     "read": true, "timestamp": "2026-01-05T10:00:00Z"}]"#,
+            );
+        let model = cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(polecats(&["toast", "nux"]), cx);
+            model
+        });
+        let view = cx.new(|cx| MailView::new(model, gt.cli(), cx));
+        cx.run_until_parked();
+
+        view.update(cx, |view, cx| {
+            assert_eq!(view.mail().unwrap().unread("frontend/polecats/toast"), 1);
+            view.select_address(Some("frontend/polecats/toast".into()), cx);
+            let subjects: Vec<&str> = view.threads().iter().map(|t| t.subject()).collect();
+            assert_eq!(subjects, vec!["Pick up fe-12"]);
+
+            view.set_folder(MailFolder::Outbox, cx);
+            let subjects: Vec<&str> = view.threads().iter().map(|t| t.subject()).collect();
+            assert_eq!(subjects, vec!["Rebase please"]);
+
+            view.select_thread("m2".into(), cx);
+            view.open_message("m2".into(), cx);
+            assert!(view.open_message.is_some());
+        });
+    }
+
+    #[gpui::test]
+    fn test_mail_view_refreshes_when_mailboxes_change(cx: &mut TestAppContext) {
+        let gt = FakeGt::new()
+            .respond("mail inbox frontend/polecats/toast --json", "[]")
+            .respond("mail inbox frontend/polecats/nux --json", "[]");
+        let model = cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(polecats(&["toast"]), cx);
+            model
+        });
+        let _view = cx.new(|cx| MailView::new(model.clone(), gt.cli(), cx));
+        cx.run_until_parked();
+        assert_eq!(gt.calls().len(), 1);
+
+        // A new status for the same agents doesn't read the mail again
+        model.update(cx, |model, cx| {
+            model.apply_dashboard_data(
+                DashboardData {
+                    agents: vec![AgentInfo {
+                        name: "frontend/polecats/toast".into(),
+                        status: AgentStatus::Active,
+                        token_usage: None,
+                        context_fill: Some(0.4),
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            )
+        });
+        cx.run_until_parked();
+        assert_eq!(gt.calls().len(), 1);
+
+        model.update(cx, |model, cx| {
+            model.apply_structure(polecats(&["toast", "nux"]), cx)
+        });
+        cx.run_until_parked();
+        let calls: Vec<String> = gt.calls().into_iter().map(|(_, args)| args).collect();
+        assert_eq!(
+            calls[1..],
+            [
+                "mail inbox frontend/polecats/nux --json",
+                "mail inbox frontend/polecats/toast --json"
+            ]
+        );
+    }
+}
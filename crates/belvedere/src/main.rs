@@ -3,9 +3,21 @@
 
 use anyhow::Result;
 use assets::Assets;
-use belvedere::Town;
-use gpui::{App, AppContext, Application, Bounds, WindowBounds, WindowOptions, actions, px, size};
-use std::path::PathBuf;
+use belvedere::{
+    Args, Command, DashboardWatch, DashboardWatchEvent, OpenTarget, Town,
+    dashboard_buffer::{DashboardFormatter, DashboardView},
+    mayor_chat::agent_project,
+    town_tree::TownNode,
+};
+use fs::{Fs, RealFs};
+use gpui::{
+    App, AppContext, Application, Bounds, Entity, Global, WindowBounds, WindowOptions, actions, px,
+    size,
+};
+use release_channel::AppVersion;
+use reqwest_client::ReqwestClient;
+use std::io::IsTerminal as _;
+use std::sync::Arc;
 
 actions!(belvedere, [Quit]);
 
@@ -13,25 +25,94 @@ fn quit(_: &Quit, cx: &mut App) {
     cx.quit();
 }
 
+/// Prints the dashboard once for `--print` and `--json`, returning the exit code
+fn print_dashboard(args: &Args) -> Result<i32> {
+    let http = ReqwestClient::user_agent("Belvedere")?;
+    let source = args.data_source(&args.town_path(), Arc::new(http));
+    match source.fetch() {
+        Ok(data) => {
+            print!("{}", args.format(&data));
+            Ok(0)
+        }
+        Err(err) => {
+            eprintln!("{}", DashboardFormatter::format_error(&err));
+            Ok(1)
+        }
+    }
+}
+
+/// Keeps `belvedere watch`'s watch alive for as long as the app runs
+struct ActiveWatch(Entity<DashboardWatch>);
+
+impl Global for ActiveWatch {}
+
+/// Redraws the text dashboard in the terminal for `belvedere watch`, exiting with 1
+/// once the town stays unavailable
+fn watch_dashboard(args: Args, no_color: bool) -> Result<()> {
+    let http = ReqwestClient::user_agent("Belvedere")?;
+    let color =
+        !no_color && std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal();
+
+    Application::headless().run(move |cx: &mut App| {
+        let source = args.data_source(&args.town_path(), Arc::new(http));
+        let fs: Arc<dyn Fs> = Arc::new(RealFs::new(None, cx.background_executor().clone()));
+        let dashboard = cx.new(|cx| {
+            let mut dashboard = DashboardView::new(source, cx);
+            dashboard.set_refresh_interval(Some(args.refresh_interval()), cx);
+            dashboard.watch(fs, cx);
+            dashboard
+        });
+        let watch =
+            cx.new(|cx| DashboardWatch::new(dashboard, color, Box::new(std::io::stdout()), cx));
+        cx.subscribe(&watch, |_, event: &DashboardWatchEvent, _| {
+            let DashboardWatchEvent::Unavailable(message) = event;
+            eprintln!("{}", message);
+            std::process::exit(1);
+        })
+        .detach();
+        cx.set_global(ActiveWatch(watch));
+    });
+
+    Ok(())
+}
+
 fn main() -> Result<()> {
     env_logger::init();
 
+    let args = Args::parse_env();
+    if let Some(Command::Watch { no_color }) = args.command {
+        return watch_dashboard(args, no_color);
+    }
+    if args.is_headless() {
+        std::process::exit(print_dashboard(&args)?);
+    }
+
     let app = Application::new().with_assets(Assets);
 
-    app.run(|cx: &mut App| {
+    app.run(move |cx: &mut App| {
         settings::init(cx);
         theme::init(theme::LoadThemes::JustBase, cx);
+        release_channel::init(AppVersion::load(env!("CARGO_PKG_VERSION"), None, None), cx);
+        gpui_tokio::init(cx);
+        match ReqwestClient::user_agent("Belvedere") {
+            Ok(http) => cx.set_http_client(Arc::new(http)),
+            Err(err) => log::error!("failed to create HTTP client: {}", err),
+        }
 
         cx.activate(true);
         cx.on_action(quit);
+        belvedere::town_tree::init(cx);
 
         let size = size(px(1200.), px(800.));
         let bounds = Bounds::centered(None, size, cx);
 
-        // Default to ~/gt/ directory
-        let gt_path = dirs::home_dir()
-            .map(|home| home.join("gt"))
-            .unwrap_or_else(|| PathBuf::from("gt"));
+        let gt_path = args.town_path();
+        let data_source = args.data_source(&gt_path, cx.http_client());
+        let refresh_interval = args.refresh_interval();
+        let open = args.open.clone();
+        let fs: Arc<dyn Fs> = Arc::new(RealFs::new(None, cx.background_executor().clone()));
+        // Agent sessions, like the Mayor chat, run in a project for the town
+        let project = agent_project(fs.clone(), cx);
 
         cx.open_window(
             WindowOptions {
@@ -44,7 +125,21 @@ fn main() -> Result<()> {
                 }),
                 ..Default::default()
             },
-            |_, cx| cx.new(|cx| Town::new(gt_path, cx)),
+            |_, cx| {
+                cx.new(|cx| {
+                    let mut town = Town::new(gt_path, cx);
+                    town.set_agent_project(project);
+                    town.watch(fs.clone(), cx);
+                    town.open_dashboard(data_source, refresh_interval, Some(fs), cx);
+                    match open {
+                        Some(OpenTarget::Convoy(id)) => town.open_convoy(id, cx),
+                        Some(OpenTarget::Agent(name)) => town.open_node(TownNode::Agent(name), cx),
+                        Some(OpenTarget::Rig(name)) => town.open_node(TownNode::Rig(name), cx),
+                        None => {}
+                    }
+                    town
+                })
+            },
         )
         .expect("Failed to open window");
     });
@@ -0,0 +1,545 @@
+use acp_thread::{
+    AcpThread, AcpThreadEvent, AgentThreadEntry, AssistantMessageChunk, ThreadStatus, ToolCall,
+    ToolCallStatus,
+};
+use agent_client_protocol as acp;
+use agent_servers::{AgentServer, AgentServerDelegate};
+use client::{Client, UserStore};
+use editor::Editor;
+use fs::Fs;
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, Hsla, Render, ScrollHandle,
+    SharedString, Subscription, Task, Window, div, prelude::*, px,
+};
+use language::LanguageRegistry;
+use markdown::{Markdown, MarkdownElement};
+use node_runtime::NodeRuntime;
+use project::Project;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use ui::{ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, Disableable, LabelSize};
+
+use crate::gt_cli::GtCli;
+use crate::town_item::{TownItem, TownItemEvent, markdown_style};
+
+/// Framing sent ahead of `gt prime`'s output when a Mayor session starts
+const MAYOR_PREAMBLE: &str = "You are the Mayor of this Gas Town, working from the town \
+directory. The human talks to you from Belvedere: turn what they ask for into beads, \
+convoys and slung work with `gt` and `bd`, and report back on progress.";
+
+/// The Mayor's role context: the preamble followed by `gt prime`, run in the town
+/// directory. Falls back to the preamble alone when `gt prime` fails.
+pub fn role_context(gt: &GtCli) -> String {
+    match gt.run(["prime"]) {
+        Ok(context) if !context.trim().is_empty() => {
+            format!("{}\n\n{}", MAYOR_PREAMBLE, context.trim())
+        }
+        Ok(_) => MAYOR_PREAMBLE.to_string(),
+        Err(err) => {
+            log::warn!("Couldn't load the Mayor's role context: {}", err);
+            MAYOR_PREAMBLE.to_string()
+        }
+    }
+}
+
+/// Creates the local project agent sessions run in.
+///
+/// Agent servers are installed and launched through a project, so Belvedere keeps
+/// one for the town even though it doesn't open its files. The app's HTTP client
+/// must be set before calling this.
+pub fn agent_project(fs: Arc<dyn Fs>, cx: &mut App) -> Entity<Project> {
+    let client = Client::production(cx);
+    client::init(&client, cx);
+    Project::init(&client, cx);
+    let (_, node_options) = watch::channel(None);
+    let node = NodeRuntime::new(cx.http_client(), None, node_options);
+    let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
+    let languages = Arc::new(LanguageRegistry::new(cx.background_executor().clone()));
+    Project::local(client, node, user_store, languages, fs, None, false, cx)
+}
+
+/// Where the Mayor conversation is
+pub enum MayorSession {
+    /// Launching the agent server and opening a thread
+    Connecting,
+    Ready(Entity<AcpThread>),
+    Failed(String),
+}
+
+/// Center pane item holding a conversation with the Mayor.
+///
+/// The Mayor is a Claude Code instance, so this connects to the agent over ACP,
+/// opens a thread in the town directory and primes it with the Mayor's role context
+/// before the first message. Tool calls that need permission are approved inline.
+pub struct MayorChat {
+    town_path: PathBuf,
+    gt: GtCli,
+    session: MayorSession,
+    /// Whether the role context has been sent; it is the thread's first message
+    primed: bool,
+    editor: Option<Entity<Editor>>,
+    /// Failure of the last message sent, shown above the editor
+    send_error: Option<String>,
+    scroll_handle: ScrollHandle,
+    focus_handle: FocusHandle,
+    _connect_task: Task<()>,
+    _send_task: Option<Task<()>>,
+    _thread_subscription: Option<Subscription>,
+}
+
+impl MayorChat {
+    pub fn new(
+        project: Entity<Project>,
+        server: Rc<dyn AgentServer>,
+        town_path: PathBuf,
+        gt: GtCli,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = AgentServerDelegate::new(
+            project.read(cx).agent_server_store().clone(),
+            project.clone(),
+            None,
+            None,
+        );
+        let connect = server.connect(Some(&town_path), delegate, cx);
+        let context = {
+            let gt = gt.clone();
+            cx.background_spawn(async move { role_context(&gt) })
+        };
+        let cwd = town_path.clone();
+        let connect_task = cx.spawn(async move |this, cx| {
+            let thread = async {
+                let (connection, _) = connect.await?;
+                cx.update(|cx| connection.new_thread(project, &cwd, cx))
+                    .await
+            }
+            .await;
+            let context = context.await;
+            this.update(cx, |this, cx| match thread {
+                Ok(thread) => this.set_thread(thread, context, cx),
+                Err(err) => {
+                    this.session = MayorSession::Failed(err.to_string());
+                    cx.emit(TownItemEvent::UpdateTab);
+                    cx.notify();
+                }
+            })
+            .ok();
+        });
+
+        Self {
+            town_path,
+            gt,
+            session: MayorSession::Connecting,
+            primed: false,
+            editor: None,
+            send_error: None,
+            scroll_handle: ScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            _connect_task: connect_task,
+            _send_task: None,
+            _thread_subscription: None,
+        }
+    }
+
+    pub fn session(&self) -> &MayorSession {
+        &self.session
+    }
+
+    pub fn thread(&self) -> Option<&Entity<AcpThread>> {
+        match &self.session {
+            MayorSession::Ready(thread) => Some(thread),
+            _ => None,
+        }
+    }
+
+    pub fn gt(&self) -> &GtCli {
+        &self.gt
+    }
+
+    pub fn is_generating(&self, cx: &App) -> bool {
+        self.thread()
+            .is_some_and(|thread| thread.read(cx).status() == ThreadStatus::Generating)
+    }
+
+    /// Sends a message to the Mayor. Ignored until the session is ready.
+    pub fn send(&mut self, message: String, cx: &mut Context<Self>) {
+        let Some(thread) = self.thread().cloned() else {
+            return;
+        };
+        self.send_error = None;
+        let send = thread.update(cx, |thread, cx| {
+            thread.send(vec![message.as_str().into()], cx)
+        });
+        self._send_task = Some(cx.spawn(async move |this, cx| {
+            let result = send.await;
+            this.update(cx, |this, cx| {
+                if let Err(err) = result {
+                    this.send_error = Some(err.to_string());
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    /// Stops the Mayor's current turn
+    pub fn stop(&mut self, cx: &mut Context<Self>) {
+        if let Some(thread) = self.thread().cloned() {
+            thread.update(cx, |thread, cx| thread.cancel(cx)).detach();
+        }
+    }
+
+    fn set_thread(&mut self, thread: Entity<AcpThread>, context: String, cx: &mut Context<Self>) {
+        self._thread_subscription = Some(cx.subscribe(&thread, Self::handle_thread_event));
+        self.session = MayorSession::Ready(thread);
+        self.primed = true;
+        self.send(context, cx);
+        cx.emit(TownItemEvent::UpdateTab);
+    }
+
+    fn handle_thread_event(
+        &mut self,
+        _: Entity<AcpThread>,
+        event: &AcpThreadEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            AcpThreadEvent::NewEntry | AcpThreadEvent::EntryUpdated(_) => {
+                self.scroll_handle.scroll_to_bottom();
+            }
+            AcpThreadEvent::LoadError(error) => {
+                self.session = MayorSession::Failed(error.to_string());
+                cx.emit(TownItemEvent::UpdateTab);
+            }
+            _ => {}
+        }
+        cx.notify();
+    }
+
+    fn authorize(
+        &mut self,
+        tool_call_id: acp::ToolCallId,
+        option_id: acp::PermissionOptionId,
+        option_kind: acp::PermissionOptionKind,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(thread) = self.thread().cloned() {
+            thread.update(cx, |thread, cx| {
+                thread.authorize_tool_call(tool_call_id, option_id, option_kind, cx)
+            });
+        }
+    }
+
+    fn send_from_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(editor) = self.editor.clone() else {
+            return;
+        };
+        let message = editor.read(cx).text(cx);
+        let message = message.trim();
+        if message.is_empty() || self.thread().is_none() {
+            return;
+        }
+        self.send(message.to_string(), cx);
+        editor.update(cx, |editor, cx| editor.clear(window, cx));
+    }
+
+    fn editor(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<Editor> {
+        self.editor
+            .get_or_insert_with(|| {
+                cx.new(|cx| {
+                    let mut editor = Editor::auto_height(3, 10, window, cx);
+                    editor.set_placeholder_text("Tell the Mayor what you want…", window, cx);
+                    editor
+                })
+            })
+            .clone()
+    }
+
+    fn render_markdown(
+        markdown: Option<&Entity<Markdown>>,
+        color: Hsla,
+        window: &Window,
+        cx: &App,
+    ) -> Option<MarkdownElement> {
+        markdown.map(|markdown| {
+            MarkdownElement::new(markdown.clone(), markdown_style(color, window, cx))
+        })
+    }
+
+    fn render_entry(
+        &self,
+        index: usize,
+        entry: &AgentThreadEntry,
+        window: &Window,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let entry_div = div()
+            .flex()
+            .flex_col()
+            .gap(px(4.0))
+            .px(px(12.0))
+            .py(px(8.0));
+
+        match entry {
+            // The role context is long and only there to prime the Mayor
+            AgentThreadEntry::UserMessage(_) if index == 0 && self.primed => entry_div.child(
+                div()
+                    .text_xs()
+                    .text_color(colors.text_muted)
+                    .child("Mayor role context loaded with `gt prime`"),
+            ),
+            AgentThreadEntry::UserMessage(message) => entry_div
+                .bg(colors.element_background)
+                .border_y_1()
+                .border_color(colors.border_variant)
+                .child(div().text_xs().text_color(colors.text_muted).child("You"))
+                .children(Self::render_markdown(
+                    message.content.markdown(),
+                    colors.text,
+                    window,
+                    cx,
+                )),
+            AgentThreadEntry::AssistantMessage(message) => entry_div
+                .child(div().text_xs().text_color(colors.text_muted).child("Mayor"))
+                .children(message.chunks.iter().filter_map(|chunk| match chunk {
+                    AssistantMessageChunk::Message { block } => {
+                        Self::render_markdown(block.markdown(), colors.text, window, cx)
+                    }
+                    AssistantMessageChunk::Thought { block } => {
+                        Self::render_markdown(block.markdown(), colors.text_muted, window, cx)
+                    }
+                })),
+            AgentThreadEntry::ToolCall(tool_call) => {
+                entry_div.child(self.render_tool_call(index, tool_call, window, cx))
+            }
+        }
+    }
+
+    fn render_tool_call(
+        &self,
+        index: usize,
+        tool_call: &ToolCall,
+        window: &Window,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+        let status_color = match &tool_call.status {
+            ToolCallStatus::Completed => status.success,
+            ToolCallStatus::Failed | ToolCallStatus::Rejected => status.error,
+            ToolCallStatus::WaitingForConfirmation { .. } => status.warning,
+            _ => colors.text_muted,
+        };
+        let options = match &tool_call.status {
+            ToolCallStatus::WaitingForConfirmation { options, .. } => options.as_slice(),
+            _ => &[],
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(4.0))
+            .p(px(6.0))
+            .rounded_sm()
+            .border_1()
+            .border_color(colors.border_variant)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .child(div().flex_1().min_w_0().child(MarkdownElement::new(
+                        tool_call.label.clone(),
+                        markdown_style(colors.text, window, cx),
+                    )))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(status_color)
+                            .child(tool_call.status.to_string()),
+                    ),
+            )
+            .when(!options.is_empty(), |this| {
+                this.child(
+                    div()
+                        .flex()
+                        .gap(px(4.0))
+                        .children(options.iter().map(|option| {
+                            let tool_call_id = tool_call.id.clone();
+                            let option_id = option.option_id.clone();
+                            let option_kind = option.kind;
+                            Button::new(
+                                SharedString::from(format!("permission-{}-{}", index, option_id.0)),
+                                option.name.clone(),
+                            )
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(
+                                move |this, _, _, cx| {
+                                    this.authorize(
+                                        tool_call_id.clone(),
+                                        option_id.clone(),
+                                        option_kind,
+                                        cx,
+                                    )
+                                },
+                            ))
+                        })),
+                )
+            })
+    }
+}
+
+impl EventEmitter<TownItemEvent> for MayorChat {}
+
+impl Focusable for MayorChat {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TownItem for MayorChat {
+    type Event = TownItemEvent;
+
+    fn tab_content_text(&self, _cx: &App) -> SharedString {
+        match &self.session {
+            MayorSession::Failed(_) => "Mayor (disconnected)".into(),
+            _ => "Mayor".into(),
+        }
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(format!("Mayor session in {}", self.town_path.display()).into())
+    }
+
+    fn to_town_item_events(event: &Self::Event, mut f: impl FnMut(TownItemEvent)) {
+        f(*event);
+    }
+}
+
+impl Render for MayorChat {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let editor = self.editor(window, cx);
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+        let generating = self.is_generating(cx);
+        let ready = self.thread().is_some();
+
+        let transcript = match &self.session {
+            MayorSession::Connecting => div()
+                .p(px(12.0))
+                .text_color(colors.text_muted)
+                .child("Starting the Mayor…")
+                .into_any_element(),
+            MayorSession::Failed(error) => div()
+                .p(px(12.0))
+                .text_color(status.error)
+                .child(format!("Couldn't start the Mayor: {}", error))
+                .into_any_element(),
+            MayorSession::Ready(thread) => div()
+                .flex()
+                .flex_col()
+                .children(
+                    thread
+                        .read(cx)
+                        .entries()
+                        .iter()
+                        .enumerate()
+                        .map(|(index, entry)| self.render_entry(index, entry, window, cx)),
+                )
+                .into_any_element(),
+        };
+
+        div()
+            .id("mayor-chat")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .text_sm()
+            .child(
+                div()
+                    .id("mayor-transcript")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .child(transcript),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(px(8.0))
+                    .p(px(12.0))
+                    .border_t_1()
+                    .border_color(colors.border_variant)
+                    .when_some(self.send_error.clone(), |this, error| {
+                        this.child(div().text_xs().text_color(status.error).child(error))
+                    })
+                    .child(
+                        div()
+                            .p(px(6.0))
+                            .rounded_sm()
+                            .border_1()
+                            .border_color(colors.border)
+                            .child(editor),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(px(8.0))
+                            .child(div().text_xs().text_color(colors.text_muted).child(
+                                if generating {
+                                    "The Mayor is working…"
+                                } else {
+                                    ""
+                                },
+                            ))
+                            .child(div().flex_1())
+                            .when(generating, |this| {
+                                this.child(
+                                    Button::new("mayor-stop", "Stop")
+                                        .style(ButtonStyle::Subtle)
+                                        .on_click(cx.listener(|this, _, _, cx| this.stop(cx))),
+                                )
+                            })
+                            .child(
+                                Button::new("mayor-send", "Send")
+                                    .style(ButtonStyle::Filled)
+                                    .disabled(!ready || generating)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.send_from_editor(window, cx)
+                                    })),
+                            ),
+                    ),
+            )
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::fake_gt::FakeGt;
+
+    #[test]
+    fn test_role_context_includes_gt_prime() {
+        let gt = FakeGt::new().respond("prime", "# Mayor\nYou coordinate the rigs.\n");
+        let context = role_context(&gt.cli().town_path(gt.path()));
+        assert!(context.starts_with(MAYOR_PREAMBLE));
+        assert!(context.ends_with("# Mayor\nYou coordinate the rigs."));
+        assert_eq!(
+            gt.calls(),
+            vec![(gt.path().canonicalize().unwrap(), "prime".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_role_context_falls_back_to_preamble() {
+        let gt = FakeGt::new().fail("prime", 1, "not in a town");
+        assert_eq!(role_context(&gt.cli()), MAYOR_PREAMBLE);
+    }
+}
@@ -0,0 +1,328 @@
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString, Subscription,
+    Window, div, prelude::*, px,
+};
+use ui::ActiveTheme;
+
+use crate::agent_discovery::AgentRole;
+use crate::dashboard_buffer::AgentStatus;
+use crate::town_item::{TownItem, TownItemEvent};
+use crate::town_model::{TownModel, TownModelEvent};
+use crate::town_tree::{RigGroup, TownNode, node_path};
+
+/// Center pane item summarizing a node selected in the town tree
+pub struct NodeDetail {
+    node: TownNode,
+    model: Entity<TownModel>,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+/// A titled list of label/value rows
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeSummary {
+    pub title: SharedString,
+    pub rows: Vec<(SharedString, SharedString)>,
+}
+
+impl NodeSummary {
+    fn new(title: impl Into<SharedString>) -> Self {
+        Self {
+            title: title.into(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn row(mut self, label: impl Into<SharedString>, value: impl Into<SharedString>) -> Self {
+        self.rows.push((label.into(), value.into()));
+        self
+    }
+
+    /// The value of the first row called `label`
+    pub fn value(&self, label: &str) -> Option<&str> {
+        self.rows
+            .iter()
+            .find(|(row_label, _)| row_label.as_ref() == label)
+            .map(|(_, value)| value.as_ref())
+    }
+}
+
+impl EventEmitter<TownItemEvent> for NodeDetail {}
+
+impl NodeDetail {
+    pub fn new(node: TownNode, model: Entity<TownModel>, cx: &mut Context<Self>) -> Self {
+        let subscription = cx.subscribe(&model, |_, _, _: &TownModelEvent, cx| {
+            cx.emit(TownItemEvent::UpdateTab);
+            cx.notify();
+        });
+
+        Self {
+            node,
+            model,
+            focus_handle: cx.focus_handle(),
+            _subscription: subscription,
+        }
+    }
+
+    pub fn node(&self) -> &TownNode {
+        &self.node
+    }
+
+    pub fn summary(&self, cx: &App) -> NodeSummary {
+        let model = self.model.read(cx);
+        let path = node_path(model, &self.node)
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        match &self.node {
+            TownNode::Town => {
+                let agents = model.agents().count();
+                let active = model
+                    .agents()
+                    .filter(|agent| agent.status == AgentStatus::Active)
+                    .count();
+                NodeSummary::new("Town")
+                    .row("Rigs", model.rigs().count().to_string())
+                    .row("Agents", format!("{} ({} active)", agents, active))
+                    .row("Hooks", model.hooks().count().to_string())
+                    .row("Convoys", model.convoys().count().to_string())
+            }
+            TownNode::Rig(name) => {
+                if model.rig(name).is_none() {
+                    return missing(name);
+                }
+                NodeSummary::new(name.clone())
+                    .row("Path", path)
+                    .row("Agents", join(model.agents_in_rig(name).map(|a| &a.name)))
+                    .row("Crew", model.crew_in_rig(name).count().to_string())
+                    .row("Hooks", model.hooks_in_rig(name).count().to_string())
+            }
+            TownNode::Group { rig, group } => {
+                let members = match group {
+                    RigGroup::Crew => join(model.crew_in_rig(rig).map(|crew| &crew.name)),
+                    RigGroup::Hooks => join(model.hooks_in_rig(rig).map(|hook| &hook.name)),
+                    RigGroup::Formulas => join(model.formulas_in_rig(rig).map(|f| &f.name)),
+                    RigGroup::Polecats => join(
+                        model
+                            .agents_in_rig(rig)
+                            .filter(|agent| agent.role == AgentRole::Polecat)
+                            .map(|agent| &agent.name),
+                    ),
+                };
+                NodeSummary::new(format!("{} {}", rig, group.label()))
+                    .row("Path", path)
+                    .row("Members", members)
+            }
+            TownNode::Agent(name) => {
+                let Some(agent) = model.agent(name) else {
+                    return missing(name);
+                };
+                let status = match &agent.status {
+                    AgentStatus::Active => "Active".to_string(),
+                    AgentStatus::Idle => "Idle".to_string(),
+                    AgentStatus::Error(message) => format!("Error: {}", message),
+                };
+                let hook = model
+                    .hook_for_agent(name)
+                    .map(|hook| hook.id())
+                    .unwrap_or_else(|| "none".into());
+                let mut summary = NodeSummary::new(name.clone())
+                    .row("Role", agent.role.to_string())
+                    .row("Status", status)
+                    .row("Rig", agent.rig.clone().unwrap_or_else(|| "town".into()))
+                    .row("Path", path)
+                    .row("Hook", hook)
+                    .row(
+                        "Convoys",
+                        join(model.convoys_for_agent(name).map(|c| &c.id)),
+                    );
+                if let Some(usage) = &agent.token_usage {
+                    summary = summary.row(
+                        "Tokens",
+                        format!("{}↓ {}↑", usage.input_tokens, usage.output_tokens),
+                    );
+                }
+                if let Some(fill) = agent.context_fill {
+                    summary = summary.row("Context", format!("{}%", (fill * 100.0).round()));
+                }
+                summary
+            }
+            TownNode::Hook(id) => {
+                let Some(hook) = model.hook(id) else {
+                    return missing(id);
+                };
+                NodeSummary::new(id.clone())
+                    .row("Rig", hook.rig.clone())
+                    .row("Path", path)
+                    .row(
+                        "Agent",
+                        hook.agent.clone().unwrap_or_else(|| "orphaned".into()),
+                    )
+            }
+            TownNode::Formula(id) => {
+                let Some(formula) = model.formula(id) else {
+                    return missing(id);
+                };
+                NodeSummary::new(id.clone())
+                    .row("Rig", formula.rig.clone().unwrap_or_else(|| "town".into()))
+                    .row("Path", path)
+            }
+        }
+    }
+}
+
+fn missing(name: &str) -> NodeSummary {
+    NodeSummary::new(name.to_string()).row("Status", "No longer part of the town")
+}
+
+fn join<'a>(names: impl Iterator<Item = &'a String>) -> String {
+    let names: Vec<&str> = names.map(String::as_str).collect();
+    if names.is_empty() {
+        "none".into()
+    } else {
+        names.join(", ")
+    }
+}
+
+impl Focusable for NodeDetail {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TownItem for NodeDetail {
+    type Event = TownItemEvent;
+
+    fn tab_content_text(&self, cx: &App) -> SharedString {
+        self.summary(cx).title
+    }
+
+    fn to_town_item_events(event: &Self::Event, mut f: impl FnMut(TownItemEvent)) {
+        f(*event);
+    }
+}
+
+impl Render for NodeDetail {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let summary = self.summary(cx);
+        let colors = cx.theme().colors();
+
+        div()
+            .id("node-detail")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .gap(px(8.0))
+            .p(px(16.0))
+            .overflow_y_scroll()
+            .child(
+                div()
+                    .text_lg()
+                    .text_color(colors.text)
+                    .child(summary.title.clone()),
+            )
+            .children(summary.rows.into_iter().map(|(label, value)| {
+                div()
+                    .flex()
+                    .gap(px(12.0))
+                    .child(
+                        div()
+                            .w(px(96.0))
+                            .flex_shrink_0()
+                            .text_color(colors.text_muted)
+                            .child(label),
+                    )
+                    .child(div().text_color(colors.text).child(value))
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_discovery::AgentDirectory;
+    use crate::dashboard_buffer::{AgentInfo, DashboardData, TokenUsage};
+    use crate::town_model::{Hook, Rig, TownStructure};
+    use gpui::{AppContext as _, TestAppContext};
+
+    fn test_model(cx: &mut TestAppContext) -> Entity<TownModel> {
+        let structure = TownStructure {
+            rigs: vec![Rig {
+                name: "frontend".into(),
+                path: "/gt/frontend".into(),
+            }],
+            agents: vec![
+                AgentDirectory::from_rig_member("/gt/frontend/polecats/toast".into()).unwrap(),
+            ],
+            hooks: vec![Hook {
+                name: "toast".into(),
+                rig: "frontend".into(),
+                path: "/gt/frontend/hooks/toast".into(),
+                agent: None,
+            }],
+            ..Default::default()
+        };
+        let data = DashboardData {
+            agents: vec![AgentInfo {
+                name: "frontend/polecats/toast".into(),
+                status: AgentStatus::Error("tests failing".into()),
+                token_usage: Some(TokenUsage {
+                    input_tokens: 1200,
+                    output_tokens: 300,
+                }),
+                context_fill: Some(0.42),
+            }],
+            ..Default::default()
+        };
+
+        cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(structure, cx);
+            model.apply_dashboard_data(data, cx);
+            model
+        })
+    }
+
+    #[gpui::test]
+    fn test_agent_summary(cx: &mut TestAppContext) {
+        let model = test_model(cx);
+        let detail = cx.new(|cx| {
+            NodeDetail::new(TownNode::Agent("frontend/polecats/toast".into()), model, cx)
+        });
+
+        detail.read_with(cx, |detail, cx| {
+            let summary = detail.summary(cx);
+            assert_eq!(summary.title.as_ref(), "frontend/polecats/toast");
+            assert_eq!(summary.value("Role"), Some("Polecat"));
+            assert_eq!(summary.value("Status"), Some("Error: tests failing"));
+            assert_eq!(summary.value("Hook"), Some("frontend/toast"));
+            assert_eq!(summary.value("Tokens"), Some("1200↓ 300↑"));
+            assert_eq!(summary.value("Context"), Some("42%"));
+        });
+    }
+
+    #[gpui::test]
+    fn test_summary_follows_model(cx: &mut TestAppContext) {
+        let model = test_model(cx);
+        let detail = cx
+            .new(|cx| NodeDetail::new(TownNode::Hook("frontend/toast".into()), model.clone(), cx));
+
+        detail.read_with(cx, |detail, cx| {
+            assert_eq!(
+                detail.summary(cx).value("Agent"),
+                Some("frontend/polecats/toast")
+            );
+        });
+
+        model.update(cx, |model, cx| {
+            model.apply_structure(TownStructure::default(), cx)
+        });
+        detail.read_with(cx, |detail, cx| {
+            assert_eq!(
+                detail.summary(cx).value("Status"),
+                Some("No longer part of the town")
+            );
+        });
+    }
+}
@@ -0,0 +1,1050 @@
+use editor::EditorEvent;
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString, Subscription,
+    Task, WeakEntity, Window, div, prelude::*, px,
+};
+use parking_lot::Mutex;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use terminal::alacritty_terminal::term::search::RegexSearch;
+use terminal::terminal_settings::{AlternateScroll, CursorShape};
+use terminal::{Terminal, TerminalBuilder};
+use terminal_view::TerminalView;
+use ui::{
+    ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, ContextMenu, Disableable,
+    DropdownMenu, LabelSize, Toggleable,
+};
+use ui_input::InputField;
+
+use crate::agent_discovery::AgentRole;
+use crate::agent_inspector::{LOG_TAIL_BYTES, find_log_file};
+use crate::gt_cli::GtCli;
+use crate::town_item::{TownItem, TownItemEvent};
+use crate::town_model::{Agent, TownModel};
+
+/// How often each session is read for new output
+pub const OUTPUT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Lines of history captured from tmux above the visible pane, and kept as
+/// scrollback in the terminal
+const SCROLLBACK_LINES: usize = 2000;
+
+/// Lines at the end of a capture that may be redrawn in place. A change reaching
+/// further up replaces the whole capture, since the cursor can't move into the
+/// scrollback to erase it.
+const MAX_REWRITE_LINES: usize = 24;
+
+/// Clears the screen and the scrollback, leaving the cursor at the top
+const CLEAR_TERMINAL: &[u8] = b"\x1b[H\x1b[2J\x1b[3J";
+
+/// Where an agent's session output is read from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OutputSource {
+    /// A tmux session, captured with `tmux capture-pane`
+    Tmux(String),
+    /// A log file, tailed as it grows
+    LogFile(PathBuf),
+}
+
+impl OutputSource {
+    /// The agent's newest log file when it writes one, otherwise its tmux session.
+    /// This lists the agent's directories, so it runs off the main thread.
+    pub fn for_agent(agent: &Agent) -> Self {
+        agent
+            .path
+            .as_deref()
+            .and_then(find_log_file)
+            .map(OutputSource::LogFile)
+            .unwrap_or_else(|| OutputSource::Tmux(agent.tmux_session()))
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            OutputSource::Tmux(session) => format!("tmux {}", session),
+            OutputSource::LogFile(path) => path.display().to_string(),
+        }
+    }
+}
+
+/// How the terminal changes after reading a source
+#[derive(Debug, PartialEq, Eq)]
+pub enum OutputChunk {
+    Unchanged,
+    /// New output following what was already written
+    Append(Vec<u8>),
+    /// The last `erase` lines written were redrawn, and `bytes` replaces them
+    Rewrite {
+        erase: usize,
+        bytes: Vec<u8>,
+    },
+    /// Output that replaces everything written so far
+    Replace(Vec<u8>),
+}
+
+/// Compares two captures of a tmux pane.
+///
+/// Captures are snapshots of the pane and its history, so new output shows up as
+/// lines past the end of the previous capture, with old lines scrolling off the top
+/// once the history is full. A TUI also redraws its last lines in place, like a
+/// spinner or a status line, so only the lines after the longest run shared with
+/// the previous capture are rewritten. Anything else replaces the whole capture.
+pub fn diff_capture(previous: &[String], current: &[String]) -> OutputChunk {
+    if previous == current {
+        return OutputChunk::Unchanged;
+    }
+    if current.is_empty() {
+        return OutputChunk::Replace(Vec::new());
+    }
+    // The line of the previous capture the current one starts at, and how many
+    // lines from there both share, preferring the longest shared run
+    let mut overlap: Option<(usize, usize)> = None;
+    for start in (0..previous.len()).filter(|&start| previous[start] == current[0]) {
+        let common = previous[start..]
+            .iter()
+            .zip(current)
+            .take_while(|(previous, current)| previous == current)
+            .count();
+        if overlap.is_none_or(|(_, longest)| common > longest) {
+            overlap = Some((start, common));
+        }
+    }
+    let Some((start, common)) = overlap else {
+        return OutputChunk::Replace(join_lines(current));
+    };
+    match previous.len() - start - common {
+        0 => OutputChunk::Append(join_lines(&current[common..])),
+        erase if erase <= MAX_REWRITE_LINES => OutputChunk::Rewrite {
+            erase,
+            bytes: join_lines(&current[common..]),
+        },
+        _ => OutputChunk::Replace(join_lines(current)),
+    }
+}
+
+fn join_lines(lines: &[String]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for line in lines {
+        bytes.extend_from_slice(line.as_bytes());
+        bytes.push(b'\n');
+    }
+    bytes
+}
+
+/// Reads an agent's session output incrementally.
+///
+/// A reader made for an agent picks its source on the first read, and looks for a
+/// newer one whenever the current source has nothing new, so it follows the
+/// runtime onto a rotated log.
+pub struct OutputReader {
+    /// The agent whose output is read, unless the source is fixed
+    agent: Option<Agent>,
+    /// `None` until an agent's reader first reads
+    source: Option<OutputSource>,
+    /// Runs `tmux`
+    tmux: GtCli,
+    /// Lines of the last tmux capture
+    captured: Vec<String>,
+    /// Bytes of the log file written so far
+    offset: Option<u64>,
+}
+
+impl OutputReader {
+    /// Reads `source` only
+    pub fn new(source: OutputSource, tmux: GtCli) -> Self {
+        Self {
+            agent: None,
+            source: Some(source),
+            tmux,
+            captured: Vec::new(),
+            offset: None,
+        }
+    }
+
+    /// Reads whichever of `agent`'s sources is newest
+    pub fn for_agent(agent: Agent, tmux: GtCli) -> Self {
+        Self {
+            agent: Some(agent),
+            source: None,
+            tmux,
+            captured: Vec::new(),
+            offset: None,
+        }
+    }
+
+    pub fn source(&self) -> Option<&OutputSource> {
+        self.source.as_ref()
+    }
+
+    /// Reads what the session printed since the last call
+    pub fn read(&mut self) -> Result<OutputChunk, String> {
+        if self.source.is_none() {
+            self.source = self.agent.as_ref().map(OutputSource::for_agent);
+        }
+        let result = self.read_source();
+        if !matches!(result, Ok(OutputChunk::Unchanged) | Err(_)) {
+            return result;
+        }
+        // The runtime may have moved on to a new log
+        let newest = self.agent.as_ref().map(OutputSource::for_agent);
+        if newest.is_none() || newest == self.source {
+            return result;
+        }
+        self.source = newest;
+        self.captured.clear();
+        self.offset = None;
+        self.read_source()
+    }
+
+    fn read_source(&mut self) -> Result<OutputChunk, String> {
+        match self.source.clone() {
+            Some(OutputSource::Tmux(session)) => self.capture_pane(&session),
+            Some(OutputSource::LogFile(path)) => self.tail_log(&path),
+            None => Ok(OutputChunk::Unchanged),
+        }
+    }
+
+    /// Runs `tmux capture-pane` with escape sequences kept, so colors survive
+    fn capture_pane(&mut self, session: &str) -> Result<OutputChunk, String> {
+        let history = format!("-{}", SCROLLBACK_LINES);
+        let output = self
+            .tmux
+            .run([
+                "capture-pane",
+                "-p",
+                "-e",
+                "-J",
+                "-t",
+                session,
+                "-S",
+                &history,
+            ])
+            .map_err(|err| err.to_string())?;
+        let mut lines: Vec<String> = output.lines().map(String::from).collect();
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        let chunk = diff_capture(&self.captured, &lines);
+        self.captured = lines;
+        Ok(chunk)
+    }
+
+    fn tail_log(&mut self, path: &Path) -> Result<OutputChunk, String> {
+        let describe = |err: std::io::Error| format!("{}: {}", path.display(), err);
+        let mut file = File::open(path).map_err(describe)?;
+        let len = file.metadata().map_err(describe)?.len();
+        let (start, replace) = match self.offset {
+            Some(offset) if offset == len => return Ok(OutputChunk::Unchanged),
+            Some(offset) if offset < len => (offset, false),
+            // A new log, or one that was truncated and started over
+            _ => (len.saturating_sub(LOG_TAIL_BYTES), true),
+        };
+        file.seek(SeekFrom::Start(start)).map_err(describe)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).map_err(describe)?;
+        self.offset = Some(start + bytes.len() as u64);
+        Ok(if replace {
+            OutputChunk::Replace(bytes)
+        } else {
+            OutputChunk::Append(bytes)
+        })
+    }
+}
+
+/// Columns and rows of a grid holding `count` panes, as square as possible
+pub fn grid_shape(count: usize) -> (usize, usize) {
+    if count == 0 {
+        return (0, 0);
+    }
+    let columns = (1..=count)
+        .find(|columns| columns * columns >= count)
+        .unwrap_or(count);
+    (columns, count.div_ceil(columns))
+}
+
+pub enum PolecatPaneEvent {
+    Close,
+}
+
+/// One agent's session output, streamed into a display-only terminal.
+///
+/// While following, the terminal stays scrolled to the newest output; otherwise
+/// new output accumulates in the scrollback without moving the view.
+pub struct PolecatPane {
+    agent: String,
+    /// Where output was last read from, once the first read picked a source
+    source: Option<OutputSource>,
+    terminal: Result<Entity<Terminal>, String>,
+    /// Created on first render, since it needs a window
+    terminal_view: Option<Entity<TerminalView>>,
+    /// The last read failure, e.g. a tmux session that isn't running
+    error: Option<String>,
+    follow: bool,
+    query: String,
+    match_count: usize,
+    active_match: Option<usize>,
+    search_input: Option<Entity<InputField>>,
+    search_task: Option<Task<()>>,
+    _search_subscription: Option<Subscription>,
+    _poll_task: Task<()>,
+    focus_handle: FocusHandle,
+}
+
+impl PolecatPane {
+    pub fn new(agent: Agent, tmux: GtCli, cx: &mut Context<Self>) -> Self {
+        let terminal = TerminalBuilder::new_display_only(
+            CursorShape::default(),
+            AlternateScroll::On,
+            Some(SCROLLBACK_LINES),
+            0,
+        )
+        .map(|builder| cx.new(|cx| builder.subscribe(cx)))
+        .map_err(|err| err.to_string());
+
+        let name = agent.name.clone();
+        let reader = Arc::new(Mutex::new(OutputReader::for_agent(agent, tmux)));
+        let poll_task = cx.spawn(async move |this, cx| {
+            loop {
+                let reader = reader.clone();
+                let (chunk, source) = cx
+                    .background_spawn(async move {
+                        let mut reader = reader.lock();
+                        let chunk = reader.read();
+                        (chunk, reader.source().cloned())
+                    })
+                    .await;
+                if this
+                    .update(cx, |this, cx| {
+                        if this.source != source {
+                            this.source = source;
+                            cx.notify();
+                        }
+                        this.apply_output(chunk, cx)
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+                cx.background_executor().timer(OUTPUT_POLL_INTERVAL).await;
+            }
+        });
+
+        Self {
+            agent: name,
+            source: None,
+            terminal,
+            terminal_view: None,
+            error: None,
+            follow: true,
+            query: String::new(),
+            match_count: 0,
+            active_match: None,
+            search_input: None,
+            search_task: None,
+            _search_subscription: None,
+            _poll_task: poll_task,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    pub fn agent(&self) -> &str {
+        &self.agent
+    }
+
+    pub fn source(&self) -> Option<&OutputSource> {
+        self.source.as_ref()
+    }
+
+    pub fn terminal(&self) -> Option<&Entity<Terminal>> {
+        self.terminal.as_ref().ok()
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn is_following(&self) -> bool {
+        self.follow
+    }
+
+    /// Follows new output, jumping to it right away when turned on
+    pub fn set_follow(&mut self, follow: bool, cx: &mut Context<Self>) {
+        self.follow = follow;
+        if follow && let Some(terminal) = self.terminal().cloned() {
+            terminal.update(cx, |terminal, _| terminal.scroll_to_bottom());
+        }
+        cx.notify();
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.match_count
+    }
+
+    pub fn active_match(&self) -> Option<usize> {
+        self.active_match
+    }
+
+    /// Highlights every occurrence of `query` in the output and scrollback, and
+    /// jumps to the newest one. Searching stops following.
+    pub fn search(&mut self, query: String, cx: &mut Context<Self>) {
+        self.query = query;
+        self.find_matches(true, cx);
+    }
+
+    /// Searches the output for the query. When `jump` is false the view and the
+    /// active match stay where they are, as far as the new matches allow.
+    fn find_matches(&mut self, jump: bool, cx: &mut Context<Self>) {
+        if !jump && self.query.is_empty() {
+            return;
+        }
+        let Some(terminal) = self.terminal().cloned() else {
+            return;
+        };
+        let searcher = match self.query.as_str() {
+            "" => None,
+            query => RegexSearch::new(&regex::escape(query)).ok(),
+        };
+        let Some(searcher) = searcher else {
+            terminal.update(cx, |terminal, _| terminal.matches.clear());
+            self.search_task = None;
+            self.match_count = 0;
+            self.active_match = None;
+            cx.notify();
+            return;
+        };
+
+        let find = terminal.update(cx, |terminal, cx| terminal.find_matches(searcher, cx));
+        self.search_task = Some(cx.spawn(async move |this, cx| {
+            let matches = find.await;
+            this.update(cx, |this, cx| {
+                let last = matches.len().checked_sub(1);
+                this.match_count = matches.len();
+                this.active_match = match this.active_match {
+                    Some(active) if !jump => last.map(|last| active.min(last)),
+                    _ => last,
+                };
+                terminal.update(cx, |terminal, _| terminal.matches = matches);
+                if jump && let Some(index) = this.active_match {
+                    this.activate_match(index, cx);
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Moves to the next match, or the previous one when `forward` is false
+    pub fn cycle_match(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let Some(active) = self.active_match else {
+            return;
+        };
+        let count = self.match_count;
+        let index = if forward {
+            (active + 1) % count
+        } else {
+            (active + count - 1) % count
+        };
+        self.activate_match(index, cx);
+    }
+
+    fn activate_match(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.active_match = Some(index);
+        self.follow = false;
+        if let Some(terminal) = self.terminal().cloned() {
+            terminal.update(cx, |terminal, _| terminal.activate_match(index));
+        }
+        cx.notify();
+    }
+
+    fn apply_output(&mut self, chunk: Result<OutputChunk, String>, cx: &mut Context<Self>) {
+        let chunk = match chunk {
+            Ok(chunk) => {
+                self.error = None;
+                chunk
+            }
+            Err(err) => {
+                self.error = Some(err);
+                cx.notify();
+                return;
+            }
+        };
+        let Some(terminal) = self.terminal().cloned() else {
+            return;
+        };
+        let output = match chunk {
+            OutputChunk::Unchanged => return,
+            OutputChunk::Append(bytes) => bytes,
+            // Moves to the start of the first redrawn line and erases from there.
+            // Lines are counted as rows, so this assumes the pane is as wide as
+            // the session's.
+            OutputChunk::Rewrite { erase, bytes } => {
+                let mut output = format!("\x1b[{}F\x1b[J", erase).into_bytes();
+                output.extend(bytes);
+                output
+            }
+            OutputChunk::Replace(bytes) => {
+                let mut output = CLEAR_TERMINAL.to_vec();
+                output.extend(bytes);
+                output
+            }
+        };
+        let follow = self.follow;
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(&output, cx);
+            if follow {
+                terminal.scroll_to_bottom();
+            }
+        });
+        // Matches are grid positions, which the new output moved or overwrote
+        self.find_matches(false, cx);
+        cx.notify();
+    }
+
+    fn search_input(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<InputField> {
+        if let Some(input) = &self.search_input {
+            return input.clone();
+        }
+        let input = cx.new(|cx| InputField::new(window, cx, "Search output…"));
+        let editor = input.read(cx).editor().clone();
+        self._search_subscription = Some(cx.subscribe(
+            &editor,
+            |this, editor, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    let query = editor.read(cx).text(cx);
+                    this.search(query, cx);
+                }
+            },
+        ));
+        self.search_input = Some(input.clone());
+        input
+    }
+
+    fn terminal_view(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<TerminalView>> {
+        let terminal = self.terminal().cloned()?;
+        Some(
+            self.terminal_view
+                .get_or_insert_with(|| {
+                    cx.new(|cx| {
+                        TerminalView::new(
+                            terminal,
+                            WeakEntity::new_invalid(),
+                            None,
+                            WeakEntity::new_invalid(),
+                            window,
+                            cx,
+                        )
+                    })
+                })
+                .clone(),
+        )
+    }
+}
+
+impl EventEmitter<PolecatPaneEvent> for PolecatPane {}
+
+impl Focusable for PolecatPane {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for PolecatPane {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let search_input = self.search_input(window, cx);
+        let terminal_view = self.terminal_view(window, cx);
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+        let has_matches = self.match_count > 0;
+        let match_label = match self.active_match {
+            Some(index) => format!("{}/{}", index + 1, self.match_count),
+            None if self.query.is_empty() => String::new(),
+            None => "No matches".into(),
+        };
+
+        div()
+            .id(SharedString::from(format!("polecat-pane-{}", self.agent)))
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .min_h(px(120.0))
+            .border_1()
+            .border_color(colors.border)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .px(px(8.0))
+                    .py(px(4.0))
+                    .bg(colors.title_bar_background)
+                    .border_b_1()
+                    .border_color(colors.border_variant)
+                    .child(div().text_color(colors.text).child(self.agent.clone()))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(colors.text_muted)
+                            .children(self.source.as_ref().map(OutputSource::describe)),
+                    )
+                    .child(div().flex_1())
+                    .child(div().w(px(160.0)).child(search_input))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(colors.text_muted)
+                            .child(match_label),
+                    )
+                    .child(
+                        Button::new("previous-match", "↑")
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .disabled(!has_matches)
+                            .on_click(cx.listener(|this, _, _, cx| this.cycle_match(false, cx))),
+                    )
+                    .child(
+                        Button::new("next-match", "↓")
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .disabled(!has_matches)
+                            .on_click(cx.listener(|this, _, _, cx| this.cycle_match(true, cx))),
+                    )
+                    .child(
+                        Button::new("follow", "Follow")
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .toggle_state(self.follow)
+                            .on_click(
+                                cx.listener(|this, _, _, cx| this.set_follow(!this.follow, cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("close-pane", "Close")
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|_, _, _, cx| cx.emit(PolecatPaneEvent::Close))),
+                    ),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .px(px(8.0))
+                        .py(px(2.0))
+                        .text_xs()
+                        .text_color(status.error)
+                        .child(error),
+                )
+            })
+            .child(
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .bg(colors.terminal_background)
+                    .map(|this| match (&self.terminal, terminal_view) {
+                        (_, Some(view)) => this.child(view),
+                        (Err(err), None) => this
+                            .p(px(8.0))
+                            .text_color(status.error)
+                            .child(format!("Couldn't create a terminal: {}", err)),
+                        (Ok(_), None) => this,
+                    }),
+            )
+    }
+}
+
+/// Center pane item showing the live session output of several polecats side by
+/// side in a grid
+pub struct PolecatOutput {
+    model: Entity<TownModel>,
+    /// Runs `tmux` for the panes
+    tmux: GtCli,
+    panes: Vec<Entity<PolecatPane>>,
+    _pane_subscriptions: Vec<Subscription>,
+    focus_handle: FocusHandle,
+}
+
+impl PolecatOutput {
+    pub fn new(model: Entity<TownModel>, tmux: GtCli, cx: &mut Context<Self>) -> Self {
+        Self {
+            model,
+            tmux,
+            panes: Vec::new(),
+            _pane_subscriptions: Vec::new(),
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    pub fn panes(&self) -> &[Entity<PolecatPane>] {
+        &self.panes
+    }
+
+    /// Agents being watched, in grid order
+    pub fn agents(&self, cx: &App) -> Vec<String> {
+        self.panes
+            .iter()
+            .map(|pane| pane.read(cx).agent().to_string())
+            .collect()
+    }
+
+    /// Polecats in the town that aren't watched yet
+    pub fn unwatched_polecats(&self, cx: &App) -> Vec<String> {
+        let watched = self.agents(cx);
+        self.model
+            .read(cx)
+            .agents()
+            .filter(|agent| agent.role == AgentRole::Polecat)
+            .map(|agent| agent.name.clone())
+            .filter(|name| !watched.contains(name))
+            .collect()
+    }
+
+    /// Adds a pane streaming `agent`'s output, unless it is already watched
+    pub fn watch(&mut self, agent: String, cx: &mut Context<Self>) {
+        if self.agents(cx).contains(&agent) {
+            return;
+        }
+        let Some(agent) = self.model.read(cx).agent(&agent).cloned() else {
+            log::warn!("Can't watch {}: no such agent", agent);
+            return;
+        };
+        let tmux = self.tmux.clone();
+        let pane = cx.new(|cx| PolecatPane::new(agent, tmux, cx));
+        self._pane_subscriptions.push(cx.subscribe(
+            &pane,
+            |this, pane, event: &PolecatPaneEvent, cx| match event {
+                PolecatPaneEvent::Close => this.unwatch(&pane, cx),
+            },
+        ));
+        self.panes.push(pane);
+        cx.emit(TownItemEvent::UpdateTab);
+        cx.notify();
+    }
+
+    pub fn unwatch(&mut self, pane: &Entity<PolecatPane>, cx: &mut Context<Self>) {
+        if let Some(index) = self.panes.iter().position(|p| p == pane) {
+            self.panes.remove(index);
+            self._pane_subscriptions.remove(index);
+            cx.emit(TownItemEvent::UpdateTab);
+            cx.notify();
+        }
+    }
+
+    fn render_add_menu(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let polecats = self.unwatched_polecats(cx);
+        let disabled = polecats.is_empty();
+        let view = cx.entity().downgrade();
+        let menu = ContextMenu::build(window, cx, move |mut menu, _, _| {
+            for polecat in polecats {
+                let view = view.clone();
+                menu = menu.entry(polecat.clone(), None, move |_, cx| {
+                    view.update(cx, |view, cx| view.watch(polecat.clone(), cx))
+                        .ok();
+                });
+            }
+            menu
+        });
+        DropdownMenu::new("watch-polecat", "Watch Polecat", menu).disabled(disabled)
+    }
+}
+
+impl EventEmitter<TownItemEvent> for PolecatOutput {}
+
+impl Focusable for PolecatOutput {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TownItem for PolecatOutput {
+    type Event = TownItemEvent;
+
+    fn tab_content_text(&self, _cx: &App) -> SharedString {
+        match self.panes.len() {
+            0 | 1 => "Polecat Output".into(),
+            count => format!("Polecat Output ({})", count).into(),
+        }
+    }
+
+    fn to_town_item_events(event: &Self::Event, mut f: impl FnMut(TownItemEvent)) {
+        f(*event);
+    }
+}
+
+impl Render for PolecatOutput {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let add_menu = self.render_add_menu(window, cx);
+        let colors = cx.theme().colors();
+        let (columns, rows) = grid_shape(self.panes.len());
+
+        div()
+            .id("polecat-output")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .text_sm()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .p(px(8.0))
+                    .border_b_1()
+                    .border_color(colors.border_variant)
+                    .child(add_menu)
+                    .child(div().flex_1())
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(colors.text_muted)
+                            .child(format!("{} watched", self.panes.len())),
+                    ),
+            )
+            .map(|this| {
+                if self.panes.is_empty() {
+                    this.child(
+                        div()
+                            .p(px(12.0))
+                            .text_color(colors.text_muted)
+                            .child("Pick a polecat to stream its session output"),
+                    )
+                } else {
+                    this.child(
+                        div()
+                            .flex_1()
+                            .min_h_0()
+                            .grid()
+                            .grid_cols(columns as u16)
+                            .grid_rows(rows as u16)
+                            .gap(px(4.0))
+                            .p(px(4.0))
+                            .children(self.panes.iter().cloned()),
+                    )
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard_buffer::AgentStatus;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    fn polecat(path: Option<PathBuf>) -> Agent {
+        Agent {
+            name: "frontend/polecats/toast".into(),
+            role: AgentRole::Polecat,
+            rig: Some("frontend".into()),
+            instance_id: Some("toast".into()),
+            path,
+            status: AgentStatus::Active,
+            token_usage: None,
+            context_fill: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_capture() {
+        let previous = lines(&["a", "b", "c"]);
+        assert_eq!(diff_capture(&previous, &previous), OutputChunk::Unchanged);
+        assert_eq!(
+            diff_capture(&[], &previous),
+            OutputChunk::Replace(b"a\nb\nc\n".to_vec())
+        );
+        // New lines below the previous capture
+        assert_eq!(
+            diff_capture(&previous, &lines(&["a", "b", "c", "d"])),
+            OutputChunk::Append(b"d\n".to_vec())
+        );
+        // History is full, so the oldest line scrolled off
+        assert_eq!(
+            diff_capture(&previous, &lines(&["b", "c", "d", "e"])),
+            OutputChunk::Append(b"d\ne\n".to_vec())
+        );
+        // Nothing in common with the previous capture
+        assert_eq!(
+            diff_capture(&previous, &lines(&["x", "y"])),
+            OutputChunk::Replace(b"x\ny\n".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_diff_capture_redrawn_lines() {
+        let previous = lines(&["$ claude", "Reading src/session.rs", "✻ Thinking… (1s)"]);
+        // A spinner redraws the last line in place
+        assert_eq!(
+            diff_capture(
+                &previous,
+                &lines(&["$ claude", "Reading src/session.rs", "✽ Thinking… (2s)"])
+            ),
+            OutputChunk::Rewrite {
+                erase: 1,
+                bytes: "✽ Thinking… (2s)\n".as_bytes().to_vec(),
+            }
+        );
+        // Output printed above the redrawn line
+        assert_eq!(
+            diff_capture(
+                &previous,
+                &lines(&[
+                    "$ claude",
+                    "Reading src/session.rs",
+                    "Editing src/session.rs",
+                    "✽ Thinking… (2s)",
+                ])
+            ),
+            OutputChunk::Rewrite {
+                erase: 1,
+                bytes: "Editing src/session.rs\n✽ Thinking… (2s)\n"
+                    .as_bytes()
+                    .to_vec(),
+            }
+        );
+        // History is full, so the oldest line scrolled off as well
+        assert_eq!(
+            diff_capture(
+                &previous,
+                &lines(&["Reading src/session.rs", "Done", "✽ Thinking… (2s)"])
+            ),
+            OutputChunk::Rewrite {
+                erase: 1,
+                bytes: "Done\n✽ Thinking… (2s)\n".as_bytes().to_vec(),
+            }
+        );
+
+        // A redraw reaching past the lines the cursor can move up to
+        let mut long = lines(&["$ claude"]);
+        long.extend((0..=MAX_REWRITE_LINES).map(|line| line.to_string()));
+        let redrawn = lines(&["$ claude", "Compacting…"]);
+        assert_eq!(
+            diff_capture(&long, &redrawn),
+            OutputChunk::Replace("$ claude\nCompacting…\n".as_bytes().to_vec())
+        );
+    }
+
+    #[test]
+    fn test_grid_shape() {
+        assert_eq!(grid_shape(0), (0, 0));
+        assert_eq!(grid_shape(1), (1, 1));
+        assert_eq!(grid_shape(2), (2, 1));
+        assert_eq!(grid_shape(4), (2, 2));
+        assert_eq!(grid_shape(5), (3, 2));
+        assert_eq!(grid_shape(9), (3, 3));
+    }
+
+    #[test]
+    fn test_output_source_prefers_log_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            OutputSource::for_agent(&polecat(Some(dir.path().into()))),
+            OutputSource::Tmux("gt-frontend-polecats-toast".into())
+        );
+        let logs = dir.path().join("logs");
+        std::fs::create_dir_all(&logs).unwrap();
+        std::fs::write(logs.join("session.log"), "").unwrap();
+        assert_eq!(
+            OutputSource::for_agent(&polecat(Some(dir.path().into()))),
+            OutputSource::LogFile(logs.join("session.log"))
+        );
+    }
+
+    #[test]
+    fn test_tail_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("session.log");
+        std::fs::write(&log, "\x1b[32mready\x1b[0m\n").unwrap();
+        let mut reader = OutputReader::new(OutputSource::LogFile(log.clone()), GtCli::default());
+
+        assert_eq!(
+            reader.read(),
+            Ok(OutputChunk::Replace(b"\x1b[32mready\x1b[0m\n".to_vec()))
+        );
+        assert_eq!(reader.read(), Ok(OutputChunk::Unchanged));
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&log).unwrap();
+        std::io::Write::write_all(&mut file, b"working on fe-12\n").unwrap();
+        assert_eq!(
+            reader.read(),
+            Ok(OutputChunk::Append(b"working on fe-12\n".to_vec()))
+        );
+
+        // The runtime restarted and truncated its log
+        std::fs::write(&log, "restarted\n").unwrap();
+        assert_eq!(
+            reader.read(),
+            Ok(OutputChunk::Replace(b"restarted\n".to_vec()))
+        );
+    }
+
+    #[test]
+    fn test_reader_follows_rotated_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = dir.path().join("logs");
+        std::fs::create_dir_all(&logs).unwrap();
+        let first = logs.join("session-1.log");
+        std::fs::write(&first, "first session\n").unwrap();
+        let mut reader =
+            OutputReader::for_agent(polecat(Some(dir.path().into())), GtCli::default());
+        assert_eq!(reader.source(), None);
+
+        assert_eq!(
+            reader.read(),
+            Ok(OutputChunk::Replace(b"first session\n".to_vec()))
+        );
+        assert_eq!(reader.source(), Some(&OutputSource::LogFile(first.clone())));
+
+        // The runtime rotated its log; the old one stops growing
+        let second = logs.join("session-2.log");
+        std::fs::write(&second, "second session\n").unwrap();
+        let modified = std::fs::metadata(&first).unwrap().modified().unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&second)
+            .unwrap()
+            .set_modified(modified + Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(
+            reader.read(),
+            Ok(OutputChunk::Replace(b"second session\n".to_vec()))
+        );
+        assert_eq!(reader.source(), Some(&OutputSource::LogFile(second)));
+        assert_eq!(reader.read(), Ok(OutputChunk::Unchanged));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capture_tmux_pane() {
+        use crate::fake_gt::FakeGt;
+
+        let args = "capture-pane -p -e -J -t gt-frontend-polecats-toast -S -2000";
+        let tmux = FakeGt::new().respond(args, "$ claude\nThinking…\n\n\n");
+        let mut reader = OutputReader::new(
+            OutputSource::Tmux("gt-frontend-polecats-toast".into()),
+            tmux.cli(),
+        );
+        assert_eq!(
+            reader.read(),
+            Ok(OutputChunk::Replace(
+                "$ claude\nThinking…\n".as_bytes().to_vec()
+            ))
+        );
+        assert_eq!(reader.read(), Ok(OutputChunk::Unchanged));
+
+        let missing = FakeGt::new().fail(args, 1, "can't find session: gt-frontend-polecats-toast");
+        let mut reader = OutputReader::new(
+            OutputSource::Tmux("gt-frontend-polecats-toast".into()),
+            missing.cli(),
+        );
+        assert!(reader.read().unwrap_err().contains("can't find session"));
+    }
+}
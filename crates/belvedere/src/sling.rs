@@ -0,0 +1,105 @@
+use crate::gt_cli::{GtCli, GtCommandError};
+
+/// Agent runtimes offered when slinging, besides the rig's default
+pub const RUNTIMES: &[&str] = &["claude", "codex", "gemini", "cursor", "amp"];
+
+/// An issue to assign with `gt sling`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlingRequest {
+    pub issue: String,
+    /// A rig, or an agent address within one
+    pub target: String,
+    /// Runtime override passed as `--agent`; the rig's default when `None`
+    pub runtime: Option<String>,
+}
+
+impl SlingRequest {
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec!["sling".to_string(), self.issue.clone(), self.target.clone()];
+        if let Some(runtime) = &self.runtime {
+            args.push("--agent".into());
+            args.push(runtime.clone());
+        }
+        args
+    }
+
+    /// Runs `gt sling <issue> <target> [--agent <runtime>]`
+    pub fn run(&self, gt: &GtCli) -> Result<(), GtCommandError> {
+        gt.run(self.args()).map(|_| ())
+    }
+}
+
+/// Progress of the last sling onto a rig or agent, shown under it in the tree
+#[derive(Clone, Debug, PartialEq)]
+pub enum SlingStatus {
+    Running(SlingRequest),
+    Succeeded(SlingRequest),
+    Failed(SlingRequest, String),
+}
+
+impl SlingStatus {
+    pub fn request(&self) -> &SlingRequest {
+        match self {
+            SlingStatus::Running(request)
+            | SlingStatus::Succeeded(request)
+            | SlingStatus::Failed(request, _) => request,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            SlingStatus::Running(request) => format!("Slinging {}…", request.issue),
+            SlingStatus::Succeeded(request) => match &request.runtime {
+                Some(runtime) => format!("Slung {} ({})", request.issue, runtime),
+                None => format!("Slung {}", request.issue),
+            },
+            SlingStatus::Failed(request, error) => {
+                format!("Couldn't sling {}: {}", request.issue, error)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(runtime: Option<&str>) -> SlingRequest {
+        SlingRequest {
+            issue: "fe-12".into(),
+            target: "frontend".into(),
+            runtime: runtime.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn test_sling_args() {
+        assert_eq!(request(None).args(), vec!["sling", "fe-12", "frontend"]);
+        assert_eq!(
+            request(Some("codex")).args(),
+            vec!["sling", "fe-12", "frontend", "--agent", "codex"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sling_runs_gt() {
+        use crate::fake_gt::FakeGt;
+
+        let gt = FakeGt::new()
+            .respond(
+                "sling fe-12 frontend --agent gemini",
+                "Slung fe-12 to frontend",
+            )
+            .fail("sling fe-12 frontend", 1, "issue fe-12 is already hooked");
+
+        assert!(request(Some("gemini")).run(&gt.cli()).is_ok());
+        let error = request(None).run(&gt.cli()).unwrap_err();
+        let status = SlingStatus::Failed(request(None), error.to_string());
+        assert_eq!(
+            status.message(),
+            "Couldn't sling fe-12: `gt sling fe-12 frontend` exited with status 1: \
+             issue fe-12 is already hooked"
+        );
+    }
+}
@@ -0,0 +1,44 @@
+//! Git repositories for tests, isolated from the user's and the system's config.
+//!
+//! Tests run in parallel in one process, so nothing here touches the process
+//! environment. Each `git` command gets its own environment, and each repository
+//! pins the config commits and merges depend on, which also covers git run by the
+//! code under test.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Config written into every test repository; worktrees share it
+const REPOSITORY_CONFIG: &[(&str, &str)] = &[
+    ("user.name", "Toast"),
+    ("user.email", "toast@example.com"),
+    ("commit.gpgsign", "false"),
+];
+
+const NULL_DEVICE: &str = if cfg!(windows) { "NUL" } else { "/dev/null" };
+
+/// Runs git in `dir` without global or system config, panicking if it fails
+pub fn run_git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .current_dir(dir)
+        .env("GIT_CONFIG_GLOBAL", NULL_DEVICE)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// Creates a repository at `path` with `files` committed on `main`
+pub fn init_repository(path: &Path, files: &[(&str, &str)]) {
+    std::fs::create_dir_all(path).unwrap();
+    run_git(path, &["init", "-q", "-b", "main"]);
+    for (key, value) in REPOSITORY_CONFIG {
+        run_git(path, &["config", key, value]);
+    }
+    for (name, text) in files {
+        std::fs::write(path.join(name), text).unwrap();
+    }
+    run_git(path, &["add", "-A"]);
+    run_git(path, &["commit", "-qm", "Initial"]);
+}
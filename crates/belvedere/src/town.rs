@@ -1,8 +1,42 @@
-use collections::HashMap;
-use gpui::{AnyView, Context, Entity, FocusHandle, Focusable, Render, Window, div, prelude::*};
+use agent_servers::ClaudeCode;
+use fs::Fs;
+use gpui::{
+    AnyView, App, Context, Entity, FocusHandle, Focusable, Render, Subscription, Task, Window, div,
+    prelude::*,
+};
+use project::Project;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 use title_bar::platform_title_bar::PlatformTitleBar;
 use ui::ActiveTheme;
 
+use crate::agent_inspector::{AgentInspector, AgentInspectorEvent};
+use crate::agent_terminal::{AgentTerminal, AttachState};
+use crate::composer::{Composer, Recipient};
+use crate::convoy_editor::{ConvoyEditor, ConvoyEditorEvent};
+use crate::convoy_view::{ConvoyView, ConvoyViewEvent};
+use crate::dashboard_buffer::{
+    DashboardDataSource, DashboardError, DashboardEvent, DashboardView, REFRESH_DEBOUNCE,
+    watch_for_changes,
+};
+use crate::formula_view::{FormulaView, FormulaViewEvent};
+use crate::fs_data_source::FsDataSource;
+use crate::gt_cli::GtCli;
+use crate::hook_conflicts::{
+    CONFLICT_ANALYSIS_INTERVAL, HookDiffCache, active_hooks, analyze_hooks,
+};
+use crate::hook_lifecycle::{HookLifecycleEvent, HookLifecycleView};
+use crate::hook_review::{HookReview, HookReviewEvent};
+use crate::kanban_board::{BoardScope, KanbanBoard};
+use crate::mail_view::MailView;
+use crate::mayor_chat::MayorChat;
+use crate::node_detail::NodeDetail;
+use crate::polecat_output::PolecatOutput;
+use crate::sling::{SlingRequest, SlingStatus};
+use crate::town_model::{TownModel, TownModelEvent};
+use crate::town_tree::{RigGroup, TownNode, TownTree, TownTreeEvent};
+
 /// Holds the tabbed items in the center pane
 struct CenterPane {
     /// List of open items (as views)
@@ -44,6 +78,10 @@ impl CenterPane {
         }
     }
 
+    fn position(&self, predicate: impl Fn(&AnyView) -> bool) -> Option<usize> {
+        self.items.iter().position(predicate)
+    }
+
     fn set_active(&mut self, index: usize) {
         if index < self.items.len() {
             self.active_index = index;
@@ -59,14 +97,53 @@ pub struct Town {
     /// Path to the ~/gt/ directory
     pub path: std::path::PathBuf,
 
-    /// Collection of rig directories
-    pub rigs: HashMap<String, ()>,
+    /// Rigs, agents, crew, hooks and convoys of this town
+    model: Entity<TownModel>,
+
+    /// Reads the town directory, caching beads stores between scans
+    structure_source: Arc<FsDataSource>,
+
+    /// In-flight scan of the town directory
+    structure_task: Option<Task<()>>,
+
+    /// Watches the town's directories, set with `watch`
+    fs: Option<Arc<dyn Fs>>,
+
+    /// Directories whose changes rescan the town, as of the last scan
+    structure_watch_paths: Vec<std::path::PathBuf>,
+
+    /// Streams changes under `structure_watch_paths`
+    structure_watch_task: Option<Task<()>>,
+
+    /// Waits for a burst of file changes to settle before rescanning
+    structure_debounce_task: Option<Task<()>>,
 
-    /// Discovered agent instances
-    pub agents: HashMap<String, ()>,
+    /// In-flight comparison of the hooks' changes
+    conflicts_task: Option<Task<()>>,
 
-    /// Multi-agent coordination groups
-    pub convoys: HashMap<String, ()>,
+    /// Each hook's changes as of the last comparison, so unchanged hooks aren't read again
+    hook_diffs: HookDiffCache,
+
+    /// Compares the hooks again every `CONFLICT_ANALYSIS_INTERVAL`
+    _conflicts_poll_task: Task<()>,
+
+    /// Runs `gt` commands in this town
+    gt: GtCli,
+
+    /// Runs `bd` commands, e.g. to pour formulas
+    bd: GtCli,
+
+    /// Runs `tmux`, to check, capture and attach to agents' sessions
+    tmux: GtCli,
+
+    /// Project agent sessions like the Mayor chat run in, when the app provides one
+    agent_project: Option<Entity<Project>>,
+
+    /// Town hierarchy shown in the left dock
+    town_tree: Entity<TownTree>,
+
+    /// Inspector for the selected agent, shown in the right dock
+    agent_inspector: Entity<AgentInspector>,
 
     /// Center pane holding tabbed items
     center_pane: CenterPane,
@@ -76,20 +153,213 @@ pub struct Town {
 
     /// Focus handle for keyboard navigation
     pub focus_handle: FocusHandle,
+
+    _subscriptions: Vec<Subscription>,
 }
 
 impl Town {
     pub fn new(path: std::path::PathBuf, cx: &mut Context<Self>) -> Self {
         let platform_titlebar = cx.new(|cx| PlatformTitleBar::new("town-titlebar", cx));
+        let model = cx.new(|_| TownModel::new());
+        let town_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string_lossy().into_owned());
+        let town_tree = cx.new(|cx| TownTree::new(model.clone(), town_name, cx));
+        let gt = GtCli::default().town_path(&path);
+        let bd = GtCli::new("bd").town_path(&path);
+        let tmux = GtCli::new("tmux");
+        let agent_inspector = cx.new(|cx| AgentInspector::new(model.clone(), gt.clone(), cx));
+        let subscriptions = vec![
+            cx.subscribe(&town_tree, Self::handle_town_tree_event),
+            cx.subscribe(&model, Self::handle_model_event),
+            cx.subscribe(&agent_inspector, Self::handle_agent_inspector_event),
+        ];
 
-        Self {
+        let conflicts_poll_task = cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(CONFLICT_ANALYSIS_INTERVAL)
+                    .await;
+                if this
+                    .update(cx, |this, cx| this.analyze_conflicts(cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        let mut town = Self {
+            structure_source: Arc::new(FsDataSource::new(path.clone())),
             path,
-            rigs: HashMap::default(),
-            agents: HashMap::default(),
-            convoys: HashMap::default(),
+            model,
+            structure_task: None,
+            fs: None,
+            structure_watch_paths: Vec::new(),
+            structure_watch_task: None,
+            structure_debounce_task: None,
+            conflicts_task: None,
+            hook_diffs: HookDiffCache::default(),
+            _conflicts_poll_task: conflicts_poll_task,
+            gt,
+            bd,
+            tmux,
+            agent_project: None,
+            town_tree,
+            agent_inspector,
             center_pane: CenterPane::new(),
             platform_titlebar,
             focus_handle: cx.focus_handle(),
+            _subscriptions: subscriptions,
+        };
+        town.reload_structure(cx);
+        town
+    }
+
+    /// The typed model of this town, shared by every view that shows part of it
+    pub fn model(&self) -> &Entity<TownModel> {
+        &self.model
+    }
+
+    pub fn town_tree(&self) -> &Entity<TownTree> {
+        &self.town_tree
+    }
+
+    pub fn agent_inspector(&self) -> &Entity<AgentInspector> {
+        &self.agent_inspector
+    }
+
+    pub fn gt(&self) -> &GtCli {
+        &self.gt
+    }
+
+    /// Replaces the `gt` runner, e.g. to use a binary outside `PATH`
+    pub fn set_gt_cli(&mut self, gt: GtCli, cx: &mut Context<Self>) {
+        self.gt = gt.clone();
+        self.agent_inspector
+            .update(cx, |inspector, _| inspector.set_gt_cli(gt));
+    }
+
+    pub fn bd(&self) -> &GtCli {
+        &self.bd
+    }
+
+    /// Replaces the `bd` runner used by views opened after this call
+    pub fn set_bd_cli(&mut self, bd: GtCli) {
+        self.bd = bd;
+    }
+
+    pub fn tmux(&self) -> &GtCli {
+        &self.tmux
+    }
+
+    /// Replaces the `tmux` runner used by views opened after this call
+    pub fn set_tmux_cli(&mut self, tmux: GtCli) {
+        self.tmux = tmux;
+    }
+
+    /// Sets the project agent sessions are started in, enabling the Mayor chat
+    pub fn set_agent_project(&mut self, project: Entity<Project>) {
+        self.agent_project = Some(project);
+    }
+
+    /// Rescans the town directory in the background and updates the model
+    pub fn reload_structure(&mut self, cx: &mut Context<Self>) {
+        let source = self.structure_source.clone();
+        let scan =
+            cx.background_spawn(async move { (source.read_structure(), source.watch_paths()) });
+
+        self.structure_task = Some(cx.spawn(async move |this, cx| {
+            let (structure, watch_paths) = scan.await;
+            this.update(cx, |this, cx| {
+                match structure {
+                    Ok(structure) => this
+                        .model
+                        .update(cx, |model, cx| model.apply_structure(structure, cx)),
+                    Err(DashboardError::NotAvailable) => {}
+                    Err(err) => log::error!("failed to read town structure: {}", err),
+                }
+                if watch_paths != this.structure_watch_paths {
+                    this.structure_watch_paths = watch_paths;
+                    this.restart_structure_watcher(cx);
+                }
+            })
+            .ok();
+        }));
+    }
+
+    /// Rescans the town whenever files under its directories change. Without a
+    /// watch the town is only rescanned on request.
+    pub fn watch(&mut self, fs: Arc<dyn Fs>, cx: &mut Context<Self>) {
+        self.fs = Some(fs);
+        self.restart_structure_watcher(cx);
+    }
+
+    fn restart_structure_watcher(&mut self, cx: &mut Context<Self>) {
+        let Some(fs) = self.fs.clone() else {
+            return;
+        };
+        if self.structure_watch_paths.is_empty() {
+            self.structure_watch_task = None;
+            return;
+        }
+
+        let paths = self.structure_watch_paths.clone();
+        self.structure_watch_task = Some(cx.spawn(async move |this, cx| {
+            watch_for_changes(fs, paths, || {
+                this.update(cx, |this, cx| this.schedule_structure_reload(cx))
+            })
+            .await;
+        }));
+    }
+
+    /// Rescans after `REFRESH_DEBOUNCE`, coalescing bursts of change notifications
+    fn schedule_structure_reload(&mut self, cx: &mut Context<Self>) {
+        self.structure_debounce_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            this.update(cx, |this, cx| this.reload_structure(cx)).ok();
+        }));
+    }
+
+    /// Compares the changes of every active hook in the background and stores the
+    /// conflicts in the model. Only hooks with new commits since the last comparison
+    /// are read again.
+    pub fn analyze_conflicts(&mut self, cx: &mut Context<Self>) {
+        let hooks = active_hooks(self.model.read(cx));
+        let executor = cx.background_executor().clone();
+        let analysis = cx.background_spawn(analyze_hooks(hooks, self.hook_diffs.clone(), executor));
+
+        self.conflicts_task = Some(cx.spawn(async move |this, cx| {
+            let (report, hook_diffs) = analysis.await;
+            this.update(cx, |this, cx| {
+                this.hook_diffs = hook_diffs;
+                this.model
+                    .update(cx, |model, cx| model.set_conflicts(report, cx));
+            })
+            .ok();
+        }));
+    }
+
+    fn handle_model_event(
+        &mut self,
+        model: Entity<TownModel>,
+        event: &TownModelEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            TownModelEvent::HooksChanged => self.analyze_conflicts(cx),
+            TownModelEvent::ConflictsChanged => {
+                let conflicts = model.read(cx).conflicts().clone();
+                for item in self.center_pane.items.clone() {
+                    if let Ok(dashboard) = item.downcast::<DashboardView>() {
+                        dashboard.update(cx, |dashboard, cx| {
+                            dashboard.set_conflicts(conflicts.clone(), cx)
+                        });
+                    }
+                }
+            }
+            _ => {}
         }
     }
 
@@ -99,6 +369,451 @@ impl Town {
         cx.notify();
     }
 
+    /// Opens the detail item for a town tree node, or activates it if already open
+    pub fn open_node(&mut self, node: TownNode, cx: &mut Context<Self>) {
+        match node {
+            TownNode::Formula(formula_id) => return self.open_formula(formula_id, cx),
+            TownNode::Group {
+                group: RigGroup::Hooks,
+                ..
+            } => return self.open_hooks(cx),
+            _ => {}
+        }
+
+        let app: &App = cx;
+        let existing = self.center_pane.position(|view| {
+            view.clone()
+                .downcast::<NodeDetail>()
+                .is_ok_and(|detail| detail.read(app).node() == &node)
+        });
+        if let Some(index) = existing {
+            self.set_active_item(index, cx);
+            return;
+        }
+
+        let model = self.model.clone();
+        let detail = cx.new(|cx| NodeDetail::new(node, model, cx));
+        self.open_item(detail.into(), cx);
+    }
+
+    /// Opens the detail view for a convoy, reusing an open one if there is one
+    pub fn open_convoy(&mut self, convoy_id: String, cx: &mut Context<Self>) {
+        let app: &App = cx;
+        let existing = self.center_pane.position(|view| {
+            view.clone()
+                .downcast::<ConvoyView>()
+                .is_ok_and(|convoy| convoy.read(app).convoy_id() == convoy_id)
+        });
+        if let Some(index) = existing {
+            self.set_active_item(index, cx);
+            return;
+        }
+
+        let model = self.model.clone();
+        let convoy = cx.new(|cx| ConvoyView::new(convoy_id, model, cx));
+        cx.subscribe(
+            &convoy,
+            |this, _, event: &ConvoyViewEvent, cx| match event {
+                ConvoyViewEvent::OpenBoard(id) => {
+                    this.open_board(BoardScope::Convoy(id.clone()), cx)
+                }
+                ConvoyViewEvent::Edit(id) => this.open_convoy_editor(Some(id.clone()), cx),
+            },
+        )
+        .detach();
+        self.open_item(convoy.into(), cx);
+    }
+
+    /// Opens the editor for a convoy, or for a new one when `convoy_id` is `None`,
+    /// reusing an open one
+    pub fn open_convoy_editor(&mut self, convoy_id: Option<String>, cx: &mut Context<Self>) {
+        let app: &App = cx;
+        let existing = self.center_pane.position(|view| {
+            view.clone()
+                .downcast::<ConvoyEditor>()
+                .is_ok_and(|editor| editor.read(app).convoy_id() == convoy_id.as_deref())
+        });
+        if let Some(index) = existing {
+            self.set_active_item(index, cx);
+            return;
+        }
+
+        let model = self.model.clone();
+        let gt = self.gt.clone();
+        let editor = cx.new(|cx| ConvoyEditor::new(convoy_id, model, gt, cx));
+        // Convoy progress comes from the dashboards and members from the town's beads
+        cx.subscribe(&editor, |this, _, _: &ConvoyEditorEvent, cx| {
+            this.refresh_dashboards(cx);
+            this.reload_structure(cx);
+        })
+        .detach();
+        self.open_item(editor.into(), cx);
+    }
+
+    /// Refreshes every open dashboard right away
+    pub fn refresh_dashboards(&mut self, cx: &mut Context<Self>) {
+        let dashboards: Vec<Entity<DashboardView>> = self
+            .center_pane
+            .items
+            .iter()
+            .filter_map(|view| view.clone().downcast::<DashboardView>().ok())
+            .collect();
+        for dashboard in dashboards {
+            dashboard.update(cx, |dashboard, cx| dashboard.refresh(cx));
+        }
+    }
+
+    /// Opens the kanban board for a rig's or convoy's issues, reusing an open one
+    pub fn open_board(&mut self, scope: BoardScope, cx: &mut Context<Self>) {
+        let app: &App = cx;
+        let existing = self.center_pane.position(|view| {
+            view.clone()
+                .downcast::<KanbanBoard>()
+                .is_ok_and(|board| board.read(app).scope() == &scope)
+        });
+        if let Some(index) = existing {
+            self.set_active_item(index, cx);
+            return;
+        }
+
+        let model = self.model.clone();
+        let board = cx.new(|cx| KanbanBoard::new(scope, model, cx));
+        self.open_item(board.into(), cx);
+    }
+
+    /// Opens the DAG view for a formula, reusing an open one if there is one
+    pub fn open_formula(&mut self, formula_id: String, cx: &mut Context<Self>) {
+        let app: &App = cx;
+        let existing = self.center_pane.position(|view| {
+            view.clone()
+                .downcast::<FormulaView>()
+                .is_ok_and(|formula| formula.read(app).formula_id() == formula_id)
+        });
+        if let Some(index) = existing {
+            self.set_active_item(index, cx);
+            return;
+        }
+
+        let model = self.model.clone();
+        let bd = self.bd.clone();
+        let formula = cx.new(|cx| FormulaView::new(formula_id, model, bd, cx));
+        cx.subscribe(
+            &formula,
+            |this, _, event: &FormulaViewEvent, cx| match event {
+                FormulaViewEvent::MoleculeCreated(_) | FormulaViewEvent::ProtoCooked(_) => {
+                    this.reload_structure(cx)
+                }
+            },
+        )
+        .detach();
+        self.open_item(formula.into(), cx);
+    }
+
+    /// Opens the hook lifecycle view, reusing an open one if there is one
+    pub fn open_hooks(&mut self, cx: &mut Context<Self>) {
+        let existing = self
+            .center_pane
+            .position(|view| view.clone().downcast::<HookLifecycleView>().is_ok());
+        if let Some(index) = existing {
+            self.set_active_item(index, cx);
+            return;
+        }
+
+        let model = self.model.clone();
+        let hooks = cx.new(|cx| HookLifecycleView::new(model, cx));
+        cx.subscribe(
+            &hooks,
+            |this, _, event: &HookLifecycleEvent, cx| match event {
+                HookLifecycleEvent::HookCreated(_) => this.reload_structure(cx),
+                HookLifecycleEvent::Review(hook_id) => this.open_review(hook_id.clone(), cx),
+            },
+        )
+        .detach();
+        self.open_item(hooks.into(), cx);
+    }
+
+    /// Opens the review of a hook's changes, reusing an open one if there is one
+    pub fn open_review(&mut self, hook_id: String, cx: &mut Context<Self>) {
+        let app: &App = cx;
+        let existing = self.center_pane.position(|view| {
+            view.clone()
+                .downcast::<HookReview>()
+                .is_ok_and(|review| review.read(app).hook_id() == hook_id)
+        });
+        if let Some(index) = existing {
+            self.set_active_item(index, cx);
+            return;
+        }
+
+        let model = self.model.clone();
+        let gt = self.gt.clone();
+        let project = self.agent_project.clone();
+        let review = cx.new(|cx| HookReview::new(hook_id, model, gt, project, cx));
+        // Merging or discarding changes the rig's hooks and branches
+        cx.subscribe(&review, |this, _, _: &HookReviewEvent, cx| {
+            this.reload_structure(cx)
+        })
+        .detach();
+        self.open_item(review.into(), cx);
+    }
+
+    /// Opens the mail viewer, reusing an open one, on `address`'s mailbox if given
+    pub fn open_mail(&mut self, address: Option<String>, cx: &mut Context<Self>) {
+        let existing = self
+            .center_pane
+            .items
+            .iter()
+            .enumerate()
+            .find_map(|(index, view)| {
+                view.clone()
+                    .downcast::<MailView>()
+                    .ok()
+                    .map(|mail| (index, mail))
+            });
+        if let Some((index, mail)) = existing {
+            if address.is_some() {
+                mail.update(cx, |mail, cx| mail.select_address(address, cx));
+            }
+            self.set_active_item(index, cx);
+            return;
+        }
+
+        let model = self.model.clone();
+        let gt = self.gt.clone();
+        let mail = cx.new(|cx| {
+            let mut mail = MailView::new(model, gt, cx);
+            mail.select_address(address, cx);
+            mail
+        });
+        self.open_item(mail.into(), cx);
+    }
+
+    /// Opens the composer addressed to `recipient`, reusing an open one
+    pub fn open_composer(&mut self, recipient: Recipient, cx: &mut Context<Self>) {
+        let existing = self
+            .center_pane
+            .items
+            .iter()
+            .enumerate()
+            .find_map(|(index, view)| {
+                view.clone()
+                    .downcast::<Composer>()
+                    .ok()
+                    .map(|composer| (index, composer))
+            });
+        if let Some((index, composer)) = existing {
+            composer.update(cx, |composer, cx| composer.set_recipient(recipient, cx));
+            self.set_active_item(index, cx);
+            return;
+        }
+
+        let model = self.model.clone();
+        let gt = self.gt.clone();
+        let composer = cx.new(|cx| Composer::new(recipient, model, gt, cx));
+        self.open_item(composer.into(), cx);
+    }
+
+    /// Opens the conversation with the Mayor, reusing an open one. The first one
+    /// starts a Claude Code session in the town directory.
+    pub fn open_mayor_chat(&mut self, cx: &mut Context<Self>) {
+        let existing = self
+            .center_pane
+            .position(|view| view.clone().downcast::<MayorChat>().is_ok());
+        if let Some(index) = existing {
+            self.set_active_item(index, cx);
+            return;
+        }
+
+        let Some(project) = self.agent_project.clone() else {
+            log::warn!("Can't talk to the Mayor: agent sessions aren't available");
+            return;
+        };
+        let path = self.path.clone();
+        let gt = self.gt.clone();
+        let chat = cx.new(|cx| MayorChat::new(project, Rc::new(ClaudeCode), path, gt, cx));
+        self.open_item(chat.into(), cx);
+    }
+
+    /// Streams an agent's session output into the polecat output grid, opening the
+    /// grid if it isn't open yet
+    pub fn watch_output(&mut self, agent: String, cx: &mut Context<Self>) {
+        let existing = self
+            .center_pane
+            .items
+            .iter()
+            .enumerate()
+            .find_map(|(index, view)| {
+                view.clone()
+                    .downcast::<PolecatOutput>()
+                    .ok()
+                    .map(|output| (index, output))
+            });
+        if let Some((index, output)) = existing {
+            output.update(cx, |output, cx| output.watch(agent, cx));
+            self.set_active_item(index, cx);
+            return;
+        }
+
+        let model = self.model.clone();
+        let tmux = self.tmux.clone();
+        let output = cx.new(|cx| {
+            let mut output = PolecatOutput::new(model, tmux, cx);
+            output.watch(agent, cx);
+            output
+        });
+        self.open_item(output.into(), cx);
+    }
+
+    /// Opens an interactive terminal attached to an agent's tmux session, reusing
+    /// one that is still attached
+    pub fn attach(&mut self, agent: String, cx: &mut Context<Self>) {
+        let existing = self.center_pane.position(|view| {
+            view.clone()
+                .downcast::<AgentTerminal>()
+                .is_ok_and(|terminal| {
+                    let terminal = terminal.read(cx);
+                    terminal.agent() == agent
+                        && matches!(
+                            terminal.state(),
+                            AttachState::Starting | AttachState::Attached(_)
+                        )
+                })
+        });
+        if let Some(index) = existing {
+            self.set_active_item(index, cx);
+            return;
+        }
+
+        let Some(session) = self
+            .model
+            .read(cx)
+            .agent(&agent)
+            .map(|agent| agent.tmux_session())
+        else {
+            log::warn!("Can't attach to {agent}: it isn't in the town");
+            return;
+        };
+        let model = self.model.clone();
+        let tmux = self.tmux.clone();
+        let terminal = cx.new(|cx| AgentTerminal::new(agent, session, tmux, model, cx));
+        self.open_item(terminal.into(), cx);
+    }
+
+    /// Assigns an issue dropped on the tree with `gt sling`, reporting back under the
+    /// rig or agent it was dropped on
+    pub fn sling(&mut self, node: TownNode, request: SlingRequest, cx: &mut Context<Self>) {
+        let gt = self.gt.clone();
+        let run = {
+            let request = request.clone();
+            cx.background_spawn(async move { request.run(&gt) })
+        };
+        cx.spawn(async move |this, cx| {
+            let result = run.await;
+            this.update(cx, |this, cx| {
+                let status = match result {
+                    Ok(()) => {
+                        // The issue's assignee and the agent's hook change
+                        this.reload_structure(cx);
+                        SlingStatus::Succeeded(request)
+                    }
+                    Err(err) => SlingStatus::Failed(request, err.to_string()),
+                };
+                this.town_tree
+                    .update(cx, |tree, cx| tree.set_sling_status(node, status, cx));
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn handle_town_tree_event(
+        &mut self,
+        _: Entity<TownTree>,
+        event: &TownTreeEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            TownTreeEvent::Selected(node) => {
+                let agent = match node {
+                    TownNode::Agent(name) => Some(name.clone()),
+                    _ => None,
+                };
+                self.agent_inspector
+                    .update(cx, |inspector, cx| inspector.set_agent(agent, cx));
+            }
+            TownTreeEvent::Open(node) => self.open_node(node.clone(), cx),
+            TownTreeEvent::OpenBoard(rig) => self.open_board(BoardScope::Rig(rig.clone()), cx),
+            TownTreeEvent::OpenMail(address) => self.open_mail(Some(address.clone()), cx),
+            TownTreeEvent::Compose(recipient) => self.open_composer(recipient.clone(), cx),
+            TownTreeEvent::NewConvoy => self.open_convoy_editor(None, cx),
+            TownTreeEvent::OpenMayorChat => self.open_mayor_chat(cx),
+            TownTreeEvent::WatchOutput(agent) => self.watch_output(agent.clone(), cx),
+            TownTreeEvent::Attach(agent) => self.attach(agent.clone(), cx),
+            TownTreeEvent::Sling { node, request } => self.sling(node.clone(), request.clone(), cx),
+            TownTreeEvent::Refresh => self.reload_structure(cx),
+        }
+    }
+
+    /// Opens a dashboard for the given data source, refreshing it in the background
+    ///
+    /// The dashboard polls every `refresh_interval` and, when `fs` is given, also
+    /// refreshes as soon as files in the town change.
+    pub fn open_dashboard(
+        &mut self,
+        data_source: Arc<dyn DashboardDataSource>,
+        refresh_interval: Duration,
+        fs: Option<Arc<dyn Fs>>,
+        cx: &mut Context<Self>,
+    ) -> Entity<DashboardView> {
+        let conflicts = self.model.read(cx).conflicts().clone();
+        let dashboard = cx.new(|cx| {
+            let mut dashboard = DashboardView::new(data_source, cx);
+            dashboard.set_refresh_interval(Some(refresh_interval), cx);
+            dashboard.set_conflicts(conflicts, cx);
+            if let Some(fs) = fs {
+                dashboard.watch(fs, cx);
+            }
+            dashboard
+        });
+        // Like other items' subscriptions, this goes away with the dashboard when
+        // its tab is closed
+        cx.subscribe(&dashboard, Self::handle_dashboard_event)
+            .detach();
+        self.open_item(dashboard.clone().into(), cx);
+        dashboard
+    }
+
+    fn handle_agent_inspector_event(
+        &mut self,
+        _: Entity<AgentInspector>,
+        event: &AgentInspectorEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            AgentInspectorEvent::Attach(agent) => self.attach(agent.clone(), cx),
+        }
+    }
+
+    /// Keeps the model in sync with the dashboard's data. The town's structure is
+    /// rescanned by `watch` instead, as scanning it is too slow to repeat every poll.
+    fn handle_dashboard_event(
+        &mut self,
+        dashboard: Entity<DashboardView>,
+        event: &DashboardEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            DashboardEvent::DataRefreshed => {
+                if let Some(data) = dashboard.read(cx).data().cloned() {
+                    self.model
+                        .update(cx, |model, cx| model.apply_dashboard_data(data, cx));
+                }
+            }
+            DashboardEvent::OpenConvoy(id) => self.open_convoy(id.clone(), cx),
+            _ => {}
+        }
+    }
+
     /// Returns the currently active item in the center pane
     pub fn active_item(&self) -> Option<&AnyView> {
         self.center_pane.active_item()
@@ -107,6 +822,14 @@ impl Town {
     /// Closes an item at the specified index
     pub fn close_item(&mut self, index: usize, cx: &mut Context<Self>) -> Option<AnyView> {
         let removed = self.center_pane.close_item(index);
+        // Closing an attached terminal detaches from the agent's session
+        if let Some(terminal) = removed
+            .as_ref()
+            .and_then(|view| view.clone().downcast::<AgentTerminal>().ok())
+            && matches!(terminal.read(cx).state(), AttachState::Attached(_))
+        {
+            terminal.update(cx, |terminal, cx| terminal.finish(cx));
+        }
         cx.notify();
         removed
     }
@@ -209,7 +932,6 @@ impl Render for Town {
                     .flex_1()
                     .w_full()
                     .child(
-                        // Left dock area (placeholder)
                         div()
                             .id("left-dock")
                             .flex()
@@ -219,11 +941,10 @@ impl Render for Town {
                             .bg(cx.theme().colors().panel_background)
                             .border_r_1()
                             .border_color(cx.theme().colors().border)
-                            .child("Left Dock"),
+                            .child(self.town_tree.clone()),
                     )
                     .child(self.render_center_pane(cx))
                     .child(
-                        // Right dock area (placeholder)
                         div()
                             .id("right-dock")
                             .flex()
@@ -233,7 +954,7 @@ impl Render for Town {
                             .bg(cx.theme().colors().panel_background)
                             .border_l_1()
                             .border_color(cx.theme().colors().border)
-                            .child("Right Dock"),
+                            .child(self.agent_inspector.clone()),
                     ),
             )
     }
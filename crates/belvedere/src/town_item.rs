@@ -1,7 +1,11 @@
 use gpui::{
-    AnyElement, App, Context, EventEmitter, Focusable, IntoElement, Render, SharedString, Window,
+    AnyElement, App, Context, EventEmitter, Focusable, Hsla, IntoElement, Render, SharedString,
+    TextStyleRefinement, Window,
 };
-use ui::{Color, Icon, Label, LabelCommon};
+use markdown::MarkdownStyle;
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::{ActiveTheme, Color, Icon, Label, LabelCommon};
 
 /// Event types that TownItems can emit
 #[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
@@ -93,3 +97,24 @@ pub trait TownItem: Focusable + EventEmitter<Self::Event> + Render + Sized {
         true
     }
 }
+
+/// Style for markdown shown in an item, like mail bodies and chat messages, in the
+/// UI font and `color`
+pub fn markdown_style(color: Hsla, window: &Window, cx: &App) -> MarkdownStyle {
+    let colors = cx.theme().colors();
+    let theme_settings = ThemeSettings::get_global(cx);
+    let mut text_style = window.text_style();
+    text_style.refine(&TextStyleRefinement {
+        font_family: Some(theme_settings.ui_font.family.clone()),
+        color: Some(color),
+        ..Default::default()
+    });
+
+    MarkdownStyle {
+        base_text_style: text_style,
+        selection_background_color: colors.element_selection_background,
+        syntax: cx.theme().syntax().clone(),
+        code_block_overflow_x_scroll: true,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,1038 @@
+use collections::{BTreeMap, HashMap, VecDeque};
+use gpui::{Context, EventEmitter};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::agent_discovery::{AgentDirectory, AgentRole};
+use crate::beads::BeadsIssue;
+use crate::dashboard_buffer::{
+    AgentInfo, AgentStatus, DashboardData, TokenUsage, convoy_display_name,
+};
+use crate::hook_conflicts::ConflictReport;
+
+/// A project container wrapping a git repository
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rig {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// An agent instance, keyed by its town address (e.g. `frontend/polecats/toast`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Agent {
+    pub name: String,
+    pub role: AgentRole,
+    /// The rig this agent works in; `None` for town-level agents like the Mayor
+    pub rig: Option<String>,
+    pub instance_id: Option<String>,
+    /// The agent's directory, when it was discovered on disk
+    pub path: Option<PathBuf>,
+    pub status: AgentStatus,
+    pub token_usage: Option<TokenUsage>,
+    pub context_fill: Option<f32>,
+}
+
+impl Agent {
+    /// The tmux session Gas Town runs this agent in: `gt-` followed by its address
+    /// with `/` replaced by `-`, e.g. `gt-frontend-polecats-toast`
+    pub fn tmux_session(&self) -> String {
+        format!("gt-{}", self.name.replace('/', "-"))
+    }
+}
+
+/// A personal workspace within a rig (`<rig>/crew/<name>`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Crew {
+    pub name: String,
+    pub rig: String,
+    pub path: PathBuf,
+}
+
+impl Crew {
+    /// Address of the agent working in this crew workspace
+    pub fn agent_name(&self) -> String {
+        format!("{}/crew/{}", self.rig, self.name)
+    }
+}
+
+/// A git worktree holding an agent's persistent work state (`<rig>/hooks/<name>`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hook {
+    pub name: String,
+    pub rig: String,
+    pub path: PathBuf,
+    /// The agent that owns this hook, if it is still around
+    pub agent: Option<String>,
+}
+
+impl Hook {
+    pub fn id(&self) -> String {
+        format!("{}/{}", self.rig, self.name)
+    }
+}
+
+/// A bundle of issues tracked together
+#[derive(Clone, Debug, PartialEq)]
+pub struct Convoy {
+    pub id: String,
+    pub name: String,
+    pub progress: f32,
+    pub issues: Vec<ConvoyIssue>,
+    pub owner: Option<String>,
+    /// Whether a human manages the convoy instead of the Mayor
+    pub human: bool,
+}
+
+impl Convoy {
+    pub fn display_name(&self) -> &str {
+        convoy_display_name(&self.id, &self.name)
+    }
+
+    pub fn has_issue(&self, issue_id: &str) -> bool {
+        self.issues.iter().any(|issue| issue.id == issue_id)
+    }
+}
+
+/// An issue tracked by a convoy
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConvoyIssue {
+    pub id: String,
+    pub title: Option<String>,
+    pub status: String,
+    /// Address of the agent the issue is assigned to
+    pub assignee: Option<String>,
+    /// Ids of the issues that block this one
+    pub depends_on: Vec<String>,
+    /// When the issue was slung to its assignee (RFC 3339)
+    pub slung_at: Option<String>,
+    /// When the issue was closed (RFC 3339)
+    pub completed_at: Option<String>,
+}
+
+impl ConvoyIssue {
+    pub fn is_closed(&self) -> bool {
+        self.status == "closed"
+    }
+}
+
+/// A beads issue and the store it was read from
+#[derive(Clone, Debug, PartialEq)]
+pub struct Issue {
+    /// The rig whose `.beads/` holds the issue; `None` for town-level issues
+    pub rig: Option<String>,
+    /// The `.beads/` directory holding the issue
+    pub beads_dir: PathBuf,
+    pub bead: BeadsIssue,
+}
+
+impl Issue {
+    pub fn id(&self) -> &str {
+        &self.bead.id
+    }
+}
+
+/// A formula definition in a `.beads/formulas/` directory
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormulaFile {
+    pub name: String,
+    /// The rig whose `.beads/` holds the formula; `None` for town-level formulas
+    pub rig: Option<String>,
+    pub path: PathBuf,
+    /// When the file was last modified, so views know to reload it
+    pub modified: Option<SystemTime>,
+}
+
+impl FormulaFile {
+    /// `<rig>/<name>`, or just the name for town-level formulas
+    pub fn id(&self) -> String {
+        match &self.rig {
+            Some(rig) => format!("{}/{}", rig, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Samples kept per agent for the token usage history
+pub const TOKEN_HISTORY_LIMIT: usize = 60;
+
+/// Errors kept per agent
+pub const RECENT_ERRORS_LIMIT: usize = 10;
+
+/// Human interventions kept per agent
+pub const INTERVENTIONS_LIMIT: usize = 20;
+
+/// What an agent has been doing across dashboard refreshes
+#[derive(Clone, Debug, Default)]
+pub struct AgentActivity {
+    /// Token usage whenever it changed, oldest first
+    pub token_history: VecDeque<(Instant, TokenUsage)>,
+    /// When the agent's status, token usage or context fill last changed
+    pub last_activity: Option<Instant>,
+    /// Errors the agent reported, oldest first
+    pub recent_errors: VecDeque<(Instant, String)>,
+    /// When a human attached to the agent's session and for how long, oldest first
+    pub interventions: VecDeque<(Instant, Duration)>,
+}
+
+impl AgentActivity {
+    fn record(&mut self, previous: Option<&AgentInfo>, current: &AgentInfo, now: Instant) {
+        let changed = previous.is_none_or(|previous| {
+            previous.status != current.status
+                || previous.token_usage != current.token_usage
+                || previous.context_fill != current.context_fill
+        });
+        if !changed {
+            return;
+        }
+        self.last_activity = Some(now);
+
+        if let Some(usage) = &current.token_usage
+            && previous.is_none_or(|previous| previous.token_usage.as_ref() != Some(usage))
+        {
+            push_bounded(
+                &mut self.token_history,
+                (now, usage.clone()),
+                TOKEN_HISTORY_LIMIT,
+            );
+        }
+
+        if let AgentStatus::Error(message) = &current.status
+            && previous.is_none_or(|previous| previous.status != current.status)
+        {
+            push_bounded(
+                &mut self.recent_errors,
+                (now, message.clone()),
+                RECENT_ERRORS_LIMIT,
+            );
+        }
+    }
+}
+
+fn push_bounded<T>(queue: &mut VecDeque<T>, value: T, limit: usize) {
+    if queue.len() == limit {
+        queue.pop_front();
+    }
+    queue.push_back(value);
+}
+
+/// What can be learned about a town by walking its directory
+#[derive(Clone, Debug, Default)]
+pub struct TownStructure {
+    pub rigs: Vec<Rig>,
+    pub agents: Vec<AgentDirectory>,
+    pub crew: Vec<Crew>,
+    /// Hooks as found on disk; owners are resolved by the model
+    pub hooks: Vec<Hook>,
+    pub convoy_issues: HashMap<String, Vec<ConvoyIssue>>,
+    /// Every beads issue in the town and its rigs
+    pub issues: Vec<Issue>,
+    pub formulas: Vec<FormulaFile>,
+}
+
+/// Events emitted when part of the town model changes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TownModelEvent {
+    RigsChanged,
+    AgentsChanged,
+    CrewChanged,
+    HooksChanged,
+    ConvoysChanged,
+    IssuesChanged,
+    FormulasChanged,
+    ConflictsChanged,
+}
+
+/// Typed model of everything in a town.
+///
+/// Combines the on-disk structure (agent directories, crew, hooks, convoy members)
+/// with the latest dashboard snapshot (status, token usage, convoy progress).
+/// Views subscribe to `TownModelEvent`s instead of re-reading raw data.
+pub struct TownModel {
+    structure: TownStructure,
+    reported: DashboardData,
+    rigs: BTreeMap<String, Rig>,
+    agents: BTreeMap<String, Agent>,
+    crew: BTreeMap<String, Crew>,
+    hooks: BTreeMap<String, Hook>,
+    convoys: BTreeMap<String, Convoy>,
+    issues: BTreeMap<String, Issue>,
+    formulas: BTreeMap<String, FormulaFile>,
+    activity: HashMap<String, AgentActivity>,
+    /// Agents a human is attached to, and since when
+    attended: HashMap<String, Instant>,
+    /// Hooks changing the same files, from the latest analysis
+    conflicts: ConflictReport,
+}
+
+impl EventEmitter<TownModelEvent> for TownModel {}
+
+impl Default for TownModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TownModel {
+    pub fn new() -> Self {
+        Self {
+            structure: TownStructure::default(),
+            reported: DashboardData::default(),
+            rigs: BTreeMap::new(),
+            agents: BTreeMap::new(),
+            crew: BTreeMap::new(),
+            hooks: BTreeMap::new(),
+            convoys: BTreeMap::new(),
+            issues: BTreeMap::new(),
+            formulas: BTreeMap::new(),
+            activity: HashMap::default(),
+            attended: HashMap::default(),
+            conflicts: ConflictReport::default(),
+        }
+    }
+
+    /// Replaces the on-disk structure, e.g. after a new `FsDataSource::read_structure`
+    pub fn apply_structure(&mut self, structure: TownStructure, cx: &mut Context<Self>) {
+        self.structure = structure;
+        self.rebuild(cx);
+    }
+
+    /// Replaces the latest dashboard snapshot
+    ///
+    /// Changes since the previous snapshot are recorded in each agent's `AgentActivity`.
+    pub fn apply_dashboard_data(&mut self, data: DashboardData, cx: &mut Context<Self>) {
+        let now = Instant::now();
+        for agent in &data.agents {
+            let previous = self
+                .reported
+                .agents
+                .iter()
+                .find(|previous| previous.name == agent.name);
+            self.activity
+                .entry(agent.name.clone())
+                .or_default()
+                .record(previous, agent, now);
+        }
+        // Interventions are kept even while an agent isn't reported
+        self.activity.retain(|name, activity| {
+            data.agents.iter().any(|agent| &agent.name == name)
+                || !activity.interventions.is_empty()
+        });
+
+        self.reported = data;
+        self.rebuild(cx);
+    }
+
+    /// Replaces the hook conflicts, e.g. after a new `hook_conflicts::analyze_hooks`
+    pub fn set_conflicts(&mut self, conflicts: ConflictReport, cx: &mut Context<Self>) {
+        if conflicts != self.conflicts {
+            self.conflicts = conflicts;
+            cx.emit(TownModelEvent::ConflictsChanged);
+            cx.notify();
+        }
+    }
+
+    pub fn conflicts(&self) -> &ConflictReport {
+        &self.conflicts
+    }
+
+    pub fn rigs(&self) -> impl Iterator<Item = &Rig> {
+        self.rigs.values()
+    }
+
+    pub fn agents(&self) -> impl Iterator<Item = &Agent> {
+        self.agents.values()
+    }
+
+    pub fn crew(&self) -> impl Iterator<Item = &Crew> {
+        self.crew.values()
+    }
+
+    pub fn hooks(&self) -> impl Iterator<Item = &Hook> {
+        self.hooks.values()
+    }
+
+    pub fn convoys(&self) -> impl Iterator<Item = &Convoy> {
+        self.convoys.values()
+    }
+
+    pub fn issues(&self) -> impl Iterator<Item = &Issue> {
+        self.issues.values()
+    }
+
+    pub fn formulas(&self) -> impl Iterator<Item = &FormulaFile> {
+        self.formulas.values()
+    }
+
+    pub fn rig(&self, name: &str) -> Option<&Rig> {
+        self.rigs.get(name)
+    }
+
+    pub fn agent(&self, name: &str) -> Option<&Agent> {
+        self.agents.get(name)
+    }
+
+    pub fn activity(&self, agent: &str) -> Option<&AgentActivity> {
+        self.activity.get(agent)
+    }
+
+    /// Whether a human is attached to the agent's session
+    pub fn is_attended(&self, agent: &str) -> bool {
+        self.attended.contains_key(agent)
+    }
+
+    /// Marks the agent as taken over by a human, e.g. in an attached terminal
+    pub fn begin_intervention(&mut self, agent: &str, cx: &mut Context<Self>) {
+        if self.attended.contains_key(agent) {
+            return;
+        }
+        self.attended.insert(agent.to_string(), Instant::now());
+        cx.emit(TownModelEvent::AgentsChanged);
+        cx.notify();
+    }
+
+    /// Returns the agent to unattended mode and records the intervention in its
+    /// activity
+    pub fn end_intervention(&mut self, agent: &str, cx: &mut Context<Self>) {
+        let Some(started) = self.attended.remove(agent) else {
+            return;
+        };
+        push_bounded(
+            &mut self
+                .activity
+                .entry(agent.to_string())
+                .or_default()
+                .interventions,
+            (started, started.elapsed()),
+            INTERVENTIONS_LIMIT,
+        );
+        cx.emit(TownModelEvent::AgentsChanged);
+        cx.notify();
+    }
+
+    /// Returns the agent to unattended mode without recording an intervention, for
+    /// attaches that never took over the session
+    pub fn discard_intervention(&mut self, agent: &str, cx: &mut Context<Self>) {
+        if self.attended.remove(agent).is_some() {
+            cx.emit(TownModelEvent::AgentsChanged);
+            cx.notify();
+        }
+    }
+
+    pub fn hook(&self, id: &str) -> Option<&Hook> {
+        self.hooks.get(id)
+    }
+
+    pub fn convoy(&self, id: &str) -> Option<&Convoy> {
+        self.convoys.get(id)
+    }
+
+    pub fn issue(&self, id: &str) -> Option<&Issue> {
+        self.issues.get(id)
+    }
+
+    pub fn formula(&self, id: &str) -> Option<&FormulaFile> {
+        self.formulas.get(id)
+    }
+
+    pub fn rig_for_agent(&self, agent: &str) -> Option<&Rig> {
+        self.rigs.get(self.agents.get(agent)?.rig.as_ref()?)
+    }
+
+    pub fn agents_in_rig<'a>(&'a self, rig: &'a str) -> impl Iterator<Item = &'a Agent> + 'a {
+        self.agents
+            .values()
+            .filter(move |agent| agent.rig.as_deref() == Some(rig))
+    }
+
+    pub fn crew_in_rig<'a>(&'a self, rig: &'a str) -> impl Iterator<Item = &'a Crew> + 'a {
+        self.crew.values().filter(move |crew| crew.rig == rig)
+    }
+
+    pub fn hooks_in_rig<'a>(&'a self, rig: &'a str) -> impl Iterator<Item = &'a Hook> + 'a {
+        self.hooks.values().filter(move |hook| hook.rig == rig)
+    }
+
+    pub fn issues_in_rig<'a>(&'a self, rig: &'a str) -> impl Iterator<Item = &'a Issue> + 'a {
+        self.issues
+            .values()
+            .filter(move |issue| issue.rig.as_deref() == Some(rig))
+    }
+
+    pub fn formulas_in_rig<'a>(
+        &'a self,
+        rig: &'a str,
+    ) -> impl Iterator<Item = &'a FormulaFile> + 'a {
+        self.formulas
+            .values()
+            .filter(move |formula| formula.rig.as_deref() == Some(rig))
+    }
+
+    pub fn hook_for_agent(&self, agent: &str) -> Option<&Hook> {
+        self.hooks
+            .values()
+            .find(|hook| hook.agent.as_deref() == Some(agent))
+    }
+
+    /// The agents assigned to a convoy's issues, in issue order and without duplicates
+    pub fn convoy_agents(&self, convoy_id: &str) -> Vec<&Agent> {
+        let Some(convoy) = self.convoys.get(convoy_id) else {
+            return Vec::new();
+        };
+
+        let mut agents: Vec<&Agent> = Vec::new();
+        for issue in &convoy.issues {
+            if let Some(agent) = issue
+                .assignee
+                .as_deref()
+                .and_then(|name| self.agents.get(name))
+                && !agents.iter().any(|existing| existing.name == agent.name)
+            {
+                agents.push(agent);
+            }
+        }
+        agents
+    }
+
+    /// The convoys with an issue assigned to `agent`
+    pub fn convoys_for_agent<'a>(
+        &'a self,
+        agent: &'a str,
+    ) -> impl Iterator<Item = &'a Convoy> + 'a {
+        self.convoys.values().filter(move |convoy| {
+            convoy
+                .issues
+                .iter()
+                .any(|issue| issue.assignee.as_deref() == Some(agent))
+        })
+    }
+
+    /// Rebuilds the typed views, emitting an event for each one that changed and
+    /// notifying observers only if any did
+    fn rebuild(&mut self, cx: &mut Context<Self>) {
+        let rigs = self.build_rigs();
+        let agents = self.build_agents();
+        let crew = self
+            .structure
+            .crew
+            .iter()
+            .map(|crew| (format!("{}/{}", crew.rig, crew.name), crew.clone()))
+            .collect();
+        let hooks = self.build_hooks(&agents);
+        let convoys = self.build_convoys();
+        let mut issues = BTreeMap::new();
+        for issue in &self.structure.issues {
+            issues
+                .entry(issue.id().to_string())
+                .or_insert_with(|| issue.clone());
+        }
+        let formulas = self
+            .structure
+            .formulas
+            .iter()
+            .map(|formula| (formula.id(), formula.clone()))
+            .collect();
+
+        let mut changed = false;
+        if rigs != self.rigs {
+            self.rigs = rigs;
+            cx.emit(TownModelEvent::RigsChanged);
+            changed = true;
+        }
+        if agents != self.agents {
+            self.agents = agents;
+            cx.emit(TownModelEvent::AgentsChanged);
+            changed = true;
+        }
+        if crew != self.crew {
+            self.crew = crew;
+            cx.emit(TownModelEvent::CrewChanged);
+            changed = true;
+        }
+        if hooks != self.hooks {
+            self.hooks = hooks;
+            cx.emit(TownModelEvent::HooksChanged);
+            changed = true;
+        }
+        if convoys != self.convoys {
+            self.convoys = convoys;
+            cx.emit(TownModelEvent::ConvoysChanged);
+            changed = true;
+        }
+        if issues != self.issues {
+            self.issues = issues;
+            cx.emit(TownModelEvent::IssuesChanged);
+            changed = true;
+        }
+        if formulas != self.formulas {
+            self.formulas = formulas;
+            cx.emit(TownModelEvent::FormulasChanged);
+            changed = true;
+        }
+        if changed {
+            cx.notify();
+        }
+    }
+
+    fn build_rigs(&self) -> BTreeMap<String, Rig> {
+        let mut rigs: BTreeMap<String, Rig> = self
+            .structure
+            .rigs
+            .iter()
+            .map(|rig| (rig.name.clone(), rig.clone()))
+            .collect();
+        for rig in &self.reported.rigs {
+            rigs.insert(
+                rig.name.clone(),
+                Rig {
+                    name: rig.name.clone(),
+                    path: PathBuf::from(&rig.path),
+                },
+            );
+        }
+        rigs
+    }
+
+    fn build_agents(&self) -> BTreeMap<String, Agent> {
+        let mut agents = BTreeMap::new();
+
+        for directory in &self.structure.agents {
+            let name = directory.address();
+            agents.insert(
+                name.clone(),
+                Agent {
+                    name,
+                    role: directory.role,
+                    rig: directory.rig.clone(),
+                    instance_id: directory.instance_id.clone(),
+                    path: Some(directory.path.clone()),
+                    status: AgentStatus::Idle,
+                    token_usage: None,
+                    context_fill: None,
+                },
+            );
+        }
+
+        for info in &self.reported.agents {
+            let agent = agents.entry(info.name.clone()).or_insert_with(|| {
+                let (role, rig, instance_id) = parse_address(&info.name);
+                Agent {
+                    name: info.name.clone(),
+                    role,
+                    rig,
+                    instance_id,
+                    path: None,
+                    status: AgentStatus::Idle,
+                    token_usage: None,
+                    context_fill: None,
+                }
+            });
+            agent.status = info.status.clone();
+            agent.token_usage = info.token_usage.clone();
+            agent.context_fill = info.context_fill;
+        }
+
+        agents
+    }
+
+    fn build_hooks(&self, agents: &BTreeMap<String, Agent>) -> BTreeMap<String, Hook> {
+        self.structure
+            .hooks
+            .iter()
+            .map(|hook| {
+                let owner = agents
+                    .values()
+                    .find(|agent| {
+                        agent.rig.as_deref() == Some(hook.rig.as_str())
+                            && agent_dir_name(agent) == Some(hook.name.as_str())
+                    })
+                    .map(|agent| agent.name.clone());
+                let hook = Hook {
+                    agent: owner,
+                    ..hook.clone()
+                };
+                (hook.id(), hook)
+            })
+            .collect()
+    }
+
+    fn build_convoys(&self) -> BTreeMap<String, Convoy> {
+        self.reported
+            .convoys
+            .iter()
+            .map(|convoy| {
+                let issues = self
+                    .structure
+                    .convoy_issues
+                    .get(&convoy.id)
+                    .cloned()
+                    .unwrap_or_default();
+                (
+                    convoy.id.clone(),
+                    Convoy {
+                        id: convoy.id.clone(),
+                        name: convoy.name.clone(),
+                        progress: convoy.progress,
+                        issues,
+                        owner: convoy.owner.clone(),
+                        human: convoy.human,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// The directory name an agent's hook would use
+fn agent_dir_name(agent: &Agent) -> Option<&str> {
+    match &agent.path {
+        Some(path) => path.file_name()?.to_str(),
+        None => agent.name.rsplit('/').next(),
+    }
+}
+
+/// Infers role, rig and instance id from an address like `frontend/polecats/toast`
+pub(crate) fn parse_address(address: &str) -> (AgentRole, Option<String>, Option<String>) {
+    let segments: Vec<&str> = address.split('/').collect();
+    match segments.as_slice() {
+        [rig, "crew", name] => (
+            AgentRole::Crew,
+            Some(rig.to_string()),
+            Some(name.to_string()),
+        ),
+        [rig, "polecats", name] => (
+            AgentRole::Polecat,
+            Some(rig.to_string()),
+            Some(name.to_string()),
+        ),
+        [rig, instance] => {
+            let directory = AgentDirectory::from_path(PathBuf::from(instance));
+            (
+                directory.map_or(AgentRole::Unknown, |directory| directory.role),
+                Some(rig.to_string()),
+                instance.split_once('-').map(|(_, id)| id.to_string()),
+            )
+        }
+        [instance] => {
+            let directory = AgentDirectory::from_path(PathBuf::from(instance));
+            (
+                directory.map_or(AgentRole::Unknown, |directory| directory.role),
+                None,
+                instance.split_once('-').map(|(_, id)| id.to_string()),
+            )
+        }
+        _ => (AgentRole::Unknown, None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard_buffer::{ConvoyInfo, RigInfo};
+    use gpui::{AppContext as _, TestAppContext};
+    use std::sync::{Arc, Mutex};
+
+    fn structure() -> TownStructure {
+        TownStructure {
+            rigs: vec![Rig {
+                name: "frontend".into(),
+                path: "/gt/frontend".into(),
+            }],
+            agents: vec![
+                AgentDirectory::from_path("/gt/mayor".into()).unwrap(),
+                AgentDirectory::from_rig_member("/gt/frontend/crew/alice".into()).unwrap(),
+                AgentDirectory::from_rig_member("/gt/frontend/polecats/toast".into()).unwrap(),
+            ],
+            crew: vec![Crew {
+                name: "alice".into(),
+                rig: "frontend".into(),
+                path: "/gt/frontend/crew/alice".into(),
+            }],
+            hooks: vec![
+                Hook {
+                    name: "toast".into(),
+                    rig: "frontend".into(),
+                    path: "/gt/frontend/hooks/toast".into(),
+                    agent: None,
+                },
+                Hook {
+                    name: "furiosa".into(),
+                    rig: "frontend".into(),
+                    path: "/gt/frontend/hooks/furiosa".into(),
+                    agent: None,
+                },
+            ],
+            convoy_issues: HashMap::from_iter([(
+                "hq-cv-1".to_string(),
+                vec![
+                    ConvoyIssue {
+                        id: "fe-1".into(),
+                        status: "closed".into(),
+                        assignee: Some("frontend/polecats/toast".into()),
+                        ..Default::default()
+                    },
+                    ConvoyIssue {
+                        id: "fe-2".into(),
+                        status: "open".into(),
+                        assignee: Some("frontend/crew/alice".into()),
+                        ..Default::default()
+                    },
+                    ConvoyIssue {
+                        id: "fe-3".into(),
+                        status: "open".into(),
+                        assignee: Some("frontend/polecats/toast".into()),
+                        ..Default::default()
+                    },
+                ],
+            )]),
+        }
+    }
+
+    fn dashboard_data() -> DashboardData {
+        DashboardData {
+            agents: vec![
+                AgentInfo {
+                    name: "frontend/polecats/toast".into(),
+                    status: AgentStatus::Active,
+                    token_usage: None,
+                    context_fill: Some(0.5),
+                },
+                AgentInfo {
+                    name: "backend/witness-backend".into(),
+                    status: AgentStatus::Idle,
+                    token_usage: None,
+                    context_fill: None,
+                },
+            ],
+            convoys: vec![ConvoyInfo {
+                id: "hq-cv-1".into(),
+                progress: 1.0 / 3.0,
+                ..Default::default()
+            }],
+            rigs: vec![RigInfo {
+                name: "backend".into(),
+                path: "/gt/backend".into(),
+            }],
+        }
+    }
+
+    #[gpui::test]
+    async fn test_model_merges_structure_and_dashboard_data(cx: &mut TestAppContext) {
+        let model = cx.new(|_| TownModel::new());
+        model.update(cx, |model, cx| {
+            model.apply_structure(structure(), cx);
+            model.apply_dashboard_data(dashboard_data(), cx);
+        });
+
+        model.read_with(cx, |model, _| {
+            let rigs: Vec<&str> = model.rigs().map(|rig| rig.name.as_str()).collect();
+            assert_eq!(rigs, vec!["backend", "frontend"]);
+
+            let toast = model.agent("frontend/polecats/toast").unwrap();
+            assert_eq!(toast.role, AgentRole::Polecat);
+            assert_eq!(toast.status, AgentStatus::Active);
+            assert_eq!(toast.context_fill, Some(0.5));
+            assert_eq!(
+                toast.path.as_deref(),
+                Some(std::path::Path::new("/gt/frontend/polecats/toast"))
+            );
+
+            let witness = model.agent("backend/witness-backend").unwrap();
+            assert_eq!(witness.role, AgentRole::Witness);
+            assert_eq!(witness.rig.as_deref(), Some("backend"));
+            assert_eq!(witness.instance_id.as_deref(), Some("backend"));
+            assert_eq!(witness.path, None);
+
+            assert_eq!(
+                model.agent("mayor").unwrap().status,
+                AgentStatus::Idle,
+                "discovered agents default to idle"
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_model_cross_references(cx: &mut TestAppContext) {
+        let model = cx.new(|_| TownModel::new());
+        model.update(cx, |model, cx| {
+            model.apply_structure(structure(), cx);
+            model.apply_dashboard_data(dashboard_data(), cx);
+        });
+
+        model.read_with(cx, |model, _| {
+            assert_eq!(
+                model.rig_for_agent("frontend/crew/alice").unwrap().name,
+                "frontend"
+            );
+            assert!(model.rig_for_agent("mayor").is_none());
+
+            let frontend_agents: Vec<&str> = model
+                .agents_in_rig("frontend")
+                .map(|agent| agent.name.as_str())
+                .collect();
+            assert_eq!(
+                frontend_agents,
+                vec!["frontend/crew/alice", "frontend/polecats/toast"]
+            );
+
+            assert_eq!(
+                model
+                    .hook_for_agent("frontend/polecats/toast")
+                    .unwrap()
+                    .id(),
+                "frontend/toast"
+            );
+            assert_eq!(model.hook("frontend/furiosa").unwrap().agent, None);
+            assert_eq!(
+                model.crew_in_rig("frontend").next().unwrap().agent_name(),
+                "frontend/crew/alice"
+            );
+
+            let convoy_agents: Vec<&str> = model
+                .convoy_agents("hq-cv-1")
+                .iter()
+                .map(|agent| agent.name.as_str())
+                .collect();
+            assert_eq!(
+                convoy_agents,
+                vec!["frontend/polecats/toast", "frontend/crew/alice"]
+            );
+            assert_eq!(model.convoys_for_agent("frontend/crew/alice").count(), 1);
+            assert_eq!(model.convoys_for_agent("mayor").count(), 0);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_model_emits_events_only_for_changes(cx: &mut TestAppContext) {
+        let model = cx.new(|_| TownModel::new());
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let notifications = Arc::new(Mutex::new(0));
+        cx.update({
+            let events = events.clone();
+            let notifications = notifications.clone();
+            |cx| {
+                cx.subscribe(&model, move |_, event: &TownModelEvent, _| {
+                    events.lock().unwrap().push(*event);
+                })
+                .detach();
+                cx.observe(&model, move |_, _| *notifications.lock().unwrap() += 1)
+                    .detach();
+            }
+        });
+
+        model.update(cx, |model, cx| model.apply_structure(structure(), cx));
+        assert_eq!(
+            std::mem::take(&mut *events.lock().unwrap()),
+            vec![
+                TownModelEvent::RigsChanged,
+                TownModelEvent::AgentsChanged,
+                TownModelEvent::CrewChanged,
+                TownModelEvent::HooksChanged,
+            ]
+        );
+
+        model.update(cx, |model, cx| {
+            model.apply_dashboard_data(dashboard_data(), cx)
+        });
+        assert_eq!(
+            std::mem::take(&mut *events.lock().unwrap()),
+            vec![
+                TownModelEvent::RigsChanged,
+                TownModelEvent::AgentsChanged,
+                TownModelEvent::ConvoysChanged,
+            ]
+        );
+
+        *notifications.lock().unwrap() = 0;
+        model.update(cx, |model, cx| {
+            model.apply_dashboard_data(dashboard_data(), cx)
+        });
+        model.update(cx, |model, cx| model.apply_structure(structure(), cx));
+        assert!(events.lock().unwrap().is_empty());
+        assert_eq!(*notifications.lock().unwrap(), 0);
+    }
+
+    #[gpui::test]
+    async fn test_model_records_agent_activity(cx: &mut TestAppContext) {
+        let model = cx.new(|_| TownModel::new());
+        let snapshot = |status: AgentStatus, input_tokens: u64| DashboardData {
+            agents: vec![AgentInfo {
+                name: "frontend/polecats/toast".into(),
+                status,
+                token_usage: Some(TokenUsage {
+                    input_tokens,
+                    output_tokens: 0,
+                }),
+                context_fill: None,
+            }],
+            ..Default::default()
+        };
+
+        model.update(cx, |model, cx| {
+            model.apply_dashboard_data(snapshot(AgentStatus::Active, 100), cx);
+            model.apply_dashboard_data(snapshot(AgentStatus::Active, 100), cx);
+            model.apply_dashboard_data(snapshot(AgentStatus::Active, 250), cx);
+            model.apply_dashboard_data(snapshot(AgentStatus::Error("stuck".into()), 250), cx);
+            model.apply_dashboard_data(snapshot(AgentStatus::Error("stuck".into()), 250), cx);
+        });
+
+        model.read_with(cx, |model, _| {
+            let activity = model.activity("frontend/polecats/toast").unwrap();
+            let history: Vec<u64> = activity
+                .token_history
+                .iter()
+                .map(|(_, usage)| usage.input_tokens)
+                .collect();
+            assert_eq!(history, vec![100, 250]);
+            assert_eq!(activity.recent_errors.len(), 1);
+            assert_eq!(activity.recent_errors[0].1, "stuck");
+            assert!(activity.last_activity.is_some());
+        });
+
+        model.update(cx, |model, cx| {
+            model.apply_dashboard_data(DashboardData::default(), cx)
+        });
+        model.read_with(cx, |model, _| {
+            assert!(model.activity("frontend/polecats/toast").is_none());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_model_records_interventions(cx: &mut TestAppContext) {
+        let model = cx.new(|_| TownModel::new());
+        let events = Arc::new(Mutex::new(Vec::new()));
+        cx.update(|cx| {
+            let events = events.clone();
+            cx.subscribe(&model, move |_, event: &TownModelEvent, _| {
+                events.lock().unwrap().push(*event)
+            })
+            .detach();
+        });
+
+        model.update(cx, |model, cx| {
+            model.begin_intervention("frontend/polecats/toast", cx);
+            model.begin_intervention("frontend/polecats/toast", cx);
+        });
+        model.read_with(cx, |model, _| {
+            assert!(model.is_attended("frontend/polecats/toast"));
+            assert!(model.activity("frontend/polecats/toast").is_none());
+        });
+
+        model.update(cx, |model, cx| {
+            model.end_intervention("frontend/polecats/toast", cx);
+            model.end_intervention("frontend/polecats/toast", cx);
+            model.apply_dashboard_data(DashboardData::default(), cx);
+
+            model.begin_intervention("frontend/polecats/toast", cx);
+            model.discard_intervention("frontend/polecats/toast", cx);
+        });
+        model.read_with(cx, |model, _| {
+            assert!(!model.is_attended("frontend/polecats/toast"));
+            let activity = model.activity("frontend/polecats/toast").unwrap();
+            assert_eq!(activity.interventions.len(), 1);
+        });
+        assert_eq!(
+            events.lock().unwrap()[..2],
+            [TownModelEvent::AgentsChanged, TownModelEvent::AgentsChanged]
+        );
+    }
+}
@@ -0,0 +1,1166 @@
+use collections::{HashMap, HashSet};
+use gpui::{
+    App, ClipboardItem, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    KeyBinding, MouseButton, MouseDownEvent, Pixels, Point, Render, SharedString, Subscription,
+    Window, actions, anchored, deferred, div, prelude::*, px,
+};
+use std::path::PathBuf;
+use ui::{ActiveTheme, ContextMenu};
+
+use crate::agent_discovery::AgentRole;
+use crate::composer::Recipient;
+use crate::dashboard_buffer::AgentStatus;
+use crate::formula;
+use crate::kanban_board::DraggedIssue;
+use crate::sling::{RUNTIMES, SlingRequest, SlingStatus};
+use crate::town_model::{Agent, TownModel, TownModelEvent};
+
+const KEY_CONTEXT: &str = "TownTree";
+
+actions!(
+    town_tree,
+    [
+        /// Selects the next visible node
+        SelectNext,
+        /// Selects the previous visible node
+        SelectPrevious,
+        /// Expands the selected node, or moves to its first child
+        ExpandSelected,
+        /// Collapses the selected node, or moves to its parent
+        CollapseSelected,
+        /// Opens the selected node in the center pane
+        OpenSelected,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("down", SelectNext, Some(KEY_CONTEXT)),
+        KeyBinding::new("up", SelectPrevious, Some(KEY_CONTEXT)),
+        KeyBinding::new("right", ExpandSelected, Some(KEY_CONTEXT)),
+        KeyBinding::new("left", CollapseSelected, Some(KEY_CONTEXT)),
+        KeyBinding::new("enter", OpenSelected, Some(KEY_CONTEXT)),
+    ]);
+}
+
+/// A node of the town hierarchy
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TownNode {
+    Town,
+    Rig(String),
+    /// The crew, hooks or polecats of a rig
+    Group {
+        rig: String,
+        group: RigGroup,
+    },
+    /// An agent, by address
+    Agent(String),
+    /// A hook, by `<rig>/<name>` id
+    Hook(String),
+    /// A formula, by `<rig>/<name>` id
+    Formula(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RigGroup {
+    Crew,
+    Hooks,
+    Polecats,
+    Formulas,
+}
+
+impl RigGroup {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RigGroup::Crew => "Crew",
+            RigGroup::Hooks => "Hooks",
+            RigGroup::Polecats => "Polecats",
+            RigGroup::Formulas => "Formulas",
+        }
+    }
+}
+
+/// The status shown next to a node
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeBadge {
+    Status(AgentStatus),
+    /// Active agents out of all agents below the node
+    Activity {
+        active: usize,
+        total: usize,
+    },
+    Count(usize),
+    /// A hook whose agent is gone
+    Orphaned,
+}
+
+/// A visible row of the tree
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeEntry {
+    pub node: TownNode,
+    pub depth: usize,
+    pub label: SharedString,
+    pub badge: Option<NodeBadge>,
+    pub expandable: bool,
+    pub expanded: bool,
+}
+
+pub enum TownTreeEvent {
+    /// The selection moved to a node
+    Selected(TownNode),
+    /// Open the detail item for a node in the center pane
+    Open(TownNode),
+    /// Open the kanban board of a rig's issues
+    OpenBoard(String),
+    /// Open the mail viewer on an agent's mailbox
+    OpenMail(String),
+    /// Open the composer to message an agent, a rig or the whole town
+    Compose(Recipient),
+    /// Open the editor for a new convoy
+    NewConvoy,
+    /// Open the conversation with the Mayor
+    OpenMayorChat,
+    /// Stream a polecat's session output into the output grid
+    WatchOutput(String),
+    /// Attach to an agent's tmux session to take it over by hand
+    Attach(String),
+    /// Assign an issue dropped on a rig or agent with `gt sling`
+    Sling {
+        node: TownNode,
+        request: SlingRequest,
+    },
+    /// Rescan the town directory
+    Refresh,
+}
+
+/// Collapsible Town → Mayor → Rigs → Crew / Hooks / Polecats hierarchy for the left dock
+pub struct TownTree {
+    model: Entity<TownModel>,
+    town_name: SharedString,
+    entries: Vec<TreeEntry>,
+    collapsed: HashSet<TownNode>,
+    selected: Option<TownNode>,
+    focus_handle: FocusHandle,
+    context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
+    /// The last issue slung onto each rig or agent
+    slings: HashMap<TownNode, SlingStatus>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl EventEmitter<TownTreeEvent> for TownTree {}
+
+impl TownTree {
+    pub fn new(
+        model: Entity<TownModel>,
+        town_name: impl Into<SharedString>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&model, |this, _, _: &TownModelEvent, cx| {
+            this.rebuild_entries(cx);
+        });
+
+        let mut tree = Self {
+            model,
+            town_name: town_name.into(),
+            entries: Vec::new(),
+            collapsed: HashSet::default(),
+            selected: None,
+            focus_handle: cx.focus_handle(),
+            context_menu: None,
+            slings: HashMap::default(),
+            _subscriptions: vec![subscription],
+        };
+        tree.rebuild_entries(cx);
+        tree
+    }
+
+    /// The rows currently visible, in display order
+    pub fn entries(&self) -> &[TreeEntry] {
+        &self.entries
+    }
+
+    pub fn selected(&self) -> Option<&TownNode> {
+        self.selected.as_ref()
+    }
+
+    pub fn select(&mut self, node: TownNode, cx: &mut Context<Self>) {
+        if self.selected.as_ref() != Some(&node) {
+            self.selected = Some(node.clone());
+            cx.emit(TownTreeEvent::Selected(node));
+        }
+        cx.notify();
+    }
+
+    pub fn is_expanded(&self, node: &TownNode) -> bool {
+        !self.collapsed.contains(node)
+    }
+
+    pub fn set_expanded(&mut self, node: &TownNode, expanded: bool, cx: &mut Context<Self>) {
+        if expanded {
+            self.collapsed.remove(node);
+        } else {
+            self.collapsed.insert(node.clone());
+        }
+        self.rebuild_entries(cx);
+    }
+
+    pub fn toggle_expanded(&mut self, node: &TownNode, cx: &mut Context<Self>) {
+        let expanded = self.is_expanded(node);
+        self.set_expanded(node, !expanded, cx);
+    }
+
+    /// Selects `node` and asks the town to open it
+    pub fn open(&mut self, node: TownNode, cx: &mut Context<Self>) {
+        self.select(node.clone(), cx);
+        cx.emit(TownTreeEvent::Open(node));
+    }
+
+    pub fn sling_status(&self, node: &TownNode) -> Option<&SlingStatus> {
+        self.slings.get(node)
+    }
+
+    /// Records the progress of a sling onto `node`, shown beneath its row
+    pub fn set_sling_status(
+        &mut self,
+        node: TownNode,
+        status: SlingStatus,
+        cx: &mut Context<Self>,
+    ) {
+        self.slings.insert(node, status);
+        cx.notify();
+    }
+
+    pub fn dismiss_sling_status(&mut self, node: &TownNode, cx: &mut Context<Self>) {
+        if self.slings.remove(node).is_some() {
+            cx.notify();
+        }
+    }
+
+    /// Asks the town to sling an issue onto a rig or agent
+    pub fn sling(&mut self, node: TownNode, request: SlingRequest, cx: &mut Context<Self>) {
+        self.slings
+            .insert(node.clone(), SlingStatus::Running(request.clone()));
+        cx.emit(TownTreeEvent::Sling { node, request });
+        cx.notify();
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        let selected = self.selected.as_ref()?;
+        self.entries
+            .iter()
+            .position(|entry| &entry.node == selected)
+    }
+
+    fn select_index(&mut self, index: usize, cx: &mut Context<Self>) {
+        if let Some(entry) = self.entries.get(index) {
+            self.select(entry.node.clone(), cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let index = match self.selected_index() {
+            Some(index) => (index + 1).min(self.entries.len().saturating_sub(1)),
+            None => 0,
+        };
+        self.select_index(index, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        let index = match self.selected_index() {
+            Some(index) => index.saturating_sub(1),
+            None => self.entries.len().saturating_sub(1),
+        };
+        self.select_index(index, cx);
+    }
+
+    fn expand_selected(&mut self, _: &ExpandSelected, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(index) = self.selected_index() else {
+            return;
+        };
+        let entry = &self.entries[index];
+        if !entry.expandable {
+            return;
+        }
+        if entry.expanded {
+            let depth = entry.depth;
+            if self
+                .entries
+                .get(index + 1)
+                .is_some_and(|child| child.depth > depth)
+            {
+                self.select_index(index + 1, cx);
+            }
+        } else {
+            let node = entry.node.clone();
+            self.set_expanded(&node, true, cx);
+        }
+    }
+
+    fn collapse_selected(&mut self, _: &CollapseSelected, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(index) = self.selected_index() else {
+            return;
+        };
+        let entry = &self.entries[index];
+        if entry.expandable && entry.expanded {
+            let node = entry.node.clone();
+            self.set_expanded(&node, false, cx);
+            return;
+        }
+
+        let depth = entry.depth;
+        if let Some(parent) = self.entries[..index]
+            .iter()
+            .rposition(|entry| entry.depth < depth)
+        {
+            self.select_index(parent, cx);
+        }
+    }
+
+    fn open_selected(&mut self, _: &OpenSelected, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(node) = self.selected.clone() {
+            self.open(node, cx);
+        }
+    }
+
+    fn rebuild_entries(&mut self, cx: &mut Context<Self>) {
+        self.entries = build_entries(self.model.read(cx), &self.town_name, &self.collapsed);
+        cx.notify();
+    }
+
+    fn deploy_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        node: TownNode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select(node.clone(), cx);
+
+        let model = self.model.read(cx);
+        let expandable = self
+            .entries
+            .iter()
+            .any(|entry| entry.node == node && entry.expandable);
+        let expanded = self.is_expanded(&node);
+        let address = match &node {
+            TownNode::Agent(name) => Some(name.clone()),
+            TownNode::Hook(id) => Some(id.clone()),
+            _ => None,
+        };
+        let path = node_path(model, &node);
+        let board_rig = match &node {
+            TownNode::Rig(rig) => Some(rig.clone()),
+            _ => None,
+        };
+        let mailbox = match &node {
+            TownNode::Agent(name) => Some(name.clone()),
+            _ => None,
+        };
+        let mayor_chat = match &node {
+            TownNode::Town => true,
+            TownNode::Agent(name) => model
+                .agent(name)
+                .is_some_and(|agent| agent.role == AgentRole::Mayor),
+            _ => false,
+        };
+        let polecat = match &node {
+            TownNode::Agent(name) => model
+                .agent(name)
+                .filter(|agent| agent.role == AgentRole::Polecat)
+                .map(|agent| agent.name.clone()),
+            _ => None,
+        };
+        let attach = match &node {
+            TownNode::Agent(name) => Some(name.clone()),
+            _ => None,
+        };
+        let compose = match &node {
+            TownNode::Town => Some(("Broadcast…", Recipient::Everyone)),
+            TownNode::Rig(rig) => Some(("Message Rig…", Recipient::Rig(rig.clone()))),
+            TownNode::Agent(name) => Some(("Nudge…", Recipient::Agent(name.clone()))),
+            _ => None,
+        };
+        let this = cx.entity().downgrade();
+
+        let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
+            menu.entry("Open", None, {
+                let this = this.clone();
+                let node = node.clone();
+                move |_, cx| {
+                    this.update(cx, |this, cx| this.open(node.clone(), cx)).ok();
+                }
+            })
+            .when_some(board_rig, |menu, rig| {
+                let this = this.clone();
+                menu.entry("Open Board", None, move |_, cx| {
+                    this.update(cx, |_, cx| cx.emit(TownTreeEvent::OpenBoard(rig.clone())))
+                        .ok();
+                })
+            })
+            .when_some(mailbox, |menu, address| {
+                let this = this.clone();
+                menu.entry("Open Mail", None, move |_, cx| {
+                    this.update(cx, |_, cx| {
+                        cx.emit(TownTreeEvent::OpenMail(address.clone()))
+                    })
+                    .ok();
+                })
+            })
+            .when_some(polecat, |menu, agent| {
+                let this = this.clone();
+                menu.entry("Watch Output", None, move |_, cx| {
+                    this.update(cx, |_, cx| {
+                        cx.emit(TownTreeEvent::WatchOutput(agent.clone()))
+                    })
+                    .ok();
+                })
+            })
+            .when_some(attach, |menu, agent| {
+                let this = this.clone();
+                menu.entry("Attach", None, move |_, cx| {
+                    this.update(cx, |_, cx| cx.emit(TownTreeEvent::Attach(agent.clone())))
+                        .ok();
+                })
+            })
+            .when(mayor_chat, |menu| {
+                let this = this.clone();
+                menu.entry("Talk to the Mayor", None, move |_, cx| {
+                    this.update(cx, |_, cx| cx.emit(TownTreeEvent::OpenMayorChat))
+                        .ok();
+                })
+            })
+            .when(node == TownNode::Town, |menu| {
+                let this = this.clone();
+                menu.entry("New Convoy…", None, move |_, cx| {
+                    this.update(cx, |_, cx| cx.emit(TownTreeEvent::NewConvoy))
+                        .ok();
+                })
+            })
+            .when_some(compose, |menu, (label, recipient)| {
+                let this = this.clone();
+                menu.entry(label, None, move |_, cx| {
+                    this.update(cx, |_, cx| {
+                        cx.emit(TownTreeEvent::Compose(recipient.clone()))
+                    })
+                    .ok();
+                })
+            })
+            .when(expandable, |menu| {
+                let this = this.clone();
+                let node = node.clone();
+                menu.entry(
+                    if expanded { "Collapse" } else { "Expand" },
+                    None,
+                    move |_, cx| {
+                        this.update(cx, |this, cx| this.toggle_expanded(&node, cx))
+                            .ok();
+                    },
+                )
+            })
+            .separator()
+            .when_some(address, |menu, address| {
+                menu.entry("Copy Address", None, move |_, cx| {
+                    cx.write_to_clipboard(ClipboardItem::new_string(address.clone()));
+                })
+            })
+            .when_some(path, |menu, path| {
+                menu.entry("Reveal in File Manager", None, move |_, cx| {
+                    cx.reveal_path(&path);
+                })
+            })
+            .separator()
+            .entry("Refresh Town", None, {
+                let this = this.clone();
+                move |_, cx| {
+                    this.update(cx, |_, cx| cx.emit(TownTreeEvent::Refresh))
+                        .ok();
+                }
+            })
+        });
+
+        window.focus(&context_menu.focus_handle(cx), cx);
+        let subscription = cx.subscribe(&context_menu, |this, _, _: &DismissEvent, cx| {
+            this.context_menu.take();
+            cx.notify();
+        });
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    /// Offers the runtimes to sling a dropped issue with
+    fn deploy_sling_menu(
+        &mut self,
+        position: Point<Pixels>,
+        node: TownNode,
+        issue: &DraggedIssue,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(target) = sling_target(&node) else {
+            return;
+        };
+        self.select(node.clone(), cx);
+
+        let this = cx.entity().downgrade();
+        let issue = issue.id.clone();
+        let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
+            let runtimes =
+                std::iter::once(None).chain(RUNTIMES.iter().map(|runtime| Some(*runtime)));
+            let mut menu = menu.header(format!("Sling {} to {}", issue, target));
+            for runtime in runtimes {
+                let this = this.clone();
+                let node = node.clone();
+                let request = SlingRequest {
+                    issue: issue.clone(),
+                    target: target.clone(),
+                    runtime: runtime.map(Into::into),
+                };
+                menu = menu.entry(runtime.unwrap_or("Default runtime"), None, move |_, cx| {
+                    this.update(cx, |this, cx| this.sling(node.clone(), request.clone(), cx))
+                        .ok();
+                });
+            }
+            menu
+        });
+
+        window.focus(&context_menu.focus_handle(cx), cx);
+        let subscription = cx.subscribe(&context_menu, |this, _, _: &DismissEvent, cx| {
+            this.context_menu.take();
+            cx.notify();
+        });
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    fn render_sling_status(
+        &self,
+        index: usize,
+        entry: &TreeEntry,
+        status: &SlingStatus,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let color = match status {
+            SlingStatus::Running(_) => colors.text_muted,
+            SlingStatus::Succeeded(_) => cx.theme().status().success,
+            SlingStatus::Failed(..) => cx.theme().status().error,
+        };
+        let node = entry.node.clone();
+
+        div()
+            .id(("town-tree-sling", index))
+            .pl(px(20.0 + entry.depth as f32 * 12.0))
+            .pr_2()
+            .text_xs()
+            .text_color(color)
+            .child(status.message())
+            .when(!matches!(status, SlingStatus::Running(_)), |this| {
+                this.cursor_pointer().on_click(
+                    cx.listener(move |this, _, _, cx| this.dismiss_sling_status(&node, cx)),
+                )
+            })
+    }
+
+    fn render_entry(
+        &self,
+        index: usize,
+        entry: &TreeEntry,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let status = cx.theme().status();
+        let is_selected = self.selected.as_ref() == Some(&entry.node);
+
+        let disclosure = match (entry.expandable, entry.expanded) {
+            (false, _) => " ",
+            (true, true) => "▾",
+            (true, false) => "▸",
+        };
+
+        let badge = entry.badge.as_ref().map(|badge| {
+            let (text, color) = match badge {
+                NodeBadge::Status(AgentStatus::Active) => ("●".to_string(), status.success),
+                NodeBadge::Status(AgentStatus::Idle) => ("○".to_string(), colors.text_muted),
+                NodeBadge::Status(AgentStatus::Error(_)) => ("✗".to_string(), status.error),
+                NodeBadge::Activity { active, total } => {
+                    let color = if *active > 0 {
+                        status.success
+                    } else {
+                        colors.text_muted
+                    };
+                    (format!("{}/{}", active, total), color)
+                }
+                NodeBadge::Count(count) => (count.to_string(), colors.text_muted),
+                NodeBadge::Orphaned => ("orphan".to_string(), status.warning),
+            };
+            div().text_xs().text_color(color).child(text)
+        });
+
+        let toggle_node = entry.node.clone();
+        let open_node = entry.node.clone();
+        let menu_node = entry.node.clone();
+        let drop_node = entry.node.clone();
+        let accepts_issues = sling_target(&entry.node).is_some();
+
+        div()
+            .id(("town-tree-entry", index))
+            .flex()
+            .items_center()
+            .gap_1()
+            .h_6()
+            .pl(px(8.0 + entry.depth as f32 * 12.0))
+            .pr_2()
+            .text_sm()
+            .text_color(colors.text)
+            .cursor_pointer()
+            .when(is_selected, |row| row.bg(colors.element_selected))
+            .hover(|style| style.bg(colors.element_hover))
+            .child(
+                div()
+                    .id(("town-tree-disclosure", index))
+                    .w_3()
+                    .text_color(colors.text_muted)
+                    .child(disclosure)
+                    .when(entry.expandable, |disclosure| {
+                        disclosure.on_click(cx.listener(move |this, _, _, cx| {
+                            cx.stop_propagation();
+                            this.toggle_expanded(&toggle_node, cx);
+                        }))
+                    }),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .child(entry.label.clone()),
+            )
+            .children(badge)
+            .on_click(cx.listener(move |this, _, window, cx| {
+                window.focus(&this.focus_handle, cx);
+                this.open(open_node.clone(), cx);
+            }))
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                    cx.stop_propagation();
+                    this.deploy_context_menu(event.position, menu_node.clone(), window, cx);
+                }),
+            )
+            .when(accepts_issues, |row| {
+                row.drag_over::<DraggedIssue>(|style, _, _, cx| {
+                    style.bg(cx.theme().colors().drop_target_background)
+                })
+                .on_drop(cx.listener(
+                    move |this, issue: &DraggedIssue, window, cx| {
+                        let position = window.mouse_position();
+                        this.deploy_sling_menu(position, drop_node.clone(), issue, window, cx);
+                    },
+                ))
+            })
+    }
+}
+
+impl Focusable for TownTree {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TownTree {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut rows = Vec::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            rows.push(self.render_entry(index, entry, cx).into_any_element());
+            if let Some(status) = self.slings.get(&entry.node) {
+                rows.push(
+                    self.render_sling_status(index, entry, status, cx)
+                        .into_any_element(),
+                );
+            }
+        }
+
+        div()
+            .id("town-tree")
+            .key_context(KEY_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::expand_selected))
+            .on_action(cx.listener(Self::collapse_selected))
+            .on_action(cx.listener(Self::open_selected))
+            .flex()
+            .flex_col()
+            .size_full()
+            .py_1()
+            .overflow_y_scroll()
+            .children(rows)
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(gpui::Corner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
+    }
+}
+
+/// Flattens the model into the visible rows of the tree.
+///
+/// Town-level agents come first (the Mayor ahead of the rest), then each rig with
+/// its own agents followed by its non-empty Crew, Hooks and Polecats groups.
+pub fn build_entries(
+    model: &TownModel,
+    town_name: &str,
+    collapsed: &HashSet<TownNode>,
+) -> Vec<TreeEntry> {
+    let mut entries = Vec::new();
+
+    let agents: Vec<&Agent> = model.agents().collect();
+    let town_expanded = !collapsed.contains(&TownNode::Town);
+    entries.push(TreeEntry {
+        node: TownNode::Town,
+        depth: 0,
+        label: town_name.to_string().into(),
+        badge: Some(activity_badge(&agents)),
+        expandable: true,
+        expanded: town_expanded,
+    });
+    if !town_expanded {
+        return entries;
+    }
+
+    let mut town_agents: Vec<&Agent> = agents
+        .iter()
+        .copied()
+        .filter(|agent| agent.rig.is_none())
+        .collect();
+    town_agents.sort_by_key(|agent| agent.role != AgentRole::Mayor);
+    for agent in town_agents {
+        entries.push(agent_entry(agent, agent.role.to_string(), 1));
+    }
+
+    for rig in model.rigs() {
+        let rig_node = TownNode::Rig(rig.name.clone());
+        let rig_agents: Vec<&Agent> = model.agents_in_rig(&rig.name).collect();
+        let rig_expanded = !collapsed.contains(&rig_node);
+        entries.push(TreeEntry {
+            node: rig_node,
+            depth: 1,
+            label: rig.name.clone().into(),
+            badge: Some(activity_badge(&rig_agents)),
+            expandable: true,
+            expanded: rig_expanded,
+        });
+        if !rig_expanded {
+            continue;
+        }
+
+        for agent in rig_agents
+            .iter()
+            .filter(|agent| !matches!(agent.role, AgentRole::Crew | AgentRole::Polecat))
+        {
+            entries.push(agent_entry(agent, last_segment(&agent.name), 2));
+        }
+
+        for group in [
+            RigGroup::Crew,
+            RigGroup::Hooks,
+            RigGroup::Polecats,
+            RigGroup::Formulas,
+        ] {
+            let children: Vec<TreeEntry> = match group {
+                RigGroup::Crew | RigGroup::Polecats => {
+                    let role = if group == RigGroup::Crew {
+                        AgentRole::Crew
+                    } else {
+                        AgentRole::Polecat
+                    };
+                    rig_agents
+                        .iter()
+                        .filter(|agent| agent.role == role)
+                        .map(|agent| agent_entry(agent, last_segment(&agent.name), 3))
+                        .collect()
+                }
+                RigGroup::Hooks => model
+                    .hooks_in_rig(&rig.name)
+                    .map(|hook| TreeEntry {
+                        node: TownNode::Hook(hook.id()),
+                        depth: 3,
+                        label: hook.name.clone().into(),
+                        badge: Some(match &hook.agent {
+                            Some(agent) => {
+                                model.agent(agent).map_or(NodeBadge::Orphaned, |agent| {
+                                    NodeBadge::Status(agent.status.clone())
+                                })
+                            }
+                            None => NodeBadge::Orphaned,
+                        }),
+                        expandable: false,
+                        expanded: false,
+                    })
+                    .collect(),
+                RigGroup::Formulas => model
+                    .formulas_in_rig(&rig.name)
+                    .map(|formula| TreeEntry {
+                        node: TownNode::Formula(formula.id()),
+                        depth: 3,
+                        label: formula.name.clone().into(),
+                        badge: None,
+                        expandable: false,
+                        expanded: false,
+                    })
+                    .collect(),
+            };
+            if children.is_empty() {
+                continue;
+            }
+
+            let group_node = TownNode::Group {
+                rig: rig.name.clone(),
+                group,
+            };
+            let group_expanded = !collapsed.contains(&group_node);
+            entries.push(TreeEntry {
+                node: group_node,
+                depth: 2,
+                label: group.label().into(),
+                badge: Some(NodeBadge::Count(children.len())),
+                expandable: true,
+                expanded: group_expanded,
+            });
+            if group_expanded {
+                entries.extend(children);
+            }
+        }
+    }
+
+    entries
+}
+
+/// Where `gt sling` sends issues dropped on a node: a rig, or an agent by address
+pub fn sling_target(node: &TownNode) -> Option<String> {
+    match node {
+        TownNode::Rig(rig) => Some(rig.clone()),
+        TownNode::Agent(address) => Some(address.clone()),
+        _ => None,
+    }
+}
+
+/// The directory behind a node, if it has one
+pub fn node_path(model: &TownModel, node: &TownNode) -> Option<PathBuf> {
+    match node {
+        TownNode::Town => None,
+        TownNode::Rig(name) => model.rig(name).map(|rig| rig.path.clone()),
+        TownNode::Group { rig, group } => model.rig(rig).map(|rig| match group {
+            RigGroup::Crew => rig.path.join("crew"),
+            RigGroup::Hooks => rig.path.join("hooks"),
+            RigGroup::Polecats => rig.path.join("polecats"),
+            RigGroup::Formulas => rig.path.join(".beads").join(formula::FORMULAS_DIR),
+        }),
+        TownNode::Agent(name) => model.agent(name).and_then(|agent| agent.path.clone()),
+        TownNode::Hook(id) => model.hook(id).map(|hook| hook.path.clone()),
+        TownNode::Formula(id) => model.formula(id).map(|formula| formula.path.clone()),
+    }
+}
+
+fn agent_entry(agent: &Agent, label: impl Into<SharedString>, depth: usize) -> TreeEntry {
+    TreeEntry {
+        node: TownNode::Agent(agent.name.clone()),
+        depth,
+        label: label.into(),
+        badge: Some(NodeBadge::Status(agent.status.clone())),
+        expandable: false,
+        expanded: false,
+    }
+}
+
+fn activity_badge(agents: &[&Agent]) -> NodeBadge {
+    NodeBadge::Activity {
+        active: agents
+            .iter()
+            .filter(|agent| agent.status == AgentStatus::Active)
+            .count(),
+        total: agents.len(),
+    }
+}
+
+fn last_segment(address: &str) -> String {
+    address.rsplit('/').next().unwrap_or(address).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_discovery::AgentDirectory;
+    use crate::dashboard_buffer::{AgentInfo, DashboardData};
+    use crate::town_model::{Hook, Rig, TownStructure};
+    use gpui::{AppContext as _, TestAppContext, VisualTestContext};
+
+    fn test_model(cx: &mut TestAppContext) -> Entity<TownModel> {
+        let structure = TownStructure {
+            rigs: vec![
+                Rig {
+                    name: "backend".into(),
+                    path: "/gt/backend".into(),
+                },
+                Rig {
+                    name: "frontend".into(),
+                    path: "/gt/frontend".into(),
+                },
+            ],
+            agents: vec![
+                AgentDirectory::from_path("/gt/deacon".into()).unwrap(),
+                AgentDirectory::from_path("/gt/mayor".into()).unwrap(),
+                AgentDirectory::from_path("/gt/backend/.agents/witness-backend".into()).unwrap(),
+                AgentDirectory::from_rig_member("/gt/frontend/crew/alice".into()).unwrap(),
+                AgentDirectory::from_rig_member("/gt/frontend/polecats/toast".into()).unwrap(),
+            ],
+            hooks: vec![
+                Hook {
+                    name: "toast".into(),
+                    rig: "frontend".into(),
+                    path: "/gt/frontend/hooks/toast".into(),
+                    agent: None,
+                },
+                Hook {
+                    name: "furiosa".into(),
+                    rig: "frontend".into(),
+                    path: "/gt/frontend/hooks/furiosa".into(),
+                    agent: None,
+                },
+            ],
+            ..Default::default()
+        };
+        let data = DashboardData {
+            agents: vec![AgentInfo {
+                name: "frontend/polecats/toast".into(),
+                status: AgentStatus::Active,
+                token_usage: None,
+                context_fill: None,
+            }],
+            ..Default::default()
+        };
+
+        cx.new(|cx| {
+            let mut model = TownModel::new();
+            model.apply_structure(structure, cx);
+            model.apply_dashboard_data(data, cx);
+            model
+        })
+    }
+
+    fn labels(tree: &TownTree) -> Vec<String> {
+        tree.entries()
+            .iter()
+            .map(|entry| format!("{}{}", "  ".repeat(entry.depth), entry.label))
+            .collect()
+    }
+
+    #[gpui::test]
+    fn test_tree_hierarchy(cx: &mut TestAppContext) {
+        let model = test_model(cx);
+        let tree = cx.new(|cx| TownTree::new(model, "gt", cx));
+
+        tree.read_with(cx, |tree, _| {
+            assert_eq!(
+                labels(tree),
+                vec![
+                    "gt",
+                    "  Mayor",
+                    "  Deacon",
+                    "  backend",
+                    "    witness-backend",
+                    "  frontend",
+                    "    Crew",
+                    "      alice",
+                    "    Hooks",
+                    "      furiosa",
+                    "      toast",
+                    "    Polecats",
+                    "      toast",
+                ]
+            );
+
+            let badge = |node: TownNode| {
+                tree.entries()
+                    .iter()
+                    .find(|entry| entry.node == node)
+                    .and_then(|entry| entry.badge.clone())
+            };
+            assert_eq!(
+                badge(TownNode::Town),
+                Some(NodeBadge::Activity {
+                    active: 1,
+                    total: 5
+                })
+            );
+            assert_eq!(
+                badge(TownNode::Hook("frontend/toast".into())),
+                Some(NodeBadge::Status(AgentStatus::Active))
+            );
+            assert_eq!(
+                badge(TownNode::Hook("frontend/furiosa".into())),
+                Some(NodeBadge::Orphaned)
+            );
+            assert_eq!(
+                badge(TownNode::Group {
+                    rig: "frontend".into(),
+                    group: RigGroup::Hooks
+                }),
+                Some(NodeBadge::Count(2))
+            );
+        });
+    }
+
+    #[gpui::test]
+    fn test_collapse_hides_children(cx: &mut TestAppContext) {
+        let model = test_model(cx);
+        let tree = cx.new(|cx| TownTree::new(model, "gt", cx));
+
+        tree.update(cx, |tree, cx| {
+            tree.set_expanded(&TownNode::Rig("frontend".into()), false, cx)
+        });
+        tree.read_with(cx, |tree, _| {
+            assert_eq!(
+                labels(tree),
+                vec![
+                    "gt",
+                    "  Mayor",
+                    "  Deacon",
+                    "  backend",
+                    "    witness-backend",
+                    "  frontend"
+                ]
+            );
+        });
+
+        tree.update(cx, |tree, cx| tree.toggle_expanded(&TownNode::Town, cx));
+        tree.read_with(cx, |tree, _| assert_eq!(labels(tree), vec!["gt"]));
+    }
+
+    #[gpui::test]
+    fn test_tree_updates_with_model(cx: &mut TestAppContext) {
+        let model = test_model(cx);
+        let tree = cx.new(|cx| TownTree::new(model.clone(), "gt", cx));
+
+        model.update(cx, |model, cx| {
+            model.apply_dashboard_data(DashboardData::default(), cx)
+        });
+        tree.read_with(cx, |tree, _| {
+            assert_eq!(
+                tree.entries()[0].badge,
+                Some(NodeBadge::Activity {
+                    active: 0,
+                    total: 5
+                })
+            );
+        });
+    }
+
+    #[gpui::test]
+    fn test_keyboard_navigation(cx: &mut TestAppContext) {
+        cx.update(init);
+        let model = test_model(cx);
+        let (tree, cx) = cx.add_window_view(|_, cx| TownTree::new(model, "gt", cx));
+        cx.focus(&tree);
+
+        let opened = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        cx.update(|_, cx| {
+            let opened = opened.clone();
+            cx.subscribe(&tree, move |_, event: &TownTreeEvent, _| {
+                if let TownTreeEvent::Open(node) = event {
+                    opened.borrow_mut().push(node.clone());
+                }
+            })
+            .detach();
+        });
+
+        cx.simulate_keystrokes("down");
+        assert_selected(&tree, cx, TownNode::Town);
+        cx.simulate_keystrokes("down down down");
+        assert_selected(&tree, cx, TownNode::Rig("backend".into()));
+
+        cx.simulate_keystrokes("left");
+        cx.simulate_keystrokes("down");
+        assert_selected(&tree, cx, TownNode::Rig("frontend".into()));
+
+        cx.simulate_keystrokes("right");
+        assert_selected(
+            &tree,
+            cx,
+            TownNode::Group {
+                rig: "frontend".into(),
+                group: RigGroup::Crew,
+            },
+        );
+        cx.simulate_keystrokes("left");
+        cx.simulate_keystrokes("left");
+        assert_selected(&tree, cx, TownNode::Rig("frontend".into()));
+
+        cx.simulate_keystrokes("up");
+        cx.simulate_keystrokes("enter");
+        assert_eq!(*opened.borrow(), vec![TownNode::Rig("backend".into())]);
+    }
+
+    #[gpui::test]
+    fn test_sling_tracks_status(cx: &mut TestAppContext) {
+        let model = test_model(cx);
+        let tree = cx.new(|cx| TownTree::new(model, "gt", cx));
+        let slung = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let slung = slung.clone();
+            cx.subscribe(&tree, move |_, event: &TownTreeEvent, _| {
+                if let TownTreeEvent::Sling { node, request } = event {
+                    slung.borrow_mut().push((node.clone(), request.args()));
+                }
+            })
+            .detach();
+        });
+
+        let node = TownNode::Rig("frontend".into());
+        assert_eq!(sling_target(&node).as_deref(), Some("frontend"));
+        assert_eq!(sling_target(&TownNode::Town), None);
+
+        let request = SlingRequest {
+            issue: "fe-12".into(),
+            target: "frontend".into(),
+            runtime: Some("codex".into()),
+        };
+        tree.update(cx, |tree, cx| tree.sling(node.clone(), request.clone(), cx));
+        assert_eq!(
+            *slung.borrow(),
+            vec![(
+                node.clone(),
+                vec!["sling", "fe-12", "frontend", "--agent", "codex"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            )]
+        );
+
+        tree.update(cx, |tree, cx| {
+            assert_eq!(
+                tree.sling_status(&node),
+                Some(&SlingStatus::Running(request.clone()))
+            );
+            tree.set_sling_status(
+                node.clone(),
+                SlingStatus::Failed(request, "no polecats available".into()),
+                cx,
+            );
+            assert_eq!(
+                tree.sling_status(&node).unwrap().message(),
+                "Couldn't sling fe-12: no polecats available"
+            );
+            tree.dismiss_sling_status(&node, cx);
+            assert_eq!(tree.sling_status(&node), None);
+        });
+    }
+
+    fn assert_selected(tree: &Entity<TownTree>, cx: &mut VisualTestContext, node: TownNode) {
+        tree.read_with(cx, |tree, _| assert_eq!(tree.selected(), Some(&node)));
+    }
+}
@@ -0,0 +1,315 @@
+use chrono::Local;
+use gpui::{Context, Entity, EventEmitter, Subscription};
+use std::io::Write;
+
+use crate::dashboard_buffer::{
+    ConnectionStatus, DashboardData, DashboardError, DashboardEvent, DashboardFormatter,
+    DashboardView, ansi,
+};
+
+/// Clears the terminal and moves the cursor to the top left
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Refreshes in a row that must find the town unavailable before the watch gives
+/// up, so a single network blip doesn't end it
+pub const UNAVAILABLE_REFRESH_LIMIT: usize = 3;
+
+pub enum DashboardWatchEvent {
+    /// The town can't be read anymore; the message says why
+    Unavailable(String),
+}
+
+/// Redraws a dashboard's text output in a plain terminal whenever it refreshes, for
+/// `belvedere watch`.
+///
+/// The dashboard decides when to refresh, on its interval or on file changes, so
+/// the watch only draws. It emits `Unavailable` once `UNAVAILABLE_REFRESH_LIMIT`
+/// refreshes in a row found the town gone.
+pub struct DashboardWatch {
+    dashboard: Entity<DashboardView>,
+    color: bool,
+    output: Box<dyn Write>,
+    /// Refreshes in a row that found the town unavailable
+    unavailable_refreshes: usize,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<DashboardWatchEvent> for DashboardWatch {}
+
+impl DashboardWatch {
+    pub fn new(
+        dashboard: Entity<DashboardView>,
+        color: bool,
+        output: Box<dyn Write>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&dashboard, |this, _, event: &DashboardEvent, cx| {
+            if let DashboardEvent::DataRefreshed = event {
+                this.redraw(cx);
+            }
+        });
+
+        Self {
+            dashboard,
+            color,
+            output,
+            unavailable_refreshes: 0,
+            _subscription: subscription,
+        }
+    }
+
+    pub fn dashboard(&self) -> &Entity<DashboardView> {
+        &self.dashboard
+    }
+
+    fn redraw(&mut self, cx: &mut Context<Self>) {
+        let dashboard = self.dashboard.read(cx);
+        let mut frame = render_frame(dashboard.data(), dashboard.error(), self.color);
+        match unavailable(dashboard) {
+            Some(message) => {
+                self.unavailable_refreshes += 1;
+                if self.unavailable_refreshes >= UNAVAILABLE_REFRESH_LIMIT {
+                    cx.emit(DashboardWatchEvent::Unavailable(message));
+                    return;
+                }
+                frame.push_str(&format!(
+                    "\nTown unavailable ({}/{} refreshes before giving up)\n",
+                    self.unavailable_refreshes, UNAVAILABLE_REFRESH_LIMIT
+                ));
+            }
+            None => {
+                self.unavailable_refreshes = 0;
+                frame.push_str(&format!("\nUpdated {}\n", Local::now().format("%H:%M:%S")));
+            }
+        }
+        let written =
+            write!(self.output, "{}{}", CLEAR_SCREEN, frame).and_then(|_| self.output.flush());
+        if let Err(err) = written {
+            log::error!("failed to draw dashboard: {}", err);
+        }
+    }
+}
+
+/// Why the dashboard's town is unavailable, or `None` while it can be read.
+///
+/// A failed fetch counts too: a `gt` that runs but fails, or a server that times
+/// out or errors, is as much an outage as one that can't be reached.
+pub fn unavailable(dashboard: &DashboardView) -> Option<String> {
+    match dashboard.error() {
+        Some(error @ DashboardError::FetchFailed(_)) => {
+            Some(DashboardFormatter::format_error(error))
+        }
+        Some(DashboardError::NotAvailable) => Some(DashboardFormatter::format_error(
+            &DashboardError::NotAvailable,
+        )),
+        _ if dashboard.connection_status() == &ConnectionStatus::Disconnected => Some(
+            DashboardFormatter::format_error(&DashboardError::NotAvailable),
+        ),
+        _ => None,
+    }
+}
+
+/// The text drawn for the latest refresh. Data that failed to refresh stays on
+/// screen with the error below it.
+pub fn render_frame(
+    data: Option<&DashboardData>,
+    error: Option<&DashboardError>,
+    color: bool,
+) -> String {
+    let mut frame = match data {
+        Some(data) if color => DashboardFormatter::format_ansi(data),
+        Some(data) => DashboardFormatter::format(data),
+        None => String::new(),
+    };
+    if let Some(error) = error {
+        let message = if data.is_some() {
+            format!("\n{}\n", error)
+        } else {
+            format!("{}\n", DashboardFormatter::format_error(error))
+        };
+        if color {
+            frame.push_str(&format!("{}{}{}", ansi::RED, message, ansi::RESET));
+        } else {
+            frame.push_str(&message);
+        }
+    }
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard_buffer::{AgentInfo, AgentStatus};
+    use crate::dashboard_buffer_tests::ReactiveMockDataSource;
+    use gpui::{AppContext as _, TestAppContext};
+    use std::sync::{Arc, Mutex};
+
+    /// Collects what the watch draws
+    #[derive(Clone, Default)]
+    struct Screen(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Screen {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Screen {
+        fn frames(&self) -> Vec<String> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .split(CLEAR_SCREEN)
+                .skip(1)
+                .map(str::to_string)
+                .collect()
+        }
+    }
+
+    fn agent(name: &str, status: AgentStatus) -> AgentInfo {
+        AgentInfo {
+            name: name.into(),
+            status,
+            token_usage: None,
+            context_fill: None,
+        }
+    }
+
+    fn data(agents: Vec<AgentInfo>) -> DashboardData {
+        DashboardData {
+            agents,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_frame_colors_status_icons() {
+        let data = data(vec![
+            agent("active", AgentStatus::Active),
+            agent("idle", AgentStatus::Idle),
+            agent("stuck", AgentStatus::Error("stuck".into())),
+        ]);
+
+        let frame = render_frame(Some(&data), None, true);
+        assert!(frame.contains("\x1b[32m●\x1b[0m active"));
+        assert!(frame.contains("\x1b[2m○\x1b[0m idle"));
+        assert!(frame.contains("\x1b[31m✗\x1b[0m stuck"));
+
+        let frame = render_frame(Some(&data), None, false);
+        assert_eq!(frame, DashboardFormatter::format(&data));
+        assert!(!frame.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_render_frame_keeps_stale_data_with_error() {
+        let data = data(vec![agent("mayor", AgentStatus::Active)]);
+        let error = DashboardError::FetchFailed("gt timed out".into());
+
+        let frame = render_frame(Some(&data), Some(&error), false);
+        assert!(frame.starts_with(&DashboardFormatter::format(&data)));
+        assert!(frame.ends_with("\nFetch failed: gt timed out\n"));
+
+        let frame = render_frame(None, Some(&error), false);
+        assert!(frame.starts_with("Failed to load dashboard"));
+    }
+
+    #[gpui::test]
+    async fn test_watch_redraws_until_town_is_unavailable(cx: &mut TestAppContext) {
+        let source = Arc::new(ReactiveMockDataSource::new(data(vec![agent(
+            "mayor",
+            AgentStatus::Active,
+        )])));
+        let dashboard = cx.new(|cx| DashboardView::new(source.clone(), cx));
+        let screen = Screen::default();
+        let watch = cx
+            .new(|cx| DashboardWatch::new(dashboard.clone(), false, Box::new(screen.clone()), cx));
+        let unavailable = Arc::new(Mutex::new(Vec::new()));
+        cx.update(|cx| {
+            let unavailable = unavailable.clone();
+            cx.subscribe(&watch, move |_, event: &DashboardWatchEvent, _| {
+                let DashboardWatchEvent::Unavailable(message) = event;
+                unavailable.lock().unwrap().push(message.clone());
+            })
+            .detach();
+        });
+        cx.run_until_parked();
+
+        source.update_data(data(vec![agent("mayor", AgentStatus::Idle)]));
+        dashboard.update(cx, |dashboard, cx| dashboard.refresh(cx));
+        cx.run_until_parked();
+
+        let frames = screen.frames();
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].contains("● mayor"));
+        assert!(frames[1].contains("○ mayor"));
+        assert!(frames[1].contains("Updated "));
+        assert!(unavailable.lock().unwrap().is_empty());
+
+        // One failed refresh is a blip
+        source.set_unavailable();
+        dashboard.update(cx, |dashboard, cx| dashboard.refresh(cx));
+        cx.run_until_parked();
+        source.set_available();
+        dashboard.update(cx, |dashboard, cx| dashboard.refresh(cx));
+        cx.run_until_parked();
+
+        let frames = screen.frames();
+        assert_eq!(frames.len(), 4);
+        assert!(frames[2].contains("Town unavailable (1/3"));
+        assert!(frames[3].contains("Updated "));
+        assert!(unavailable.lock().unwrap().is_empty());
+
+        source.set_unavailable();
+        for _ in 0..UNAVAILABLE_REFRESH_LIMIT {
+            dashboard.update(cx, |dashboard, cx| dashboard.refresh(cx));
+            cx.run_until_parked();
+        }
+
+        assert_eq!(screen.frames().len(), 4 + UNAVAILABLE_REFRESH_LIMIT - 1);
+        assert_eq!(
+            *unavailable.lock().unwrap(),
+            vec![DashboardFormatter::format_error(
+                &DashboardError::NotAvailable
+            )]
+        );
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_watch_gives_up_when_gt_keeps_failing(cx: &mut TestAppContext) {
+        use crate::fake_gt::FakeGt;
+        use crate::gt_data_source::GtDataSource;
+
+        let gt = FakeGt::new().fail("agents --json", 1, "gt: no town at ~/gt");
+        let source = Arc::new(GtDataSource::new(gt.cli()));
+        let dashboard = cx.new(|cx| DashboardView::new(source, cx));
+        let screen = Screen::default();
+        let watch = cx
+            .new(|cx| DashboardWatch::new(dashboard.clone(), false, Box::new(screen.clone()), cx));
+        let unavailable = Arc::new(Mutex::new(Vec::new()));
+        cx.update(|cx| {
+            let unavailable = unavailable.clone();
+            cx.subscribe(&watch, move |_, event: &DashboardWatchEvent, _| {
+                let DashboardWatchEvent::Unavailable(message) = event;
+                unavailable.lock().unwrap().push(message.clone());
+            })
+            .detach();
+        });
+        cx.run_until_parked();
+        for _ in 1..UNAVAILABLE_REFRESH_LIMIT {
+            dashboard.update(cx, |dashboard, cx| dashboard.refresh(cx));
+            cx.run_until_parked();
+        }
+
+        let frames = screen.frames();
+        assert_eq!(frames.len(), UNAVAILABLE_REFRESH_LIMIT - 1);
+        assert!(frames[0].contains("Town unavailable (1/3"));
+        let messages = unavailable.lock().unwrap().clone();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("gt: no town at ~/gt"));
+    }
+}
@@ -162,7 +162,8 @@ impl FileDiffView {
     }
 }
 
-async fn build_buffer_diff(
+/// Diffs `new_buffer` against the text of `old_buffer`, for [`FileDiffView::new`]
+pub async fn build_buffer_diff(
     old_buffer: &Entity<Buffer>,
     new_buffer: &Entity<Buffer>,
     language_registry: Arc<LanguageRegistry>,